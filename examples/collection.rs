@@ -15,8 +15,8 @@ async fn main() -> Result<(), Error> {
 
     let conn = Connection::establish_jwt(URL, "username", "password").await?;
     let database = conn.db("test_db").await?;
-    let coll = database.drop_collection(collection_name).await;
-    println!("Should fail: {:?}", coll);
+    let existed = database.drop_collection(collection_name).await?;
+    println!("Should not exist: {:?}", existed);
 
     let coll = database.create_collection(collection_name).await;
     println!("{:?}", coll);
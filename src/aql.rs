@@ -9,9 +9,13 @@
 /// 1. perform AQL query via `database.aql_query`.
 use std::collections::HashMap;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 use serde_json::value::Value;
 use typed_builder::TypedBuilder;
+use uclient::ClientExt;
+use url::Url;
+
+use crate::{database::DefaultOptions, ClientError, Collection};
 
 #[derive(Debug, Serialize, TypedBuilder)]
 #[builder(
@@ -19,9 +23,10 @@ use typed_builder::TypedBuilder;
     builder_method_doc = r#"Create a builder for building `AqlQuery`.
 
 On the builder, call `.query(...)`, `.bind_vars(...)(optional)`, `.bind_var(...)(optional)`,
-`.try_bind(...)(optional)`, `.count(...)(optional)`, `.batch_size(...)(optional)`,
-`.cache(...)(optional)`, `.memory_limit(...)(optional)`, `.ttl(...)(optional)`,
-`.options(...)(optional)` to set the values of the fields (they accept Into values).
+`.try_bind(...)(optional)`, `.bind_collection(...)(optional)`,
+`.bind_collections(...)(optional)`, `.count(...)(optional)`,
+`.batch_size(...)(optional)`, `.cache(...)(optional)`, `.memory_limit(...)(optional)`,
+`.ttl(...)(optional)`, `.options(...)(optional)` to set the values of the fields (they accept Into values).
 
 Use `.try_bind(...)` to accept any serializable struct
 while `.bind_value(...)` accepts an `Into<serde_json::Value>`.
@@ -34,9 +39,9 @@ pub struct AqlQuery<'a> {
     query: &'a str,
 
     /// bind parameters to substitute in query string
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
-    #[builder(default)]
-    bind_vars: HashMap<&'a str, Value>,
+    #[serde(skip_serializing_if = "BindVars::is_empty")]
+    #[builder(default, setter(into))]
+    bind_vars: BindVars<'a>,
 
     /// Indicates whether the number of documents in the result set should be
     /// returned in the "count" attribute of the result.
@@ -72,8 +77,9 @@ pub struct AqlQuery<'a> {
     /// The maximum number of memory (measured in bytes) that the query is
     /// allowed to use.
     ///
-    /// If set, then the query will fail with error 'resource
-    /// limit exceeded' in case it allocates too much memory.
+    /// If set, then the query will fail with
+    /// `ClientError::QueryMemoryLimitExceeded` in case it allocates too much
+    /// memory.
     ///
     /// A value of 0 indicates that there is no memory limit.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -86,7 +92,12 @@ pub struct AqlQuery<'a> {
     /// the specified amount of time. This is useful to ensure garbage
     /// collection of cursors that are not fully fetched by clients.
     ///
-    /// If not set, a server-defined value will be used.
+    /// The time-to-live is renewed whenever a batch is fetched. Once it
+    /// expired, fetching the next batch fails with
+    /// `ClientError::CursorExpired`, hence set a generous value when batches
+    /// are fetched at the pace of a user, e.g. to paginate.
+    ///
+    /// If not set, a server-defined value will be used (30 seconds).
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     ttl: Option<u32>,
@@ -97,6 +108,50 @@ pub struct AqlQuery<'a> {
     options: Option<AqlOptions>,
 }
 
+/// Bind parameters of an `AqlQuery`, created from a map of their values.
+///
+/// Besides the values, it keeps the databases of the collections bound with
+/// `bind_collection(s)`, so that a query run on another database is rejected
+/// instead of reaching a collection of the same name there.
+#[derive(Debug, Default)]
+pub struct BindVars<'a> {
+    vars: HashMap<&'a str, Value>,
+    databases: Vec<Url>,
+}
+
+impl<'a> BindVars<'a> {
+    fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+}
+
+impl<'a> From<HashMap<&'a str, Value>> for BindVars<'a> {
+    fn from(vars: HashMap<&'a str, Value>) -> Self {
+        BindVars {
+            vars,
+            databases: Vec::new(),
+        }
+    }
+}
+
+impl Serialize for BindVars<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.vars.serialize(serializer)
+    }
+}
+
+impl<'a> AqlQuery<'a> {
+    /// Fill the options left unset with `defaults`.
+    pub(crate) fn apply_defaults(&mut self, defaults: &DefaultOptions) {
+        if self.batch_size.is_none() {
+            self.batch_size = defaults.batch_size;
+        }
+    }
+}
+
 // when binding the first query variable
 #[allow(non_camel_case_types, missing_docs)]
 impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __options>
@@ -123,7 +178,7 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
         'a,
         (
             __query,
-            (HashMap<&'a str, Value>,),
+            (BindVars<'a>,),
             __count,
             __batch_size,
             __cache,
@@ -136,8 +191,8 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
         K: Into<&'a str>,
         V: Into<Value>,
     {
-        let mut bind_vars = HashMap::new();
-        bind_vars.insert(key.into(), value.into());
+        let mut bind_vars = BindVars::default();
+        bind_vars.vars.insert(key.into(), value.into());
         let (query, _, count, batch_size, cache, memory_limit, ttl, options) = self.fields;
         AqlQueryBuilder {
             fields: (
@@ -164,7 +219,7 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
             'a,
             (
                 __query,
-                (HashMap<&'a str, Value>,),
+                (BindVars<'a>,),
                 __count,
                 __batch_size,
                 __cache,
@@ -181,6 +236,102 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
     {
         Ok(self.bind_var(key, serde_json::to_value(value)?))
     }
+
+    /// Bind the name of `collection` to the collection bind parameter `key`.
+    ///
+    /// `key` is the bind parameter as written in the query, i.e. with the
+    /// `@@` prefix of collection bind parameters, e.g. `@@users` for
+    /// `FOR u IN @@users RETURN u`. Binding collections this way avoids
+    /// interpolating collection names into the query string.
+    ///
+    /// The query must be executed on the database of `collection`, running it
+    /// on another database fails with `ClientError::InvalidQuery`.
+    ///
+    /// # Errors
+    /// `ClientError::InvalidQuery` if `key` does not start with `@@`.
+    #[allow(clippy::type_complexity)]
+    pub fn bind_collection<C: ClientExt>(
+        self,
+        key: &'a str,
+        collection: &Collection<C>,
+    ) -> Result<
+        AqlQueryBuilder<
+            'a,
+            (
+                __query,
+                (BindVars<'a>,),
+                __count,
+                __batch_size,
+                __cache,
+                __memory_limit,
+                __ttl,
+                __options,
+            ),
+        >,
+        ClientError,
+    > {
+        if !key.starts_with("@@") {
+            return Err(ClientError::InvalidQuery(format!(
+                "collection bind parameter should start with `@@`: {}",
+                key
+            )));
+        }
+        // the bind variable of `@@name` is named `@name`
+        let mut builder = self.bind_var(&key[1..], collection.name());
+        (builder.fields.1).0.databases.push(collection.db_url());
+        Ok(builder)
+    }
+
+    /// Bind the names of `collections`, as an array, to the bind parameter
+    /// `key`.
+    ///
+    /// Collection bind parameters (`@@`) only accept a single collection, so
+    /// bind each of them with `bind_collection`, e.g. for
+    /// `UNION(@@first, @@second)`. Arrays of names are accepted where a query
+    /// expects collection names as values, e.g. the `vertexCollections` option
+    /// of a traversal, hence `key` is a value bind parameter as written in the
+    /// query, e.g. `@vertices`.
+    ///
+    /// As with `bind_collection`, the query must be executed on the database
+    /// of `collections`.
+    ///
+    /// # Errors
+    /// `ClientError::InvalidQuery` if `key` does not start with a single `@`.
+    #[allow(clippy::type_complexity)]
+    pub fn bind_collections<C: ClientExt>(
+        self,
+        key: &'a str,
+        collections: &[&Collection<C>],
+    ) -> Result<
+        AqlQueryBuilder<
+            'a,
+            (
+                __query,
+                (BindVars<'a>,),
+                __count,
+                __batch_size,
+                __cache,
+                __memory_limit,
+                __ttl,
+                __options,
+            ),
+        >,
+        ClientError,
+    > {
+        if !key.starts_with('@') || key.starts_with("@@") {
+            return Err(ClientError::InvalidQuery(format!(
+                "bind parameter of collection names should start with a single `@`: {}",
+                key
+            )));
+        }
+        let names: Vec<&str> = collections.iter().map(|c| c.name()).collect();
+        let mut builder = self.bind_var(&key[1..], names);
+        (builder.fields.1)
+            .0
+            .databases
+            .extend(collections.iter().map(|c| c.db_url()));
+        Ok(builder)
+    }
 }
 
 // when bind_var(s) are not empty
@@ -190,7 +341,7 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
         'a,
         (
             __query,
-            (HashMap<&'a str, Value>,),
+            (BindVars<'a>,),
             __count,
             __batch_size,
             __cache,
@@ -209,7 +360,7 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
         'a,
         (
             __query,
-            (HashMap<&'a str, Value>,),
+            (BindVars<'a>,),
             __count,
             __batch_size,
             __cache,
@@ -222,7 +373,7 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
         K: Into<&'a str>,
         V: Into<Value>,
     {
-        (self.fields.1).0.insert(key.into(), value.into());
+        (self.fields.1).0.vars.insert(key.into(), value.into());
         self
     }
 
@@ -236,7 +387,7 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
             'a,
             (
                 __query,
-                (HashMap<&'a str, Value>,),
+                (BindVars<'a>,),
                 __count,
                 __batch_size,
                 __cache,
@@ -253,6 +404,102 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
     {
         Ok(self.bind_var(key, serde_json::to_value(value)?))
     }
+
+    /// Bind the name of `collection` to the collection bind parameter `key`.
+    ///
+    /// `key` is the bind parameter as written in the query, i.e. with the
+    /// `@@` prefix of collection bind parameters, e.g. `@@users` for
+    /// `FOR u IN @@users RETURN u`. Binding collections this way avoids
+    /// interpolating collection names into the query string.
+    ///
+    /// The query must be executed on the database of `collection`, running it
+    /// on another database fails with `ClientError::InvalidQuery`.
+    ///
+    /// # Errors
+    /// `ClientError::InvalidQuery` if `key` does not start with `@@`.
+    #[allow(clippy::type_complexity)]
+    pub fn bind_collection<C: ClientExt>(
+        self,
+        key: &'a str,
+        collection: &Collection<C>,
+    ) -> Result<
+        AqlQueryBuilder<
+            'a,
+            (
+                __query,
+                (BindVars<'a>,),
+                __count,
+                __batch_size,
+                __cache,
+                __memory_limit,
+                __ttl,
+                __options,
+            ),
+        >,
+        ClientError,
+    > {
+        if !key.starts_with("@@") {
+            return Err(ClientError::InvalidQuery(format!(
+                "collection bind parameter should start with `@@`: {}",
+                key
+            )));
+        }
+        // the bind variable of `@@name` is named `@name`
+        let mut builder = self.bind_var(&key[1..], collection.name());
+        (builder.fields.1).0.databases.push(collection.db_url());
+        Ok(builder)
+    }
+
+    /// Bind the names of `collections`, as an array, to the bind parameter
+    /// `key`.
+    ///
+    /// Collection bind parameters (`@@`) only accept a single collection, so
+    /// bind each of them with `bind_collection`, e.g. for
+    /// `UNION(@@first, @@second)`. Arrays of names are accepted where a query
+    /// expects collection names as values, e.g. the `vertexCollections` option
+    /// of a traversal, hence `key` is a value bind parameter as written in the
+    /// query, e.g. `@vertices`.
+    ///
+    /// As with `bind_collection`, the query must be executed on the database
+    /// of `collections`.
+    ///
+    /// # Errors
+    /// `ClientError::InvalidQuery` if `key` does not start with a single `@`.
+    #[allow(clippy::type_complexity)]
+    pub fn bind_collections<C: ClientExt>(
+        self,
+        key: &'a str,
+        collections: &[&Collection<C>],
+    ) -> Result<
+        AqlQueryBuilder<
+            'a,
+            (
+                __query,
+                (BindVars<'a>,),
+                __count,
+                __batch_size,
+                __cache,
+                __memory_limit,
+                __ttl,
+                __options,
+            ),
+        >,
+        ClientError,
+    > {
+        if !key.starts_with('@') || key.starts_with("@@") {
+            return Err(ClientError::InvalidQuery(format!(
+                "bind parameter of collection names should start with a single `@`: {}",
+                key
+            )));
+        }
+        let names: Vec<&str> = collections.iter().map(|c| c.name()).collect();
+        let mut builder = self.bind_var(&key[1..], names);
+        (builder.fields.1)
+            .0
+            .databases
+            .extend(collections.iter().map(|c| c.db_url()));
+        Ok(builder)
+    }
 }
 
 #[derive(Debug, Serialize, TypedBuilder, PartialEq)]
@@ -313,6 +560,18 @@ pub struct AqlOptions {
     #[builder(default, setter(strip_option))]
     max_plans: Option<u32>,
 
+    /// If set to true, the query is executed lazily: the server only computes
+    /// the next batch when it is fetched, instead of the whole result upfront.
+    ///
+    /// This saves memory on the server for large results, and work when the
+    /// client stops reading early. However, a streaming cursor holds on to
+    /// its resources, e.g. the locks of the collections it reads, until it is
+    /// exhausted or its `ttl` expires, hence it should be read until the end
+    /// promptly. `count` and `full_count` are not available with streaming.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    stream: Option<bool>,
+
     /// A list string indicating to-be-included or to-be-excluded optimizer
     /// rules can be put into this attribute, telling the optimizer to
     /// include or exclude specific rules.
@@ -460,8 +719,350 @@ pub struct Cursor<T> {
 pub struct QueryExtra {
     // TODO
     pub stats: Option<QueryStats>,
-    // TODO
-    pub warnings: Option<Vec<Value>>,
+    pub warnings: Option<Vec<AqlWarning>>,
+}
+
+/// A warning raised while running a query, e.g. a division by zero
+///
+/// Unless the query is run with `fail_on_warning`, warnings do not abort the
+/// query: the faulty expression evaluates to `null` instead.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct AqlWarning {
+    /// Error number of the warning.
+    pub code: u16,
+    pub message: String,
+}
+
+impl<'a> AqlQuery<'a> {
+    pub(crate) fn query(&self) -> &'a str {
+        self.query
+    }
+
+    /// Raise the batch size of the query to at least `batch_size`.
+    pub(crate) fn set_min_batch_size(&mut self, batch_size: u32) {
+        if self.batch_size < Some(batch_size) {
+            self.batch_size = Some(batch_size);
+        }
+    }
+
+    /// Whether the query is aborted by its first warning.
+    pub(crate) fn fails_on_warning(&self) -> bool {
+        self.options
+            .as_ref()
+            .and_then(|options| options.fail_on_warning)
+            .unwrap_or(false)
+    }
+
+    pub(crate) fn explain_request(&self) -> ExplainRequest<'_> {
+        ExplainRequest {
+            query: self.query,
+            bind_vars: &self.bind_vars,
+        }
+    }
+
+    /// Fail if a collection bound with `bind_collection(s)` belongs to
+    /// another database than the one at `db_url`, which runs the query.
+    pub(crate) fn check_database(&self, db_url: &Url) -> Result<(), ClientError> {
+        match self.bind_vars.databases.iter().find(|url| *url != db_url) {
+            Some(url) => Err(ClientError::InvalidQuery(format!(
+                "a collection of database {} is bound to a query run on database {}",
+                url, db_url
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Body of a request to explain an AQL query.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ExplainRequest<'a> {
+    query: &'a str,
+    #[serde(skip_serializing_if = "BindVars::is_empty")]
+    bind_vars: &'a BindVars<'a>,
+}
+
+/// Result of explaining an AQL query, i.e. the optimal execution plan chosen
+/// by the query optimizer.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainResult {
+    pub plan: ExecutionPlan,
+    /// Whether the query result could be cached.
+    pub cacheable: Option<bool>,
+    #[serde(default)]
+    pub warnings: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPlan {
+    /// Execution nodes of the plan, in the order of the query.
+    pub nodes: Vec<ExecutionNode>,
+    /// Optimizer rules applied to the plan.
+    #[serde(default)]
+    pub rules: Vec<String>,
+    pub estimated_cost: f64,
+    pub estimated_nr_items: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionNode {
+    /// Type of the node, e.g. `EnumerateCollectionNode` or `IndexNode`.
+    #[serde(rename = "type")]
+    pub node_type: String,
+    pub id: u64,
+    pub estimated_cost: f64,
+    /// Name of the collection accessed by this node, if any.
+    pub collection: Option<String>,
+    /// All other node specific attributes.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// A query currently running on the server
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningQuery {
+    /// Id of the query, to kill it with `Database::kill_query`.
+    pub id: String,
+    pub database: Option<String>,
+    pub user: Option<String>,
+    pub query: String,
+    #[serde(default)]
+    pub bind_vars: HashMap<String, Value>,
+    /// Start time of the query, as an ISO 8601 date.
+    pub started: String,
+    /// Run time of the query so far, in seconds.
+    pub run_time: f64,
+    /// Execution state of the query, e.g. `executing`.
+    pub state: String,
+    /// Whether the query uses a streaming cursor.
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// A collection and the attributes it is filtered on without the help of an
+/// index.
+#[derive(Debug, PartialEq, Eq)]
+pub struct IndexSuggestion {
+    pub collection: String,
+    pub fields: Vec<String>,
+}
+
+/// Inspect an execution plan for full collection scans whose output is
+/// filtered on some attributes.
+///
+/// This is a heuristic: it only looks at direct attribute accesses on the
+/// documents produced by `EnumerateCollectionNode`s, in their own filter or in
+/// calculations consumed by a `FilterNode`, and cannot tell whether an index
+/// would really be selective enough to be used.
+pub(crate) fn suggest_indexes(explain: &ExplainResult) -> Vec<IndexSuggestion> {
+    // the variables that `FilterNode`s filter on
+    let filtered: Vec<&str> = explain
+        .plan
+        .nodes
+        .iter()
+        .filter(|n| n.node_type == "FilterNode")
+        .filter_map(|n| variable_name(n, "inVariable"))
+        .collect();
+
+    let mut suggestions = Vec::new();
+    for node in explain.plan.nodes.iter() {
+        if node.node_type != "EnumerateCollectionNode" {
+            continue;
+        }
+        let (collection, variable) =
+            match (node.collection.as_ref(), variable_name(node, "outVariable")) {
+                (Some(collection), Some(variable)) => (collection, variable),
+                _ => continue,
+            };
+
+        let mut fields = Vec::new();
+        if let Some(filter) = node.extra.get("filter") {
+            collect_attribute_accesses(filter, variable, &mut fields);
+        }
+        // only the calculations filtered on, not e.g. projections or sort keys
+        for calculation in explain.plan.nodes.iter().filter(|n| {
+            n.node_type == "CalculationNode"
+                && variable_name(n, "outVariable").is_some_and(|v| filtered.contains(&v))
+        }) {
+            if let Some(expression) = calculation.extra.get("expression") {
+                collect_attribute_accesses(expression, variable, &mut fields);
+            }
+        }
+
+        if !fields.is_empty() {
+            suggestions.push(IndexSuggestion {
+                collection: collection.clone(),
+                fields,
+            });
+        }
+    }
+    suggestions
+}
+
+/// Name of the variable a plan node refers to in `attribute`, e.g.
+/// `outVariable`.
+fn variable_name<'a>(node: &'a ExecutionNode, attribute: &str) -> Option<&'a str> {
+    node.extra
+        .get(attribute)
+        .and_then(|v| v.get("name"))
+        .and_then(Value::as_str)
+}
+
+/// Collect the attributes accessed on `variable` in an AST expression.
+fn collect_attribute_accesses(expression: &Value, variable: &str, fields: &mut Vec<String>) {
+    let sub_nodes = expression.get("subNodes").and_then(Value::as_array);
+    if expression.get("type").and_then(Value::as_str) == Some("attribute access") {
+        let on_variable = sub_nodes
+            .and_then(|nodes| nodes.first())
+            .is_some_and(|node| {
+                node.get("type").and_then(Value::as_str) == Some("reference")
+                    && node.get("name").and_then(Value::as_str) == Some(variable)
+            });
+        if let (true, Some(name)) = (on_variable, expression.get("name").and_then(Value::as_str)) {
+            if !fields.iter().any(|f| f == name) {
+                fields.push(name.to_owned());
+            }
+        }
+    }
+    for node in sub_nodes.into_iter().flatten() {
+        collect_attribute_accesses(node, variable, fields);
+    }
+}
+
+/// Build an `AqlQuery` whose bind parameters are checked against the query.
+///
+/// The query must be a string literal, so that values cannot be formatted
+/// into it, and is followed by `name = value` pairs. A value is bound to
+/// the value bind parameter `@name` or, as a collection name, to the
+/// collection bind parameter `@@name`, whichever the query uses. Values can
+/// be anything serializable.
+///
+/// Other options of the query can be set with `AqlQuery::builder` instead.
+///
+/// The macro evaluates to a `Result<AqlQuery, ClientError>`: it fails with
+/// `ClientError::InvalidQuery` if a bind parameter of the query is not given
+/// a value, or if a value is given for a parameter the query does not use,
+/// and with `ClientError::Serde` if a value fails to serialize.
+///
+/// # Example
+/// ```rust
+/// use arangors::aql;
+///
+/// let min_age = 18;
+/// let aql = aql!(
+///     "FOR u IN @@users FILTER u.age >= @age RETURN u",
+///     users = "users",
+///     age = min_age,
+/// )
+/// .unwrap();
+/// ```
+#[macro_export]
+macro_rules! aql {
+    ($query:literal $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::aql::__checked_bind_vars(
+            $query,
+            vec![$((stringify!($name), $crate::aql::__bind_value(&$value))),*],
+        )
+        .map(|bind_vars| {
+            $crate::AqlQuery::builder()
+                .query($query)
+                .bind_vars(bind_vars)
+                .build()
+        })
+    };
+}
+
+#[doc(hidden)]
+pub fn __bind_value<T: Serialize + ?Sized>(value: &T) -> Result<Value, serde_json::Error> {
+    serde_json::to_value(value)
+}
+
+/// Bind `values` to the bind parameters of `query`, see `aql!`.
+#[doc(hidden)]
+pub fn __checked_bind_vars<'a>(
+    query: &'a str,
+    values: Vec<(&str, Result<Value, serde_json::Error>)>,
+) -> Result<HashMap<&'a str, Value>, ClientError> {
+    let params = bind_parameters(query);
+    let mut bind_vars = HashMap::new();
+    for (name, value) in values {
+        let value = value?;
+        let mut used = false;
+        for param in params.iter().filter(|p| p.trim_start_matches('@') == name) {
+            bind_vars.insert(*param, value.clone());
+            used = true;
+        }
+        if !used {
+            return Err(ClientError::InvalidQuery(format!(
+                "bind parameter `{}` is not used in the query",
+                name
+            )));
+        }
+    }
+    if let Some(param) = params.iter().find(|p| !bind_vars.contains_key(*p)) {
+        return Err(ClientError::InvalidQuery(format!(
+            "bind parameter `@{}` of the query is not bound",
+            param
+        )));
+    }
+    Ok(bind_vars)
+}
+
+/// Names of the bind parameters of an AQL query, as sent in `bindVars`, i.e.
+/// `@users` for the collection bind parameter `@@users`.
+///
+/// String literals, quoted names and comments are skipped.
+fn bind_parameters(query: &str) -> Vec<&str> {
+    let bytes = query.as_bytes();
+    let mut params = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'' | b'`') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = query[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |end| i + 2 + end + 2);
+            }
+            b'@' => {
+                let start = i + 1;
+                let mut end = start;
+                if bytes.get(end) == Some(&b'@') {
+                    end += 1;
+                }
+                while end < bytes.len()
+                    && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'_')
+                {
+                    end += 1;
+                }
+                let name = &query[start..end];
+                if !name.trim_start_matches('@').is_empty() && !params.contains(&name) {
+                    params.push(name);
+                }
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    params
 }
 
 #[cfg(test)]
@@ -492,11 +1093,11 @@ mod test {
         assert_eq!(aql.options, None);
 
         assert_eq!(
-            aql.bind_vars.get("username"),
+            aql.bind_vars.vars.get("username"),
             Some(&Value::String("test2".to_owned()))
         );
         assert_eq!(
-            aql.bind_vars.get("password"),
+            aql.bind_vars.vars.get("password"),
             Some(&Value::String("test2_pwd".to_owned()))
         );
     }
@@ -527,7 +1128,7 @@ mod test {
         map.insert("username".into(), "test2".into());
         map.insert("password".into(), "test2_pwd".into());
 
-        assert_eq!(aql.bind_vars.get("user"), Some(&Value::Object(map)));
+        assert_eq!(aql.bind_vars.vars.get("user"), Some(&Value::Object(map)));
 
         let aql = AqlQuery::builder()
             .query(r#"FOR i in test_collection FILTER i.username==@username AND i.password==@password return i"#)
@@ -540,12 +1141,201 @@ mod test {
             .build();
 
         assert_eq!(
-            aql.bind_vars.get("username"),
+            aql.bind_vars.vars.get("username"),
             Some(&Value::String("test2".to_owned()))
         );
         assert_eq!(
-            aql.bind_vars.get("password"),
+            aql.bind_vars.vars.get("password"),
             Some(&Value::String("test2_pwd".to_owned()))
         );
     }
+
+    #[test]
+    fn aql_macro_binds_parameters() {
+        let username = "test2".to_owned();
+        let aql = aql!(
+            r#"FOR i IN @@coll FILTER i.username == @username AND i.email != "a@b.c" RETURN i // @ignored"#,
+            coll = "test_collection",
+            username = username,
+        )
+        .unwrap();
+        assert_eq!(aql.bind_vars.vars.len(), 2);
+        assert_eq!(
+            aql.bind_vars.vars.get("@coll"),
+            Some(&Value::from("test_collection"))
+        );
+        assert_eq!(
+            aql.bind_vars.vars.get("username"),
+            Some(&Value::from("test2"))
+        );
+
+        let aql = aql!(
+            "FOR i IN test_collection FILTER i.age > @age.min RETURN i",
+            age = serde_json::json!({ "min": 18 })
+        )
+        .unwrap();
+        assert_eq!(aql.bind_vars.vars.get("age").unwrap()["min"], 18);
+    }
+
+    #[test]
+    fn aql_macro_rejects_unbound_parameter() {
+        let result = aql!(
+            "FOR i IN test_collection FILTER i.username == @username AND i.password == @password RETURN i",
+            username = "test2",
+        );
+        match result {
+            Err(ClientError::InvalidQuery(message)) => assert_eq!(
+                message,
+                "bind parameter `@password` of the query is not bound"
+            ),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn aql_macro_rejects_unused_value() {
+        let result = aql!(
+            "FOR i IN test_collection FILTER i.username == @username RETURN i",
+            username = "test2",
+            password = "test2_pwd",
+        );
+        match result {
+            Err(ClientError::InvalidQuery(message)) => {
+                assert_eq!(
+                    message,
+                    "bind parameter `password` is not used in the query"
+                )
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn aql_macro_rejects_unserializable_value() {
+        // json object keys must be strings
+        let value: HashMap<(u8, u8), u8> = [((1, 2), 3)].into_iter().collect();
+        let result = aql!("RETURN @value", value = value);
+        assert!(matches!(result, Err(ClientError::Serde(_))));
+    }
+
+    #[test]
+    fn suggest_indexes_for_full_collection_scan() {
+        let plan = serde_json::json!({
+            "plan": {
+                "nodes": [
+                    { "type": "SingletonNode", "id": 1, "estimatedCost": 1 },
+                    {
+                        "type": "EnumerateCollectionNode",
+                        "id": 2,
+                        "estimatedCost": 102,
+                        "collection": "users",
+                        "outVariable": { "id": 0, "name": "u" }
+                    },
+                    {
+                        "type": "CalculationNode",
+                        "id": 3,
+                        "estimatedCost": 202,
+                        "expression": {
+                            "type": "compare ==",
+                            "subNodes": [
+                                {
+                                    "type": "attribute access",
+                                    "name": "email",
+                                    "subNodes": [{ "type": "reference", "name": "u", "id": 0 }]
+                                },
+                                { "type": "value", "value": "a@b.c" }
+                            ]
+                        },
+                        "outVariable": { "id": 2, "name": "2" }
+                    },
+                    {
+                        "type": "FilterNode",
+                        "id": 5,
+                        "estimatedCost": 302,
+                        "inVariable": { "id": 2, "name": "2" }
+                    },
+                    {
+                        "type": "IndexNode",
+                        "id": 4,
+                        "estimatedCost": 1,
+                        "collection": "orders",
+                        "outVariable": { "id": 1, "name": "o" }
+                    }
+                ],
+                "rules": [],
+                "estimatedCost": 302,
+                "estimatedNrItems": 100
+            },
+            "cacheable": true,
+            "warnings": []
+        });
+        let explain: ExplainResult = serde_json::from_value(plan).unwrap();
+        assert_eq!(
+            suggest_indexes(&explain),
+            vec![IndexSuggestion {
+                collection: "users".to_owned(),
+                fields: vec!["email".to_owned()],
+            }]
+        );
+    }
+
+    #[test]
+    fn no_index_suggestion_without_filter() {
+        // FOR u IN users SORT u.age RETURN u.name
+        let plan = serde_json::json!({
+            "plan": {
+                "nodes": [
+                    { "type": "SingletonNode", "id": 1, "estimatedCost": 1 },
+                    {
+                        "type": "EnumerateCollectionNode",
+                        "id": 2,
+                        "estimatedCost": 102,
+                        "collection": "users",
+                        "outVariable": { "id": 0, "name": "u" }
+                    },
+                    {
+                        "type": "CalculationNode",
+                        "id": 3,
+                        "estimatedCost": 202,
+                        "expression": {
+                            "type": "attribute access",
+                            "name": "age",
+                            "subNodes": [{ "type": "reference", "name": "u", "id": 0 }]
+                        },
+                        "outVariable": { "id": 1, "name": "1" }
+                    },
+                    {
+                        "type": "SortNode",
+                        "id": 4,
+                        "estimatedCost": 866,
+                        "elements": [{ "inVariable": { "id": 1, "name": "1" }, "ascending": true }]
+                    },
+                    {
+                        "type": "CalculationNode",
+                        "id": 5,
+                        "estimatedCost": 966,
+                        "expression": {
+                            "type": "attribute access",
+                            "name": "name",
+                            "subNodes": [{ "type": "reference", "name": "u", "id": 0 }]
+                        },
+                        "outVariable": { "id": 2, "name": "2" }
+                    },
+                    {
+                        "type": "ReturnNode",
+                        "id": 6,
+                        "estimatedCost": 1066,
+                        "inVariable": { "id": 2, "name": "2" }
+                    }
+                ],
+                "rules": [],
+                "estimatedCost": 1066,
+                "estimatedNrItems": 100
+            },
+            "cacheable": true,
+            "warnings": []
+        });
+        let explain: ExplainResult = serde_json::from_value(plan).unwrap();
+        assert_eq!(suggest_indexes(&explain), vec![]);
+    }
 }
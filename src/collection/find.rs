@@ -0,0 +1,222 @@
+//! A minimal query builder for simple lookups on a collection.
+//!
+//! Every value, attribute name and the collection itself are passed as bind
+//! parameters, never interpolated into the query string.
+use std::collections::HashMap;
+
+use maybe_async::maybe_async;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use uclient::ClientExt;
+
+use crate::{ClientError, Collection};
+
+/// Largest integer exactly represented in AQL, used as the count of a
+/// `LIMIT` with an offset but no limit.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Sort direction of `Find::sort`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Access of the attribute `path` of `d`, binding every segment of the path.
+fn attribute_access(path: &str, bind_vars: &mut Vec<(String, Value)>) -> String {
+    let mut access = String::from("d");
+    for segment in path.split('.') {
+        let name = format!("a{}", bind_vars.len());
+        access.push_str(&format!("[@{}]", name));
+        bind_vars.push((name, segment.into()));
+    }
+    access
+}
+
+#[derive(Debug, Clone)]
+enum Filter {
+    Eq(String, Value),
+    In(String, Vec<Value>),
+}
+
+/// Builder of a `FOR d IN collection FILTER ... SORT ... LIMIT ... RETURN d`
+/// query, created by `Collection::find`.
+///
+/// Attribute paths are split on `.`, so `address.city` refers to the `city`
+/// attribute of the `address` sub-document.
+///
+/// # Example
+/// ```rust, ignore
+/// let active: Vec<Document<User>> = collection
+///     .find()
+///     .filter_eq("status", "active")
+///     .sort("name", SortOrder::Asc)
+///     .limit(10)
+///     .run()
+///     .await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Find<'c, C: ClientExt> {
+    collection: &'c Collection<C>,
+    filters: Vec<Filter>,
+    sorts: Vec<(String, SortOrder)>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+
+impl<'c, C: ClientExt> Find<'c, C> {
+    pub(crate) fn new(collection: &'c Collection<C>) -> Self {
+        Find {
+            collection,
+            filters: Vec::new(),
+            sorts: Vec::new(),
+            limit: None,
+            offset: None,
+        }
+    }
+
+    /// Keep documents whose `attribute` equals `value`.
+    pub fn filter_eq<V: Into<Value>>(mut self, attribute: &str, value: V) -> Self {
+        self.filters
+            .push(Filter::Eq(attribute.to_owned(), value.into()));
+        self
+    }
+
+    /// Keep documents whose `attribute` is one of `values`.
+    pub fn filter_in<I, V>(mut self, attribute: &str, values: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        self.filters.push(Filter::In(
+            attribute.to_owned(),
+            values.into_iter().map(Into::into).collect(),
+        ));
+        self
+    }
+
+    /// Sort by `attribute`. Can be called several times to sort by several
+    /// attributes.
+    pub fn sort(mut self, attribute: &str, order: SortOrder) -> Self {
+        self.sorts.push((attribute.to_owned(), order));
+        self
+    }
+
+    /// Return at most `limit` documents.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Skip the first `offset` documents.
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Build the query string and its bind parameters.
+    fn build(&self) -> (String, Vec<(String, Value)>) {
+        let mut query = String::from("FOR d IN @@collection");
+        let mut bind_vars = vec![("@collection".to_owned(), self.collection.name().into())];
+
+        for filter in &self.filters {
+            let (path, operator, value) = match filter {
+                Filter::Eq(path, value) => (path, "==", value.clone()),
+                Filter::In(path, values) => (path, "IN", Value::Array(values.clone())),
+            };
+            let access = attribute_access(path, &mut bind_vars);
+            let name = format!("v{}", bind_vars.len());
+            query.push_str(&format!(" FILTER {} {} @{}", access, operator, name));
+            bind_vars.push((name, value));
+        }
+
+        if !self.sorts.is_empty() {
+            let sorts: Vec<String> = self
+                .sorts
+                .iter()
+                .map(|(path, order)| {
+                    let access = attribute_access(path, &mut bind_vars);
+                    match order {
+                        SortOrder::Asc => format!("{} ASC", access),
+                        SortOrder::Desc => format!("{} DESC", access),
+                    }
+                })
+                .collect();
+            query.push_str(&format!(" SORT {}", sorts.join(", ")));
+        }
+
+        if self.limit.is_some() || self.offset.is_some() {
+            query.push_str(" LIMIT @offset, @limit");
+            bind_vars.push(("offset".to_owned(), self.offset.unwrap_or(0).into()));
+            bind_vars.push((
+                "limit".to_owned(),
+                self.limit.unwrap_or(MAX_SAFE_INTEGER).into(),
+            ));
+        }
+
+        query.push_str(" RETURN d");
+        (query, bind_vars)
+    }
+
+    /// Execute the query and fetch all matching documents.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn run<T>(&self) -> Result<Vec<T>, ClientError>
+    where
+        T: DeserializeOwned,
+    {
+        let (query, bind_vars) = self.build();
+        let bind_vars: HashMap<&str, Value> = bind_vars
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone()))
+            .collect();
+        self.collection.db().aql_bind_vars(&query, bind_vars).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use serde_json::json;
+    use url::Url;
+
+    use super::*;
+    use crate::{collection::CollectionType, connection::Session, mock::NoClient};
+
+    #[test]
+    fn build_query() {
+        let url = Url::parse("http://localhost:8529/_db/test/").unwrap();
+        let client = NoClient::new(None).unwrap();
+        let session = Arc::new(Session::with_client(client, Default::default()).unwrap());
+        let collection = Collection::new("users", "1", CollectionType::Document, &url, session);
+
+        let (query, bind_vars) = collection.find().build();
+        assert_eq!(query, "FOR d IN @@collection RETURN d");
+        assert_eq!(bind_vars, vec![("@collection".to_owned(), json!("users"))]);
+
+        let (query, bind_vars) = collection
+            .find()
+            .filter_eq("status", "active")
+            .filter_in("address.city", vec!["Paris", "Berlin"])
+            .sort("name", SortOrder::Desc)
+            .offset(20)
+            .build();
+        assert_eq!(
+            query,
+            "FOR d IN @@collection FILTER d[@a1] == @v2 FILTER d[@a3][@a4] IN @v5 SORT d[@a6] \
+             DESC LIMIT @offset, @limit RETURN d"
+        );
+        let bind_vars: HashMap<String, Value> = bind_vars.into_iter().collect();
+        assert_eq!(bind_vars["a1"], "status");
+        assert_eq!(bind_vars["v2"], "active");
+        assert_eq!(bind_vars["a3"], "address");
+        assert_eq!(bind_vars["a4"], "city");
+        assert_eq!(bind_vars["v5"], json!(["Paris", "Berlin"]));
+        assert_eq!(bind_vars["a6"], "name");
+        assert_eq!(bind_vars["offset"], 20);
+        assert_eq!(bind_vars["limit"], MAX_SAFE_INTEGER);
+    }
+}
@@ -2,15 +2,29 @@
 //!
 //! This mod contains struct and type of colleciton info and management, as well
 //! as document related operations.
-use std::{convert::TryFrom, sync::Arc};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
-use http::Request;
+#[cfg(not(feature = "blocking"))]
+use futures_util::stream::{self, Stream, StreamExt};
+use http::{
+    header::{ETAG, IF_MATCH, IF_NONE_MATCH, LOCATION},
+    HeaderMap, HeaderValue, Request, StatusCode,
+};
 use maybe_async::maybe_async;
-use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
-use serde_json::json;
+use serde::{
+    de::{DeserializeOwned, IgnoredAny},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::{json, Value};
 use uclient::ClientExt;
 use url::Url;
 
+use find::Find;
 use options::*;
 use response::*;
 
@@ -20,14 +34,24 @@ use crate::{
         response::DocumentResponse,
         Header,
     },
-    response::{deserialize_response, ArangoResult},
+    error::{ERROR_CONFLICT, ERROR_DOCUMENT_NOT_FOUND},
+    index::{Index, IndexType},
+    job::{JobId, ASYNC_HEADER, ASYNC_ID_HEADER},
+    replication::{
+        CollectionDump, DumpBatch, DumpEntry, DumpOptions, CHECK_MORE_HEADER, LAST_INCLUDED_HEADER,
+    },
+    response::{
+        deserialize_array_response, deserialize_element, deserialize_lines, deserialize_response,
+        ArangoResult, WithHeaders,
+    },
     transaction::Transaction,
-    ClientError,
+    ArangoError, ClientError,
 };
 
-use super::{Database, Document};
+use super::{connection::Session, database::DefaultOptions, Database, Document};
 use crate::transaction::TRANSACTION_HEADER;
 
+pub mod find;
 pub mod options;
 pub mod response;
 
@@ -48,7 +72,20 @@ pub struct Collection<C: ClientExt> {
     collection_type: CollectionType,
     base_url: Url,
     document_base_url: Url,
-    session: Arc<C>,
+    session: Arc<Session<C>>,
+    /// Document count fetched by `count_cached`
+    count_cache: Arc<Mutex<CountCache>>,
+    default_options: DefaultOptions,
+}
+
+/// Document count cached by `Collection::count_cached`.
+#[derive(Debug, Default)]
+struct CountCache {
+    /// The count, with the time it was fetched
+    count: Option<(Instant, u64)>,
+    /// Bumped by every invalidation, so that a count fetched concurrently
+    /// with a write is not cached
+    generation: u64,
 }
 
 impl<'a, C: ClientExt> Collection<C> {
@@ -61,7 +98,7 @@ impl<'a, C: ClientExt> Collection<C> {
         id: S,
         collection_type: CollectionType,
         db_url: &Url,
-        session: Arc<C>,
+        session: Arc<Session<C>>,
     ) -> Collection<C> {
         let name = name.into();
         let path = format!("_api/collection/{}/", &name);
@@ -75,17 +112,22 @@ impl<'a, C: ClientExt> Collection<C> {
             base_url: url,
             document_base_url,
             collection_type,
+            count_cache: Arc::new(Mutex::new(CountCache::default())),
+            default_options: DefaultOptions::default(),
         }
     }
 
     pub(crate) fn from_response(database: &Database<C>, collection: &Info) -> Collection<C> {
-        Self::new(
-            &collection.name,
-            &collection.id,
-            collection.collection_type,
-            database.url(),
-            database.session(),
-        )
+        Collection {
+            default_options: database.default_options().clone(),
+            ..Self::new(
+                &collection.name,
+                &collection.id,
+                collection.collection_type,
+                database.url(),
+                database.session(),
+            )
+        }
     }
 
     pub(crate) fn from_transaction_response(
@@ -150,29 +192,74 @@ impl<'a, C: ClientExt> Collection<C> {
         &self.document_base_url
     }
 
+    /// Document base url, same as `doc_url`
+    ///
+    /// Useful to build custom requests on documents. Keys appended to it must
+    /// be percent-encoded, e.g. with `Url::path_segments_mut`.
+    pub fn document_url(&self) -> &Url {
+        &self.document_base_url
+    }
+
+    /// Url of the document with `_key`, which is percent-encoded as keys may
+    /// contain characters like `:` or `@`.
+    fn key_url(&self, _key: &str) -> Url {
+        let mut url = self.document_base_url.clone();
+        url.path_segments_mut().unwrap().pop_if_empty().push(_key);
+        url
+    }
+
     /// HTTP Client used to query the server
-    pub fn session(&self) -> Arc<C> {
+    pub fn session(&self) -> Arc<Session<C>> {
         Arc::clone(&self.session)
     }
 
     /// Get the db of current collection
     pub fn db(&self) -> Database<C> {
-        // Base url should be like `http://server:port/_db/mydb/_api/collection/{collection-name}`
-        let mut paths = self.base_url.path_segments().unwrap();
-        // must be `_db`
-        paths.next();
-        // must be db name
-        let name = paths.next().unwrap();
-        Database::new(name, &self.url().join("/").unwrap(), self.session())
+        let db_url = self.db_url();
+        let arango_url = db_url.join("../../").unwrap();
+        // the last segment of the db url is empty
+        let name = db_url.path_segments().unwrap().rev().nth(1).unwrap();
+        Database::new(name, &arango_url, self.session())
+            .with_default_options(self.default_options.clone())
+    }
+
+    /// Url of the database of this collection.
+    pub(crate) fn db_url(&self) -> Url {
+        // Base url should be like
+        // `http://server:port/{path}/_db/mydb/_api/collection/{collection-name}/`,
+        // where the path of the server is usually empty
+        self.base_url.join("../../../").unwrap()
     }
 
     /// Drop a collection
     ///
+    /// The handle is consumed, as the collection it refers to does not exist
+    /// anymore. Clones of the handle are not invalidated though: requests
+    /// made through them fail with a "collection or view not found" error
+    /// (1203), or reach a new collection created with the same name.
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
     pub async fn drop(self) -> Result<String, ClientError> {
-        let url = self.base_url.join("").unwrap();
+        let dropped = self.drop_with_options(Default::default()).await?;
+        Ok(dropped.id)
+    }
+
+    /// Drop a collection with options, returning what was dropped
+    ///
+    /// System collections, whose names start with an underscore, can only be
+    /// dropped with `DropOptions::is_system` set.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn drop_with_options(
+        self,
+        options: DropOptions,
+    ) -> Result<DroppedCollection, ClientError> {
+        let mut url = self.base_url.join("").unwrap();
+        url.set_query(Some(&serde_qs::to_string(&options).unwrap()));
 
         #[derive(Debug, Deserialize)]
         struct DropCollectionResponse {
@@ -181,20 +268,61 @@ impl<'a, C: ClientExt> Collection<C> {
 
         let resp: DropCollectionResponse =
             deserialize_response(self.session.delete(url, "").await?.body())?;
-        Ok(resp.id)
+        Ok(DroppedCollection {
+            id: resp.id,
+            was_system: self.name.starts_with('_'),
+            name: self.name,
+        })
+    }
+
+    /// Drop a collection, treating a collection that does not exist (anymore)
+    /// as success.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn drop_if_exists(self) -> Result<(), ClientError> {
+        match self.drop().await {
+            Ok(_) => Ok(()),
+            Err(e) if e.is_data_source_not_found() => Ok(()),
+            Err(e) => Err(e),
+        }
     }
 
     /// Truncate current collection
     ///
+    /// All documents are removed, while the collection, its indexes and its
+    /// properties are kept, hence the handle stays valid. Values previously
+    /// read from the collection, e.g. its count or checksum, are outdated
+    /// afterwards.
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
     pub async fn truncate(&self) -> Result<Info, ClientError> {
         let url = self.base_url.join("truncate").unwrap();
-        let resp: Info = deserialize_response(self.session.put(url, "").await?.body())?;
+        let resp = self.session.put(url, "").await;
+        self.invalidate_count();
+        let resp: Info = deserialize_response(resp?.body())?;
         Ok(resp)
     }
 
+    /// Truncate current collection, treating a collection that does not exist
+    /// (anymore) as success.
+    ///
+    /// Useful for idempotent cleanup, e.g. in test teardown.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn truncate_if_exists(&self) -> Result<(), ClientError> {
+        match self.truncate().await {
+            Ok(_) => Ok(()),
+            Err(e) if e.is_data_source_not_found() => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Fetch the properties of collection
     ///
     /// # Note
@@ -206,6 +334,39 @@ impl<'a, C: ClientExt> Collection<C> {
         Ok(resp)
     }
 
+    /// Fetch the attributes used to determine the shard of a document
+    ///
+    /// Documents with the same values for these attributes are stored in the
+    /// same shard, which allows grouping them by shard before a bulk write.
+    /// A single server reports no shard keys, hence the default `_key` is
+    /// returned.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[cfg(feature = "cluster")]
+    #[maybe_async]
+    pub async fn shard_keys(&self) -> Result<Vec<String>, ClientError> {
+        let properties = self.properties().await?;
+        Ok(properties
+            .detail
+            .shard_keys
+            .unwrap_or_else(|| vec!["_key".to_owned()]))
+    }
+
+    /// Retrieve the indexes of this collection that are of the given type.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn indexes_of_type(&self, index_type: IndexType) -> Result<Vec<Index>, ClientError> {
+        let list = self.db().indexes(&self.name).await?;
+        Ok(list
+            .indexes
+            .into_iter()
+            .filter(|index| index.index_type() == index_type)
+            .collect())
+    }
+
     /// Count the documents in this collection
     ///
     /// # Note
@@ -216,6 +377,106 @@ impl<'a, C: ClientExt> Collection<C> {
         let resp: Properties = deserialize_response(self.session.get(url, "").await?.body())?;
         Ok(resp)
     }
+
+    /// Count the documents in this collection, reusing the count fetched by a
+    /// previous call when it is not older than `max_age`
+    ///
+    /// Meant for callers polling the count, e.g. a UI, that do not need it
+    /// to be exact. The cached count is dropped whenever documents are
+    /// written through this handle or its clones, but writes made by other
+    /// clients are only seen once it is older than `max_age`.
+    ///
+    /// # Note
+    /// this function would make a request to arango server when the cached
+    /// count is missing or outdated.
+    #[maybe_async]
+    pub async fn count_cached(&self, max_age: Duration) -> Result<u64, ClientError> {
+        let generation = {
+            let cache = self.count_cache.lock().unwrap();
+            if let Some((fetched_at, count)) = cache.count {
+                if fetched_at.elapsed() <= max_age {
+                    return Ok(count);
+                }
+            }
+            cache.generation
+        };
+        let fetched_at = Instant::now();
+        let count = self.document_count().await?.info.count.unwrap_or_default() as u64;
+        // a write answered while the count was fetched may not be part of it
+        let mut cache = self.count_cache.lock().unwrap();
+        if cache.generation == generation {
+            cache.count = Some((fetched_at, count));
+        }
+        Ok(count)
+    }
+
+    /// Deserialize the response of a bulk write into one result per
+    /// document.
+    fn deserialize_bulk_response<R>(
+        &self,
+        body: &str,
+    ) -> Result<Vec<Result<DocumentResponse<R>, ClientError>>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        let results: Vec<Value> = deserialize_array_response(body)?;
+        Ok(results
+            .into_iter()
+            .map(|result| deserialize_element(result).map_err(|e| e.in_collection(&self.name)))
+            .collect())
+    }
+
+    /// Add the default options of the database that are not set in the
+    /// query of `url`.
+    fn apply_default_options(&self, url: &mut Url) {
+        if let Some(wait_for_sync) = self.default_options.wait_for_sync {
+            if !url.query_pairs().any(|(name, _)| name == "waitForSync") {
+                url.query_pairs_mut()
+                    .append_pair("waitForSync", &wait_for_sync.to_string());
+            }
+        }
+    }
+
+    /// Drop the count cached by `count_cached`.
+    ///
+    /// Called once the response of a write arrived, whether it succeeded or
+    /// not: invalidating before sending would let a concurrent
+    /// `count_cached` cache the count from before the write.
+    fn invalidate_count(&self) {
+        let mut cache = self.count_cache.lock().unwrap();
+        cache.generation += 1;
+        cache.count = None;
+    }
+
+    /// Count the documents of each shard of this collection
+    ///
+    /// Useful to detect skewed shards in a cluster. On a single server, the
+    /// map contains a single entry named after the collection.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn count_by_shard(&self) -> Result<HashMap<String, u64>, ClientError> {
+        #[derive(Debug, Deserialize)]
+        #[serde(untagged)]
+        enum ShardCount {
+            Total(u64),
+            Shards(HashMap<String, u64>),
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct CountResponse {
+            count: ShardCount,
+        }
+
+        let mut url = self.base_url.join("count").unwrap();
+        url.set_query(Some("details=true"));
+        let resp: CountResponse = deserialize_response(self.session.get(url, "").await?.body())?;
+        match resp.count {
+            ShardCount::Total(count) => Ok(HashMap::from([(self.name.clone(), count)])),
+            ShardCount::Shards(shards) => Ok(shards),
+        }
+    }
     /// Fetch the statistics of a collection
     ///
     /// The result also contains the number of documents and additional
@@ -251,6 +512,23 @@ impl<'a, C: ClientExt> Collection<C> {
         Ok(resp)
     }
 
+    /// Fetch the statistics of a collection, with the details reported by the
+    /// storage engine
+    ///
+    /// Same as `statistics`, `Figures::engine` being filled in addition, e.g.
+    /// with the compaction status of the collection. Collecting the details
+    /// can be expensive for large collections. (rocksdb only)
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn statistics_with_details(&self) -> Result<Statistics, ClientError> {
+        let mut url = self.base_url.join("figures").unwrap();
+        url.query_pairs_mut().append_pair("details", "true");
+        let resp: Statistics = deserialize_response(self.session.get(url, "").await?.body())?;
+        Ok(resp)
+    }
+
     /// Retrieve the collections revision id
     ///
     /// The revision id is a server-generated string that clients can use to
@@ -327,6 +605,88 @@ impl<'a, C: ClientExt> Collection<C> {
         Ok(resp)
     }
 
+    /// Start computing the checksum of the collection as an async job
+    ///
+    /// With `with_data`, computing the checksum of a huge collection can take
+    /// longer than the request timeout. The server accepts the job right away
+    /// and computes the checksum in the background, the result is then polled
+    /// with `checksum_result`.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn checksum_async(&self, options: ChecksumOptions) -> Result<JobId, ClientError> {
+        let mut url = self.base_url.join("checksum").unwrap();
+        let query = serde_qs::to_string(&options).unwrap();
+        url.set_query(Some(query.as_str()));
+
+        let req = Request::get(url.to_string())
+            .header(ASYNC_HEADER, "store")
+            .body("".to_string())
+            .unwrap();
+        let resp = self.session.request(req).await?;
+        if resp.status() != StatusCode::ACCEPTED {
+            deserialize_response::<Value>(resp.body())?;
+            return Err(ClientError::InvalidServer(format!(
+                "checksum job not accepted: {}",
+                resp.status()
+            )));
+        }
+        resp.headers()
+            .get(ASYNC_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(|id| JobId(id.to_owned()))
+            .ok_or_else(|| {
+                ClientError::InvalidServer(format!("missing {} header", ASYNC_ID_HEADER))
+            })
+    }
+
+    /// Fetch the checksum computed by a job started with `checksum_async`
+    ///
+    /// Returns `None` while the job is pending. The result can only be fetched
+    /// once, see `Database::job_result`.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn checksum_result(&self, job: &JobId) -> Result<Option<Checksum>, ClientError> {
+        self.db().job_result(job).await
+    }
+
+    /// Compare the checksum of this collection with the one of another
+    /// collection, e.g. a copy on a different ArangoDB instance.
+    ///
+    /// Both checksums are calculated with the same options, see
+    /// `checksum_with_options`. The revisions the checksums were calculated
+    /// on are returned as well, so one can make sure that neither collection
+    /// changed in between.
+    ///
+    /// # Note
+    /// this function would make two requests, one to the server of each
+    /// collection.
+    #[maybe_async]
+    pub async fn checksums_match<D: ClientExt>(
+        &self,
+        other: &Collection<D>,
+        with_revisions: bool,
+        with_data: bool,
+    ) -> Result<ChecksumComparison, ClientError> {
+        let options = || {
+            ChecksumOptions::builder()
+                .with_revision(with_revisions)
+                .with_data(with_data)
+                .build()
+        };
+        let checksum = self.checksum_with_options(options()).await?;
+        let other_checksum = other.checksum_with_options(options()).await?;
+
+        Ok(ChecksumComparison {
+            matches: checksum.checksum == other_checksum.checksum,
+            revision: checksum.revision,
+            other_revision: other_checksum.revision,
+        })
+    }
+
     /// Load a collection into memory
     ///
     /// Returns the collection on success.
@@ -354,7 +714,9 @@ impl<'a, C: ClientExt> Collection<C> {
     /// Remove a collection from memory
     ///
     /// This call does not delete any documents. You can use the collection
-    /// afterwards; in which case it will be loaded into memory, again.
+    /// afterwards; in which case it will be loaded into memory, again. The
+    /// handle is not affected, only the `status` reported by the server
+    /// changes.
     ///
     /// **Warning**: The unload function is deprecated from version 3.8.0
     /// onwards and is a no-op from version 3.9.0 onwards. It should no
@@ -389,17 +751,43 @@ impl<'a, C: ClientExt> Collection<C> {
     /// limit and for the time being there is no way to control which indexes
     /// of the collection should have priority over others.
     ///
-    /// On success this function returns an object with attribute result set to
-    /// true
+    /// The server only reports whether it accepted the request, not whether
+    /// the memory limit stopped the loading, hence the cache figures of the
+    /// returned `LoadIndexes` are `None`. Use `load_indexes_with_figures` to
+    /// read them as well.
     ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
-    pub async fn load_indexes(&self) -> Result<bool, ClientError> {
+    pub async fn load_indexes(&self) -> Result<LoadIndexes, ClientError> {
         let url = self.base_url.join("loadIndexesIntoMemory").unwrap();
         let resp: ArangoResult<bool> =
             deserialize_response(self.session.put(url, "").await?.body())?;
-        Ok(resp.unwrap())
+        Ok(LoadIndexes {
+            result: resp.unwrap(),
+            cache_size: None,
+            cache_usage: None,
+        })
+    }
+
+    /// Same as `load_indexes`, then read the cache figures of the collection,
+    /// so that `LoadIndexes::truncated` can tell whether the memory limit
+    /// likely stopped the loading.
+    ///
+    /// The figures are a snapshot taken by a second request, hence may
+    /// already include the effect of other operations.
+    ///
+    /// # Note
+    /// this function would make two requests to arango server.
+    #[maybe_async]
+    pub async fn load_indexes_with_figures(&self) -> Result<LoadIndexes, ClientError> {
+        let loaded = self.load_indexes().await?;
+        let figures = self.statistics().await?.figures;
+        Ok(LoadIndexes {
+            cache_size: figures.cache_size,
+            cache_usage: figures.cache_usage,
+            ..loaded
+        })
     }
 
     /// Change the properties of a collection
@@ -420,6 +808,10 @@ impl<'a, C: ClientExt> Collection<C> {
 
     /// Rename the collection
     ///
+    /// The handle is updated to refer to the collection by its new name, both
+    /// for the operations on the collection and on its documents. Other
+    /// clones of the handle still use the old name, and fail afterwards.
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
@@ -428,8 +820,10 @@ impl<'a, C: ClientExt> Collection<C> {
         let body = json!({ "name": name });
         let resp: Info =
             deserialize_response(self.session.put(url, body.to_string()).await?.body())?;
-        self.name = name.to_string();
-        self.base_url = self.base_url.join(&format!("../{}/", name)).unwrap();
+        let path = format!("../{}/", &resp.name);
+        self.base_url = self.base_url.join(&path).unwrap();
+        self.document_base_url = self.document_base_url.join(&path).unwrap();
+        self.name = resp.name.clone();
         Ok(resp)
     }
 
@@ -511,6 +905,9 @@ impl<'a, C: ClientExt> Collection<C> {
     /// document, the complete new document is returned under the new attribute
     /// in the result.
     ///
+    /// Use `create_document_ref` when the input document cannot be
+    /// deserialized back, or the response should use another type.
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
@@ -522,144 +919,699 @@ impl<'a, C: ClientExt> Collection<C> {
     where
         T: Serialize + DeserializeOwned,
     {
-        let mut url = self.document_base_url.join("").unwrap();
-        let body = serde_json::to_string(&doc)?;
-        let query = serde_qs::to_string(&insert_options).unwrap();
-        url.set_query(Some(query.as_str()));
-        let resp: DocumentResponse<T> =
-            deserialize_response(self.session.post(url, body).await?.body())?;
-        Ok(resp)
+        self.create_document_ref(&doc, insert_options).await
     }
 
-    /// Read a single document with `_key`
+    /// Create a new document from a borrowed value
     ///
-    /// Returns the document identified by document-id. The returned document
-    /// contains three special attributes: _id containing the document
-    /// identifier, _key containing key which uniquely identifies a document in
-    /// a given collection and _rev containing the revision.
+    /// Same as `create_document`, but the document is only serialized from a
+    /// reference, and the returned new/old documents are deserialized into
+    /// `R`, which does not have to be the type of the input document.
     ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
-    pub async fn document<T>(&self, _key: &str) -> Result<Document<T>, ClientError>
+    pub async fn create_document_ref<T, R>(
+        &self,
+        doc: &T,
+        insert_options: InsertOptions,
+    ) -> Result<DocumentResponse<R>, ClientError>
     where
-        T: Serialize + DeserializeOwned,
+        T: Serialize + ?Sized,
+        R: DeserializeOwned,
     {
-        self.document_with_options(_key, Default::default()).await
+        let resp = self
+            .create_document_with_headers(doc, insert_options)
+            .await?;
+        Ok(resp.into_inner())
     }
 
-    /// Read a single document with options
+    /// Create a new document, returning the headers of the response as well
     ///
-    /// Returns the document identified by document-id. The returned document
-    /// contains three special attributes: _id containing the document
-    /// identifier, _key containing key which uniquely identifies a document in
-    /// a given collection and _rev containing the revision.
+    /// Same as `create_document_ref`. The headers include the `Etag` holding
+    /// the revision and the `Location` of the new document.
     ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
-    pub async fn document_with_options<T>(
+    pub async fn create_document_with_headers<T, R>(
         &self,
-        _key: &str,
-        read_options: ReadOptions,
-    ) -> Result<Document<T>, ClientError>
+        doc: &T,
+        insert_options: InsertOptions,
+    ) -> Result<WithHeaders<DocumentResponse<R>>, ClientError>
     where
-        T: Serialize + DeserializeOwned,
+        T: Serialize + ?Sized,
+        R: DeserializeOwned,
     {
-        let url = self.document_base_url.join(_key).unwrap();
-        let mut build = Request::get(url.to_string());
+        let mut url = self.document_base_url.join("").unwrap();
+        let body = if insert_options.is_strict() {
+            let value = serde_json::to_value(doc)?;
+            check_no_system_attributes(&value)?;
+            serde_json::to_string(&value)?
+        } else {
+            serde_json::to_string(doc)?
+        };
+        let query = serde_qs::to_string(&insert_options).unwrap();
+        url.set_query(Some(query.as_str()));
+        self.apply_default_options(&mut url);
+        let resp = self.session.post(url, body).await;
+        self.invalidate_count();
+        let resp = resp?;
+        let value: DocumentResponse<R> =
+            deserialize_response(resp.body()).map_err(|e| e.in_collection(&self.name))?;
+        Ok(WithHeaders::new(value, resp.headers().clone()))
+    }
+
+    /// Insert a document and return only its header
+    ///
+    /// A leaner alternative to `create_document` for write-mostly workloads:
+    /// the document does not need to be deserializable, and only `_id`,
+    /// `_key` and `_rev` are parsed from the response. In silent mode, they
+    /// are read from the `Location` and `Etag` headers instead.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn insert<T>(
+        &self,
+        doc: &T,
+        insert_options: InsertOptions,
+    ) -> Result<Header, ClientError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let WithHeaders { value, headers } = self
+            .create_document_with_headers::<T, IgnoredAny>(doc, insert_options)
+            .await?;
+        if let DocumentResponse::Response { header, .. } = value {
+            return Ok(header);
+        }
 
-        let header = make_header_from_options(read_options);
-        if let Some(h) = header {
-            build = build.header(h.0, h.1)
+        let header_str =
+            |name: http::header::HeaderName| headers.get(name).and_then(|v| v.to_str().ok());
+        let key = header_str(LOCATION)
+            .and_then(|location| location.rsplit('/').next())
+            .map(percent_decode);
+        let rev = header_str(ETAG).map(|etag| etag.trim_matches('"').to_owned());
+        match (key, rev) {
+            (Some(_key), Some(_rev)) => Ok(Header {
+                _id: format!("{}/{}", self.name, _key),
+                _key,
+                _rev,
+            }),
+            _ => Err(ClientError::InvalidServer(
+                "response misses the Location or Etag header".to_owned(),
+            )),
         }
-        let req = build.body("".to_string()).unwrap();
-        let resp: Document<T> = deserialize_response(self.session.request(req).await?.body())?;
-        Ok(resp)
     }
 
-    /// Read a single document header
+    /// Insert a document and return only its `_key`
     ///
-    /// Like GET, but only returns the header fields and not the body. You can
-    /// use this call to get the current revision of a document or check if the
-    /// document was deleted.
+    /// Handy to reference documents whose key is generated by the server.
+    /// Combined with `InsertOptions::silent`, the key is read from the
+    /// `Location` header and the response has no body to parse at all.
     ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
-    pub async fn document_header(&self, _key: &str) -> Result<Header, ClientError> {
-        self.document_header_with_options(_key, Default::default())
-            .await
+    pub async fn insert_get_key<T>(
+        &self,
+        doc: &T,
+        insert_options: InsertOptions,
+    ) -> Result<String, ClientError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let header = self.insert(doc, insert_options).await?;
+        Ok(header._key)
     }
 
-    /// Read a single document header with options
+    /// Create many documents in a single request
     ///
-    /// Like GET, but only returns the header fields and not the body. You can
-    /// use this call to get the current revision of a document or check if the
-    /// document was deleted.
+    /// The result has one entry per document, in the order of `docs`. A
+    /// document rejected by the server, e.g. because its `_key` is already
+    /// used, gets an error of its own and does not fail the other ones. In
+    /// silent mode, the server only reports the rejected documents, hence the
+    /// entries cannot be matched with `docs` anymore.
     ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
-    pub async fn document_header_with_options(
+    pub async fn create_documents<T, R>(
         &self,
-        _key: &str,
-        read_options: ReadOptions,
-    ) -> Result<Header, ClientError> {
-        let url = self.document_base_url.join(_key).unwrap();
-        let mut build = Request::get(url.to_string());
+        docs: &[T],
+        insert_options: InsertOptions,
+    ) -> Result<Vec<Result<DocumentResponse<R>, ClientError>>, ClientError>
+    where
+        T: Serialize,
+        R: DeserializeOwned,
+    {
+        let mut url = self.document_base_url.join("").unwrap();
+        let body = serde_json::to_string(docs)?;
+        let query = serde_qs::to_string(&insert_options).unwrap();
+        url.set_query(Some(query.as_str()));
+        self.apply_default_options(&mut url);
+        let resp = self.session.post(url, body).await;
+        self.invalidate_count();
+        self.deserialize_bulk_response(resp?.body())
+    }
 
-        let header = make_header_from_options(read_options);
-        if let Some(h) = header {
-            build = build.header(h.0, h.1)
-        }
-        let req = build.body("".to_string()).unwrap();
-        let resp: Header = deserialize_response(self.session.request(req).await?.body())?;
-        Ok(resp)
+    /// Insert the documents of a stream, `batch_size` documents per request
+    ///
+    /// The documents are inserted with `create_documents` once `batch_size`
+    /// of them are collected, or the stream ended. The returned stream yields
+    /// the result of every document, in order. When a whole request fails,
+    /// e.g. because the server cannot be reached, a single error is yielded
+    /// for its batch and the next batch is tried.
+    ///
+    /// The input is only pulled when the returned stream is: a batch is not
+    /// collected before the request of the previous one completed, so a
+    /// slow server slows down the producer instead of buffering documents.
+    ///
+    /// Only available with the async clients.
+    ///
+    /// # Panics
+    /// Panics if `batch_size` is 0.
+    #[cfg(not(feature = "blocking"))]
+    pub fn insert_stream<'s, S, T, R>(
+        &'s self,
+        docs: S,
+        batch_size: usize,
+        insert_options: InsertOptions,
+    ) -> impl Stream<Item = Result<DocumentResponse<R>, ClientError>> + 's
+    where
+        S: Stream<Item = T> + 's,
+        T: Serialize + 's,
+        R: DeserializeOwned + 's,
+    {
+        docs.chunks(batch_size)
+            .then(move |batch| {
+                let insert_options = insert_options.clone();
+                async move { self.create_documents(&batch, insert_options).await }
+            })
+            .flat_map(|results| {
+                stream::iter(match results {
+                    Ok(results) => results,
+                    Err(e) => vec![Err(e)],
+                })
+            })
     }
-    /// Partially update a document
+
+    /// Create a new document expiring after `ttl`
+    ///
+    /// `field` is set to the current time plus `ttl`, in seconds since the
+    /// Unix epoch, before inserting the document. Combined with a TTL index
+    /// on `field` with `expire_after: 0`, the server removes the document
+    /// once it expired.
+    ///
+    /// The document must serialize to a JSON object, otherwise a
+    /// `ClientError::InvalidDocumentBody` is returned before any request is
+    /// made. A value already stored in `field` is overwritten.
     ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
-    pub async fn update_document<T>(
+    pub async fn create_document_with_ttl<T>(
         &self,
-        _key: &str,
         doc: T,
-        update_options: UpdateOptions,
+        ttl: Duration,
+        field: &str,
+        insert_options: InsertOptions,
     ) -> Result<DocumentResponse<T>, ClientError>
     where
         T: Serialize + DeserializeOwned,
     {
-        let mut url = self.document_base_url.join(_key).unwrap();
-        let body = serde_json::to_string(&doc)?;
-        let query = serde_qs::to_string(&update_options).unwrap();
-        url.set_query(Some(query.as_str()));
-
-        let resp: DocumentResponse<T> =
-            deserialize_response(self.session.patch(url, body).await?.body())?;
-        Ok(resp)
+        let mut body = serde_json::to_value(&doc)?;
+        let object = body.as_object_mut().ok_or_else(|| {
+            ClientError::InvalidDocumentBody("document should be a json object".to_owned())
+        })?;
+        let expires_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            + ttl;
+        object.insert(field.to_owned(), expires_at.as_secs().into());
+        self.create_document_ref(&body, insert_options).await
     }
 
-    /// Replace a document
+    /// Import edges in bulk, e.g. to load a graph
     ///
-    /// Replaces the specified document with the one in the body, provided there
-    /// is such a document and no precondition is violated.
+    /// Every edge is given as a `(from, to, payload)` tuple, where `from`
+    /// and `to` are document handles like `vertices/alice`. The payload must
+    /// serialize to a json object, `_from` and `_to` are set on it.
     ///
-    /// The value of the _key attribute as well as attributes used as sharding
-    /// keys may not be changed.
+    /// All edges are sent in a single request to the import endpoint, which
+    /// is much faster than inserting them one by one. Edges that could not
+    /// be imported are counted in `ImportResult::errors`, unless
+    /// `ImportOptions::complete` is set.
     ///
-    /// If the If-Match header is specified and the revision of the document in
-    /// the database is unequal to the given revision, the precondition is
-    /// violated. If If-Match is not given and ignoreRevs is false and there
-    /// is a _rev attribute in the body and its value does not match the
-    /// revision of the document in the database, the precondition is violated.
-    /// If a precondition is violated, an HTTP 412 is returned.
+    /// A `ClientError::InvalidDocumentBody` is returned, before any request
+    /// is made, when a handle is not prefixed by a collection name or a
+    /// payload is not an object.
     ///
-    /// If the document exists and can be updated, then an HTTP 201 or an HTTP
-    /// 202 is returned (depending on waitForSync, see below), the Etag header
-    /// field contains the new revision of the document and the Location header
-    /// contains a complete URL under which the document can be queried.
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn import_edges<S, T>(
+        &self,
+        edges: &[(S, S, T)],
+        options: ImportOptions,
+    ) -> Result<ImportResult, ClientError>
+    where
+        S: AsRef<str>,
+        T: Serialize,
+    {
+        let mut documents = Vec::with_capacity(edges.len());
+        for (from, to, payload) in edges {
+            let mut edge = serde_json::to_value(payload)?;
+            let object = edge.as_object_mut().ok_or_else(|| {
+                ClientError::InvalidDocumentBody("edge payload should be a json object".to_owned())
+            })?;
+            object.insert("_from".to_owned(), document_handle(from.as_ref())?.into());
+            object.insert("_to".to_owned(), document_handle(to.as_ref())?.into());
+            documents.push(edge);
+        }
+
+        let mut url = self.base_url.join("../../import").unwrap();
+        url.set_query(Some(&serde_qs::to_string(&options).unwrap()));
+        self.apply_default_options(&mut url);
+        url.query_pairs_mut()
+            .append_pair("collection", &self.name)
+            .append_pair("type", "list");
+        let body = serde_json::to_string(&documents)?;
+        let resp = self.session.post(url, body).await;
+        self.invalidate_count();
+        deserialize_response(resp?.body())
+    }
+
+    /// Read a single document with `_key`
+    ///
+    /// Returns the document identified by document-id. The returned document
+    /// contains three special attributes: _id containing the document
+    /// identifier, _key containing key which uniquely identifies a document in
+    /// a given collection and _rev containing the revision.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document<T>(&self, _key: &str) -> Result<Document<T>, ClientError>
+    where
+        T: DeserializeOwned,
+    {
+        self.document_with_options(_key, Default::default()).await
+    }
+
+    /// Read a single document with options
+    ///
+    /// Returns the document identified by document-id. The returned document
+    /// contains three special attributes: _id containing the document
+    /// identifier, _key containing key which uniquely identifies a document in
+    /// a given collection and _rev containing the revision.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_with_options<T>(
+        &self,
+        _key: &str,
+        read_options: ReadOptions,
+    ) -> Result<Document<T>, ClientError>
+    where
+        T: DeserializeOwned,
+    {
+        let resp = self.document_with_headers::<T>(_key, read_options).await?;
+        Ok(resp.into_inner())
+    }
+
+    /// Read a single document from a follower, falling back to the leader
+    /// when the follower does not know it yet
+    ///
+    /// A dirty read, see `ReadOptions::allow_dirty_read`, spares the leader
+    /// but may miss a document written right before. Reading again from the
+    /// leader on a miss makes the writes of the caller visible to its reads.
+    /// An outdated revision returned by the follower is not detected though.
+    ///
+    /// # Note
+    /// this function would make one or two requests to arango server.
+    #[maybe_async]
+    pub async fn read_document_consistent<T>(&self, _key: &str) -> Result<Document<T>, ClientError>
+    where
+        T: DeserializeOwned,
+    {
+        let dirty = ReadOptions::builder().allow_dirty_read(true).build();
+        match self.document_with_options(_key, dirty).await {
+            Err(e) if e.is_document_not_found() => self.document(_key).await,
+            result => result,
+        }
+    }
+
+    /// Read a single document with options, returning the headers of the
+    /// response as well
+    ///
+    /// Same as `document_with_options`. The `Etag` header holds the revision
+    /// of the document, handy to cache it and read it again with
+    /// `ReadOptions::builder().if_none_match(..)`.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_with_headers<T>(
+        &self,
+        _key: &str,
+        read_options: ReadOptions,
+    ) -> Result<WithHeaders<Document<T>>, ClientError>
+    where
+        T: DeserializeOwned,
+    {
+        let url = self.key_url(_key);
+        let mut build = Request::get(url.to_string());
+
+        if let Some(headers) = build.headers_mut() {
+            headers.extend(make_header_from_options(read_options));
+        }
+        let req = build.body("".to_string()).unwrap();
+        let resp = self.session.request(req).await?;
+        let value: Document<T> = deserialize_response(resp.body())?;
+        Ok(WithHeaders::new(value, resp.headers().clone()))
+    }
+
+    /// Read a single document as the raw JSON body sent by the server
+    ///
+    /// Handy to pass documents through unchanged, e.g. in a gateway, without
+    /// deserializing and serializing them again. Errors, including a failed
+    /// condition of `read_options` (HTTP 412), are returned as such rather
+    /// than as a body.
+    ///
+    /// `None` is returned when the document still has the revision given as
+    /// `if_none_match` in `read_options`, i.e. the server answered `304 Not
+    /// Modified` without any body.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn read_document_raw(
+        &self,
+        _key: &str,
+        read_options: ReadOptions,
+    ) -> Result<Option<Vec<u8>>, ClientError> {
+        let url = self.key_url(_key);
+        let mut build = Request::get(url.to_string());
+        if let Some(headers) = build.headers_mut() {
+            headers.extend(make_header_from_options(read_options));
+        }
+        let req = build.body("".to_string()).unwrap();
+        let resp = self.session.request(req).await?;
+
+        let status = resp.status();
+        if status == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            deserialize_response::<Value>(resp.body())?;
+            // no error in the body, hence no error number either
+            return Err(ArangoError {
+                code: status.as_u16(),
+                error_num: 0,
+                message: format!("failed to read document {}: {}", _key, status),
+            }
+            .into());
+        }
+        Ok(Some(resp.into_body().into_bytes()))
+    }
+
+    /// Read multiple documents with `keys` in a single request
+    ///
+    /// The result has one entry per key, in the order of `keys`, so that it
+    /// can be zipped with the input. The entry is `None` when the document
+    /// does not exist (or could not be read).
+    ///
+    /// The server does not guarantee the order of the documents it returns,
+    /// hence they are reordered by `_key` once fetched.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn read_documents<T>(
+        &self,
+        keys: &[&str],
+    ) -> Result<Vec<Option<Document<T>>>, ClientError>
+    where
+        T: DeserializeOwned,
+    {
+        let mut url = self.document_base_url.join("").unwrap();
+        url.query_pairs_mut().append_pair("onlyget", "true");
+        let body = serde_json::to_string(keys)?;
+        let resp = self.session.put(url, body).await?;
+        let results: Vec<Value> = deserialize_array_response(resp.body())?;
+
+        let mut by_key: HashMap<String, Value> = results
+            .into_iter()
+            // a document that could not be read is sent as an error stub,
+            // which may carry the `_key` it was asked for
+            .filter(|doc| doc.get("error").and_then(Value::as_bool) != Some(true))
+            .filter_map(|doc| {
+                let key = doc.get("_key")?.as_str()?.to_owned();
+                Some((key, doc))
+            })
+            .collect();
+        // how many times each key is still to be placed
+        let mut remaining: HashMap<&str, usize> = HashMap::new();
+        for key in keys {
+            *remaining.entry(key).or_default() += 1;
+        }
+        let mut documents = Vec::with_capacity(keys.len());
+        for key in keys {
+            let count = remaining.get_mut(key).unwrap();
+            *count -= 1;
+            // keep a copy when the same key is asked for again later on
+            let doc = if *count > 0 {
+                by_key.get(*key).cloned()
+            } else {
+                by_key.remove(*key)
+            };
+            documents.push(doc.map(serde_json::from_value).transpose()?);
+        }
+        Ok(documents)
+    }
+
+    /// Read a single document header
+    ///
+    /// Like GET, but only returns the header fields and not the body. You can
+    /// use this call to get the current revision of a document or check if the
+    /// document was deleted.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_header(&self, _key: &str) -> Result<Header, ClientError> {
+        self.document_header_with_options(_key, Default::default())
+            .await
+    }
+
+    /// Read a single document header with options
+    ///
+    /// Like GET, but only returns the header fields and not the body. You can
+    /// use this call to get the current revision of a document or check if the
+    /// document was deleted.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_header_with_options(
+        &self,
+        _key: &str,
+        read_options: ReadOptions,
+    ) -> Result<Header, ClientError> {
+        let url = self.key_url(_key);
+        let mut build = Request::get(url.to_string());
+
+        if let Some(headers) = build.headers_mut() {
+            headers.extend(make_header_from_options(read_options));
+        }
+        let req = build.body("".to_string()).unwrap();
+        let resp: Header = deserialize_response(self.session.request(req).await?.body())?;
+        Ok(resp)
+    }
+
+    /// Get the current revision of a document
+    ///
+    /// A HEAD request is sent and the revision read from the `Etag` header of
+    /// the response, hence neither the body nor the other header fields are
+    /// transferred.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_revision(&self, _key: &str) -> Result<String, ClientError> {
+        let rev = self.head_document(_key, None).await?;
+        // the server only answers `304 Not Modified` to an `If-None-Match`
+        Ok(rev.unwrap_or_default())
+    }
+
+    /// Same as `document_revision`, but returns `None` when the document
+    /// does not exist.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn try_document_revision(&self, _key: &str) -> Result<Option<String>, ClientError> {
+        match self.document_revision(_key).await {
+            Ok(rev) => Ok(Some(rev)),
+            Err(e) if e.is_document_not_found() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Check whether a document changed since revision `known_rev`
+    ///
+    /// Same as `document_revision_if_changed`, when only the outcome
+    /// matters.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_changed(&self, _key: &str, known_rev: &str) -> Result<bool, ClientError> {
+        let rev = self.document_revision_if_changed(_key, known_rev).await?;
+        Ok(rev.is_some())
+    }
+
+    /// Get the current revision of a document, unless it is still
+    /// `known_rev`
+    ///
+    /// A HEAD request is sent with an `If-None-Match` header, so that the
+    /// server answers `304 Not Modified` without any body when the document
+    /// did not change, in which case `None` is returned. Otherwise the new
+    /// revision is read from the `Etag` header of the response.
+    ///
+    /// This is the cheapest way to find out whether a cached document is
+    /// stale.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_revision_if_changed(
+        &self,
+        _key: &str,
+        known_rev: &str,
+    ) -> Result<Option<String>, ClientError> {
+        self.head_document(_key, Some(known_rev)).await
+    }
+
+    /// Send a HEAD request for a document and read its revision from the
+    /// `Etag` header, or `None` if it is still `known_rev`.
+    #[maybe_async]
+    async fn head_document(
+        &self,
+        _key: &str,
+        known_rev: Option<&str>,
+    ) -> Result<Option<String>, ClientError> {
+        let url = self.key_url(_key);
+        let mut build = Request::head(url.to_string());
+        if let Some(known_rev) = known_rev {
+            build = build.header(
+                IF_NONE_MATCH,
+                format!("\"{}\"", known_rev.trim_matches('"')),
+            );
+        }
+        let req = build.body("".to_string()).unwrap();
+        let resp = self.session.request(req).await?;
+
+        // a response to a HEAD request has no body, hence the outcome is only
+        // given by the status
+        let status = resp.status();
+        if status == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            // the error numbers the server uses for these statuses, 0 for
+            // the others, which have no known error number
+            let error_num = match status {
+                StatusCode::NOT_FOUND => ERROR_DOCUMENT_NOT_FOUND,
+                StatusCode::PRECONDITION_FAILED => ERROR_CONFLICT,
+                _ => 0,
+            };
+            return Err(ArangoError {
+                code: status.as_u16(),
+                error_num,
+                message: format!("failed to read the header of document {}: {}", _key, status),
+            }
+            .into());
+        }
+        let rev = resp
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(|etag| etag.trim_matches('"').to_owned())
+            .ok_or_else(|| ClientError::InvalidServer("missing Etag header".to_owned()))?;
+        Ok(Some(rev))
+    }
+
+    /// Partially update a document
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn update_document<T>(
+        &self,
+        _key: &str,
+        doc: T,
+        update_options: UpdateOptions,
+    ) -> Result<DocumentResponse<T>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        self.update_document_ref(_key, &doc, update_options).await
+    }
+
+    /// Partially update a document from a borrowed value
+    ///
+    /// Same as `update_document`, but the patch and the returned documents
+    /// can have different types.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn update_document_ref<T, R>(
+        &self,
+        _key: &str,
+        doc: &T,
+        update_options: UpdateOptions,
+    ) -> Result<DocumentResponse<R>, ClientError>
+    where
+        T: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        let mut url = self.key_url(_key);
+        let body = serde_json::to_string(doc)?;
+        let query = serde_qs::to_string(&update_options).unwrap();
+        url.set_query(Some(query.as_str()));
+        self.apply_default_options(&mut url);
+        let resp = self.session.patch(url, body).await;
+        self.invalidate_count();
+
+        let resp: DocumentResponse<R> =
+            deserialize_response(resp?.body()).map_err(|e| e.in_collection(&self.name))?;
+        Ok(resp)
+    }
+
+    /// Replace a document
+    ///
+    /// Replaces the specified document with the one in the body, provided there
+    /// is such a document and no precondition is violated.
+    ///
+    /// The value of the _key attribute as well as attributes used as sharding
+    /// keys may not be changed.
+    ///
+    /// If the If-Match header is specified and the revision of the document in
+    /// the database is unequal to the given revision, the precondition is
+    /// violated. If If-Match is not given and ignoreRevs is false and there
+    /// is a _rev attribute in the body and its value does not match the
+    /// revision of the document in the database, the precondition is violated.
+    /// If a precondition is violated, an HTTP 412 is returned.
+    ///
+    /// If the document exists and can be updated, then an HTTP 201 or an HTTP
+    /// 202 is returned (depending on waitForSync, see below), the Etag header
+    /// field contains the new revision of the document and the Location header
+    /// contains a complete URL under which the document can be queried.
     ///
     /// Cluster only: The replace documents may contain values for the
     /// collection’s pre-defined shard keys. Values for the shard keys are
@@ -705,10 +1657,34 @@ impl<'a, C: ClientExt> Collection<C> {
     where
         T: Serialize + DeserializeOwned,
     {
-        let mut url = self.document_base_url.join(_key).unwrap();
-        let body = serde_json::to_string(&doc)?;
+        self.replace_document_ref(_key, &doc, replace_options, if_match_header)
+            .await
+    }
+
+    /// Replace a document from a borrowed value
+    ///
+    /// Same as `replace_document`, but the new document and the returned
+    /// documents can have different types.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn replace_document_ref<T, R>(
+        &self,
+        _key: &str,
+        doc: &T,
+        replace_options: ReplaceOptions,
+        if_match_header: Option<String>,
+    ) -> Result<DocumentResponse<R>, ClientError>
+    where
+        T: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        let mut url = self.key_url(_key);
+        let body = serde_json::to_string(doc)?;
         let query = serde_qs::to_string(&replace_options).unwrap();
         url.set_query(Some(query.as_str()));
+        self.apply_default_options(&mut url);
 
         let mut build = Request::put(url.to_string());
 
@@ -717,12 +1693,63 @@ impl<'a, C: ClientExt> Collection<C> {
         }
 
         let req = build.body(body).unwrap();
+        let resp = self.session.request(req).await;
+        self.invalidate_count();
 
-        let resp: DocumentResponse<T> =
-            deserialize_response(self.session.request(req).await?.body())?;
+        let resp: DocumentResponse<R> =
+            deserialize_response(resp?.body()).map_err(|e| e.in_collection(&self.name))?;
         Ok(resp)
     }
 
+    /// Replace many documents in a single request
+    ///
+    /// Every document must carry the `_key` of the document it replaces.
+    /// With `ignore_revs(false)`, the `_rev` of a document is checked against
+    /// the current revision, and a document whose revision changed is
+    /// rejected.
+    ///
+    /// The result has one entry per document, in the order of `docs`. A
+    /// document rejected by the server, e.g. because it does not exist, gets
+    /// an error of its own and does not fail the other ones.
+    ///
+    /// A `ClientError::InvalidDocumentBody` is returned, before any request
+    /// is made, when a document is not an object with a `_key`.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn replace_documents<T, R>(
+        &self,
+        docs: &[T],
+        replace_options: ReplaceOptions,
+    ) -> Result<Vec<Result<DocumentResponse<R>, ClientError>>, ClientError>
+    where
+        T: Serialize,
+        R: DeserializeOwned,
+    {
+        let docs = docs
+            .iter()
+            .map(|doc| {
+                let doc = serde_json::to_value(doc)?;
+                match doc.get("_key") {
+                    Some(Value::String(_)) => Ok(doc),
+                    _ => Err(ClientError::InvalidDocumentBody(
+                        "document to replace should be an object with a _key".to_owned(),
+                    )),
+                }
+            })
+            .collect::<Result<Vec<Value>, ClientError>>()?;
+
+        let mut url = self.document_base_url.join("").unwrap();
+        let body = serde_json::to_string(&docs)?;
+        let query = serde_qs::to_string(&replace_options).unwrap();
+        url.set_query(Some(query.as_str()));
+        self.apply_default_options(&mut url);
+        let resp = self.session.put(url, body).await;
+        self.invalidate_count();
+        self.deserialize_bulk_response(resp?.body())
+    }
+
     /// Remove a document
     ///
     /// If silent is not set to true, the body of the response contains a JSON
@@ -754,11 +1781,12 @@ impl<'a, C: ClientExt> Collection<C> {
         if_match_header: Option<String>,
     ) -> Result<DocumentResponse<T>, ClientError>
     where
-        T: Serialize + DeserializeOwned,
+        T: DeserializeOwned,
     {
-        let mut url = self.document_base_url.join(_key).unwrap();
+        let mut url = self.key_url(_key);
         let query = serde_qs::to_string(&remove_options).unwrap();
         url.set_query(Some(query.as_str()));
+        self.apply_default_options(&mut url);
 
         let mut build = Request::delete(url.to_string());
 
@@ -767,12 +1795,358 @@ impl<'a, C: ClientExt> Collection<C> {
         }
 
         let req = build.body("".to_string()).unwrap();
+        let resp = self.session.request(req).await;
+        self.invalidate_count();
 
-        let resp: DocumentResponse<T> =
-            deserialize_response(self.session.request(req).await?.body())?;
+        let resp: DocumentResponse<T> = deserialize_response(resp?.body())?;
         Ok(resp)
     }
 
+    /// Start building a simple query on this collection
+    ///
+    /// See `Find` for the supported filters.
+    pub fn find(&self) -> Find<'_, C> {
+        Find::new(self)
+    }
+
+    /// Return the documents closest to the given coordinate, sorted by
+    /// distance.
+    ///
+    /// `geo_field` is the attribute holding the location of the documents,
+    /// either a GeoJSON point or a `[longitude, latitude]` array, so that a
+    /// geo index on it with `geo_json: true` is used. At most `limit`
+    /// documents are returned. If `distance_attribute` is given, the distance
+    /// (in meters) to the coordinate is attached to each document under that
+    /// attribute.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn near<T>(
+        &self,
+        geo_field: &str,
+        latitude: f64,
+        longitude: f64,
+        limit: u32,
+        distance_attribute: Option<&str>,
+    ) -> Result<Vec<T>, ClientError>
+    where
+        T: DeserializeOwned,
+    {
+        let query = format!(
+            "FOR d IN @@collection \
+             LET distance = GEO_DISTANCE([@longitude, @latitude], d.@field) \
+             SORT distance LIMIT @limit {}",
+            distance_return(distance_attribute)
+        );
+        let mut bind_vars = self.geo_bind_vars(geo_field, latitude, longitude, distance_attribute);
+        bind_vars.insert("limit", limit.into());
+        self.db().aql_bind_vars(&query, bind_vars).await
+    }
+
+    /// Return the documents within `radius` meters of the given coordinate,
+    /// sorted by distance.
+    ///
+    /// `geo_field` is the attribute holding the location of the documents,
+    /// either a GeoJSON point or a `[longitude, latitude]` array, so that a
+    /// geo index on it with `geo_json: true` is used. If `distance_attribute`
+    /// is given, the distance (in meters) to the coordinate is attached to
+    /// each document under that attribute.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn within<T>(
+        &self,
+        geo_field: &str,
+        latitude: f64,
+        longitude: f64,
+        radius: f64,
+        distance_attribute: Option<&str>,
+    ) -> Result<Vec<T>, ClientError>
+    where
+        T: DeserializeOwned,
+    {
+        let query = format!(
+            "FOR d IN @@collection \
+             LET distance = GEO_DISTANCE([@longitude, @latitude], d.@field) \
+             FILTER distance <= @radius SORT distance {}",
+            distance_return(distance_attribute)
+        );
+        let mut bind_vars = self.geo_bind_vars(geo_field, latitude, longitude, distance_attribute);
+        bind_vars.insert("radius", radius.into());
+        self.db().aql_bind_vars(&query, bind_vars).await
+    }
+
+    /// Bind variables shared by the geo queries of `near` and `within`.
+    fn geo_bind_vars<'b>(
+        &'b self,
+        geo_field: &'b str,
+        latitude: f64,
+        longitude: f64,
+        distance_attribute: Option<&'b str>,
+    ) -> HashMap<&'b str, Value> {
+        let mut bind_vars: HashMap<&str, Value> = HashMap::new();
+        bind_vars.insert("@collection", self.name.as_str().into());
+        bind_vars.insert("field", geo_field.into());
+        bind_vars.insert("latitude", latitude.into());
+        bind_vars.insert("longitude", longitude.into());
+        if let Some(distance) = distance_attribute {
+            bind_vars.insert("distance", distance.into());
+        }
+        bind_vars
+    }
+
+    /// Return the documents whose `field` matches the fulltext `query`.
+    ///
+    /// The query supports the fulltext syntax of ArangoDB, e.g. `prefix:` and
+    /// `complete:` modifiers combined with `,` (and) or `|` (or).
+    ///
+    /// The collection must have a fulltext index on `field`. Otherwise the
+    /// server refuses the query and an `ClientError::Arango` with error number
+    /// 1571 (no suitable fulltext index found) is returned.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn fulltext<T>(
+        &self,
+        field: &str,
+        query: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<T>, ClientError>
+    where
+        T: DeserializeOwned,
+    {
+        let aql = match limit {
+            Some(_) => "FOR d IN FULLTEXT(@@collection, @field, @query, @limit) RETURN d",
+            None => "FOR d IN FULLTEXT(@@collection, @field, @query) RETURN d",
+        };
+        let mut bind_vars: HashMap<&str, Value> = HashMap::new();
+        bind_vars.insert("@collection", self.name.as_str().into());
+        bind_vars.insert("field", field.into());
+        bind_vars.insert("query", query.into());
+        if let Some(limit) = limit {
+            bind_vars.insert("limit", limit.into());
+        }
+        self.db().aql_bind_vars(aql, bind_vars).await
+    }
+
+    /// Return the keys `k` with `lower <= k < upper`, in ascending order.
+    ///
+    /// Keys are compared as strings, so `"10" < "9"`.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn keys_in_range(
+        &self,
+        lower: &str,
+        upper: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<String>, ClientError> {
+        let mut bind_vars: HashMap<&str, Value> = HashMap::new();
+        bind_vars.insert("l", lower.into());
+        bind_vars.insert("u", upper.into());
+        self.keys_matching("d._key >= @l && d._key < @u", bind_vars, limit)
+            .await
+    }
+
+    /// Return the keys starting with `prefix`, in ascending order.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn keys_with_prefix(
+        &self,
+        prefix: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<String>, ClientError> {
+        let mut bind_vars: HashMap<&str, Value> = HashMap::new();
+        bind_vars.insert("prefix", prefix.into());
+        self.keys_matching("STARTS_WITH(d._key, @prefix)", bind_vars, limit)
+            .await
+    }
+
+    #[maybe_async]
+    async fn keys_matching(
+        &self,
+        filter: &str,
+        mut bind_vars: HashMap<&str, Value>,
+        limit: Option<u32>,
+    ) -> Result<Vec<String>, ClientError> {
+        let mut aql = format!("FOR d IN @@collection FILTER {} SORT d._key", filter);
+        if let Some(limit) = limit {
+            aql.push_str(" LIMIT @limit");
+            bind_vars.insert("limit", limit.into());
+        }
+        aql.push_str(" RETURN d._key");
+        bind_vars.insert("@collection", self.name.as_str().into());
+        self.db().aql_bind_vars(&aql, bind_vars).await
+    }
+
+    /// Count the documents per distinct value of the attribute `field`
+    ///
+    /// Documents without `field` are counted under `null`. The values are
+    /// returned in ascending order.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn group_count(&self, field: &str) -> Result<Vec<(Value, u64)>, ClientError> {
+        let aql = "FOR d IN @@collection COLLECT v = d.@field WITH COUNT INTO n RETURN [v, n]";
+        let mut bind_vars: HashMap<&str, Value> = HashMap::new();
+        bind_vars.insert("@collection", self.name.as_str().into());
+        bind_vars.insert("field", field.into());
+        self.db().aql_bind_vars(aql, bind_vars).await
+    }
+
+    /// Insert a document if none matches `search`, update or replace the
+    /// matching one otherwise, atomically
+    ///
+    /// Runs `UPSERT search INSERT insert UPDATE update IN collection`, or
+    /// `REPLACE update` when `replace` is true, and returns the resulting
+    /// document. Unlike the overwrite modes of `create_document`, the
+    /// document can be matched on any attributes, not only on `_key`.
+    ///
+    /// `search`, `insert` and `update` must serialize to json objects. They
+    /// are passed as bind variables. An index on the searched attributes is
+    /// recommended, as the lookup is a regular AQL filter.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_upsert<S, I, U, R>(
+        &self,
+        search: S,
+        insert: I,
+        update: U,
+        replace: bool,
+    ) -> Result<Document<R>, ClientError>
+    where
+        S: Serialize,
+        I: Serialize,
+        U: Serialize,
+        R: DeserializeOwned,
+    {
+        let aql = format!(
+            "UPSERT @search INSERT @insert {} @update IN @@collection RETURN NEW",
+            if replace { "REPLACE" } else { "UPDATE" }
+        );
+        let mut bind_vars: HashMap<&str, Value> = HashMap::new();
+        bind_vars.insert("@collection", self.name.as_str().into());
+        bind_vars.insert("search", serde_json::to_value(search)?);
+        bind_vars.insert("insert", serde_json::to_value(insert)?);
+        bind_vars.insert("update", serde_json::to_value(update)?);
+        let result = self.db().aql_bind_vars(&aql, bind_vars).await;
+        self.invalidate_count();
+        let mut result: Vec<Document<R>> = result?;
+        result.pop().ok_or_else(|| {
+            <serde_json::Error as serde::de::Error>::invalid_length(0, &"one document").into()
+        })
+    }
+
+    /// Stream the operations of a dump of the collection via the
+    /// replication API.
+    ///
+    /// The chunks are fetched with `replication_dump_batch`, every chunk
+    /// starting right after the last entry of the previous one, until the
+    /// server has no more entries. The `from`, `to` and `chunk_size` of
+    /// `options` bound the dump and size its chunks.
+    ///
+    /// A failed chunk yields its error and ends the stream.
+    ///
+    /// With the blocking clients, an iterator is returned instead.
+    #[cfg(not(feature = "blocking"))]
+    pub fn replication_dump(
+        &self,
+        options: DumpOptions,
+    ) -> impl Stream<Item = Result<DumpEntry, ClientError>> + '_ {
+        stream::unfold(CollectionDump::new(options), move |mut dump| async move {
+            loop {
+                if let Some(entry) = dump.next_entry() {
+                    return Some((Ok(entry), dump));
+                }
+                if dump.is_done() {
+                    return None;
+                }
+                match self.replication_dump_batch(dump.options()).await {
+                    Ok(batch) => dump.advance(batch),
+                    Err(e) => {
+                        dump.fail();
+                        return Some((Err(e), dump));
+                    }
+                }
+            }
+        })
+    }
+
+    /// Iterate over the operations of a dump of the collection via the
+    /// replication API.
+    ///
+    /// The chunks are fetched with `replication_dump_batch`, every chunk
+    /// starting right after the last entry of the previous one, until the
+    /// server has no more entries. The `from`, `to` and `chunk_size` of
+    /// `options` bound the dump and size its chunks.
+    ///
+    /// A failed chunk yields its error and ends the iterator.
+    #[cfg(feature = "blocking")]
+    pub fn replication_dump(
+        &self,
+        options: DumpOptions,
+    ) -> impl Iterator<Item = Result<DumpEntry, ClientError>> + '_ {
+        let mut dump = CollectionDump::new(options);
+        std::iter::from_fn(move || loop {
+            if let Some(entry) = dump.next_entry() {
+                return Some(Ok(entry));
+            }
+            if dump.is_done() {
+                return None;
+            }
+            match self.replication_dump_batch(dump.options()) {
+                Ok(batch) => dump.advance(batch),
+                Err(e) => {
+                    dump.fail();
+                    return Some(Err(e));
+                }
+            }
+        })
+    }
+
+    /// Dump a chunk of the content of the collection via the replication API.
+    ///
+    /// Keep fetching chunks, passing `last_included` of the previous chunk as
+    /// `from`, while `check_more` is true.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn replication_dump_batch(
+        &self,
+        options: DumpOptions,
+    ) -> Result<DumpBatch, ClientError> {
+        let mut url = self.db().url().join("_api/replication/dump").unwrap();
+        let query = serde_qs::to_string(&options).unwrap();
+        url.set_query(Some(query.as_str()));
+        url.query_pairs_mut().append_pair("collection", &self.name);
+
+        let resp = self.session.get(url, "").await?;
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_owned())
+        };
+        let last_included = header(LAST_INCLUDED_HEADER).filter(|tick| tick != "0");
+        let check_more = header(CHECK_MORE_HEADER).is_some_and(|v| v == "true");
+
+        Ok(DumpBatch {
+            entries: deserialize_lines(resp.body())?,
+            last_included,
+            check_more,
+        })
+    }
+
     /// Returns a new Collection with its `session` updated with the transaction
     /// id
     pub fn clone_with_transaction(&self, transaction_id: String) -> Result<Self, ClientError> {
@@ -787,23 +2161,84 @@ impl<'a, C: ClientExt> Collection<C> {
     }
 }
 
-/// Create header name and header value from read_options
-fn make_header_from_options(
-    document_read_options: ReadOptions,
-) -> Option<(http::header::HeaderName, http::header::HeaderValue)> {
-    match document_read_options {
-        ReadOptions::IfNoneMatch(value) => Some((
-            "If-None-Match".to_string().parse().unwrap(),
-            http::HeaderValue::try_from(value).unwrap(),
-        )),
+/// `RETURN` clause of the geo queries, attaching the distance to the document
+/// under `distance_attribute`, if given.
+fn distance_return(distance_attribute: Option<&str>) -> &'static str {
+    match distance_attribute {
+        Some(_) => "RETURN MERGE(d, { [@distance]: distance })",
+        None => "RETURN d",
+    }
+}
+
+/// Create the headers of a read request from read_options
+fn make_header_from_options(document_read_options: ReadOptions) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(value) = document_read_options.if_none_match() {
+        headers.insert(IF_NONE_MATCH, HeaderValue::try_from(value).unwrap());
+    }
+    if let Some(value) = document_read_options.if_match() {
+        headers.insert(IF_MATCH, HeaderValue::try_from(value).unwrap());
+    }
+    if let Some(allow) = document_read_options.allow_dirty_read() {
+        headers.insert(
+            "x-arango-allow-dirty-read",
+            HeaderValue::from_static(if allow { "true" } else { "false" }),
+        );
+    }
+    headers
+}
 
-        ReadOptions::IfMatch(value) => Some((
-            "If-Match".to_string().parse().unwrap(),
-            http::HeaderValue::try_from(value).unwrap(),
-        )),
+/// Check that `handle` is a document handle like `collection/key`.
+fn document_handle(handle: &str) -> Result<&str, ClientError> {
+    match handle.split_once('/') {
+        Some((collection, key)) if !collection.is_empty() && !key.is_empty() => Ok(handle),
+        _ => Err(ClientError::InvalidDocumentBody(format!(
+            "invalid document handle `{}`, expected `collection/key`",
+            handle
+        ))),
+    }
+}
 
-        ReadOptions::NoHeader => None,
+/// Check that a document to create does not carry the system attributes
+/// `_id` and `_rev`, which the server would silently ignore.
+fn check_no_system_attributes(doc: &Value) -> Result<(), ClientError> {
+    let present: Vec<&str> = ["_id", "_rev"]
+        .into_iter()
+        .filter(|attribute| doc.get(attribute).is_some())
+        .collect();
+    if present.is_empty() {
+        return Ok(());
     }
+    Err(ClientError::InvalidDocumentBody(format!(
+        "document to create contains {}, remove {} before creating it, e.g. when \
+         inserting a document read before",
+        present.join(" and "),
+        if present.len() == 1 { "it" } else { "them" }
+    )))
+}
+
+/// Decode a percent-encoded segment of an url, e.g. a document key.
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy, Serialize)]
@@ -830,3 +2265,234 @@ impl<'de> Deserialize<'de> for CollectionType {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use http::Response;
+
+    use super::*;
+    use crate::mock::NoClient;
+
+    /// A client answering bulk inserts, recording the size of every batch.
+    ///
+    /// The batch holding the document `{"no": 10}` fails as a whole.
+    #[cfg(not(feature = "blocking"))]
+    #[derive(Debug, Clone)]
+    struct BulkClient(HeaderMap, Arc<Mutex<Vec<usize>>>);
+
+    #[cfg(not(feature = "blocking"))]
+    #[maybe_async]
+    impl ClientExt for BulkClient {
+        fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, uclient::ClientError> {
+            Ok(BulkClient(
+                headers.into().unwrap_or_default(),
+                Default::default(),
+            ))
+        }
+
+        fn headers(&mut self) -> &mut HeaderMap {
+            &mut self.0
+        }
+
+        async fn request(
+            &self,
+            request: Request<String>,
+        ) -> Result<Response<String>, uclient::ClientError> {
+            let docs: Vec<Value> = serde_json::from_str(request.body()).unwrap();
+            self.1.lock().unwrap().push(docs.len());
+            if docs.contains(&json!({ "no": 10 })) {
+                let body = json!({
+                    "error": true,
+                    "code": 500,
+                    "errorNum": 4,
+                    "errorMessage": "internal error",
+                });
+                return Ok(Response::builder()
+                    .status(500)
+                    .body(body.to_string())
+                    .unwrap());
+            }
+            let results: Vec<Value> = docs
+                .iter()
+                .map(|doc| {
+                    json!({
+                        "_id": format!("users/{}", doc["no"]),
+                        "_key": doc["no"].to_string(),
+                        "_rev": "_a",
+                    })
+                })
+                .collect();
+            Ok(Response::new(Value::from(results).to_string()))
+        }
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    #[tokio::test]
+    async fn insert_stream_in_batches() {
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let client = BulkClient(HeaderMap::new(), batches.clone());
+        let session = Arc::new(Session::with_client(client, Default::default()).unwrap());
+        let db = Database::new(
+            "test_db",
+            &Url::parse("http://localhost:8529/").unwrap(),
+            session.clone(),
+        );
+        let coll = Collection::new("users", "1", CollectionType::Document, db.url(), session);
+
+        let docs = stream::iter((0..25).map(|no| json!({ "no": no })));
+        let results: Vec<_> = coll
+            .insert_stream::<_, _, Value>(docs, 10, Default::default())
+            .collect()
+            .await;
+
+        assert_eq!(*batches.lock().unwrap(), vec![10, 10, 5]);
+        // the failed batch yields a single error, the others one result per
+        // document
+        assert_eq!(results.len(), 16);
+        let keys: Vec<_> = results
+            .iter()
+            .filter_map(|result| result.as_ref().ok())
+            .map(|result| result.header().unwrap()._key.clone())
+            .collect();
+        let expected: Vec<_> = (0..10).chain(20..25).map(|no| no.to_string()).collect();
+        assert_eq!(keys, expected);
+        match &results[10] {
+            Err(ClientError::Arango(error)) => assert_eq!(error.error_num(), 4),
+            Err(e) => panic!("expected the error of the batch, got {:?}", e),
+            Ok(_) => panic!("expected the error of the batch"),
+        }
+    }
+
+    /// A client answering count requests with the count it holds.
+    ///
+    /// The collection in the second field is written to while the first
+    /// count is fetched.
+    #[derive(Debug, Clone)]
+    struct CountClient(
+        HeaderMap,
+        Arc<Mutex<Option<Collection<CountClient>>>>,
+        Arc<Mutex<u32>>,
+    );
+
+    #[maybe_async]
+    impl ClientExt for CountClient {
+        fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, uclient::ClientError> {
+            Ok(CountClient(
+                headers.into().unwrap_or_default(),
+                Default::default(),
+                Default::default(),
+            ))
+        }
+
+        fn headers(&mut self) -> &mut HeaderMap {
+            &mut self.0
+        }
+
+        async fn request(
+            &self,
+            _request: Request<String>,
+        ) -> Result<Response<String>, uclient::ClientError> {
+            if let Some(coll) = self.1.lock().unwrap().take() {
+                coll.invalidate_count();
+            }
+            let body = json!({
+                "count": *self.2.lock().unwrap(),
+                "id": "1",
+                "name": "users",
+                "globallyUniqueId": "h1/1",
+                "isSystem": false,
+                "status": 3,
+                "type": 2,
+                "statusString": "loaded",
+                "keyOptions": { "allowUserKeys": true, "type": "traditional" },
+                "waitForSync": false,
+                "writeConcern": 1,
+            });
+            Ok(Response::new(body.to_string()))
+        }
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn count_fetched_during_a_write_is_not_cached() {
+        let client = CountClient::new(None).unwrap();
+        let session = Arc::new(Session::with_client(client.clone(), Default::default()).unwrap());
+        let db_url = Url::parse("http://localhost:8529/_db/test_db/").unwrap();
+        let coll = Collection::new("users", "1", CollectionType::Document, &db_url, session);
+        let max_age = Duration::from_secs(60);
+
+        *client.1.lock().unwrap() = Some(coll.clone());
+        *client.2.lock().unwrap() = 1;
+        let count = coll.count_cached(max_age).await.unwrap();
+        assert_eq!(count, 1);
+
+        *client.2.lock().unwrap() = 2;
+        let count = coll.count_cached(max_age).await.unwrap();
+        assert_eq!(count, 2);
+        *client.2.lock().unwrap() = 3;
+        let count = coll.count_cached(max_age).await.unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn urls_of_ipv6_hosts_and_custom_ports() {
+        let client = NoClient::new(None).unwrap();
+        let session = Arc::new(Session::with_client(client, Default::default()).unwrap());
+        for (host, expected) in [
+            ("http://[::1]:8529/", "http://[::1]:8529"),
+            (
+                "https://[2001:db8::1]:65535/",
+                "https://[2001:db8::1]:65535",
+            ),
+            ("http://[fe80::1]/", "http://[fe80::1]"),
+            ("http://127.0.0.1:1/", "http://127.0.0.1:1"),
+            ("http://[::1]:8529/arango/", "http://[::1]:8529/arango"),
+        ] {
+            let arango_url = Url::parse(host).unwrap();
+            let db = Database::new("test_db", &arango_url, session.clone());
+            assert_eq!(db.url().as_str(), format!("{}/_db/test_db/", expected));
+
+            let coll = Collection::new(
+                "users",
+                "1",
+                CollectionType::Document,
+                db.url(),
+                session.clone(),
+            );
+            assert_eq!(
+                coll.url().as_str(),
+                format!("{}/_db/test_db/_api/collection/users/", expected)
+            );
+            assert_eq!(
+                coll.key_url("a:b").as_str(),
+                format!("{}/_db/test_db/_api/document/users/a:b", expected)
+            );
+            assert_eq!(
+                coll.base_url.join("checksum").unwrap().as_str(),
+                format!("{}/_db/test_db/_api/collection/users/checksum", expected)
+            );
+
+            let db = coll.db();
+            assert_eq!(db.name(), "test_db");
+            assert_eq!(db.url().as_str(), format!("{}/_db/test_db/", expected));
+        }
+    }
+
+    #[test]
+    fn system_attributes_on_create() {
+        assert!(check_no_system_attributes(&json!({ "_key": "a", "name": "b" })).is_ok());
+        assert!(check_no_system_attributes(&json!([{ "_id": "users/a" }])).is_ok());
+
+        let err =
+            check_no_system_attributes(&json!({ "_id": "users/a", "name": "b" })).unwrap_err();
+        assert!(matches!(err, ClientError::InvalidDocumentBody(_)));
+        assert_eq!(
+            err.to_string(),
+            "Invalid document body: document to create contains _id, remove it before \
+             creating it, e.g. when inserting a document read before"
+        );
+
+        let err =
+            check_no_system_attributes(&json!({ "_id": "users/a", "_rev": "_a" })).unwrap_err();
+        assert!(err.to_string().contains("_id and _rev, remove them"));
+    }
+}
@@ -103,7 +103,7 @@ pub struct CreateOptions<'a> {
     /// overriding the sharding strategy does not yet provide a benefit, but it
     /// may later in case other sharding strategies are added.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[builder(default, setter(strip_option))]
+    #[builder(default, setter(into, strip_option))]
     sharding_strategy: Option<String>,
 
     /// whether or not the collection will be compacted (default is true) This
@@ -185,17 +185,21 @@ pub struct CreateOptions<'a> {
     #[builder(default, setter(strip_option))]
     write_concern: Option<usize>,
 
-    /// (The default is ”“): in an Enterprise Edition cluster, this attribute
-    /// binds the specifics of sharding for the newly created collection to
-    /// follow that of a specified existing collection. Note: Using this
-    /// parameter has consequences for the prototype collection. It can no
-    /// longer be dropped, before the sharding-imitating collections are
-    /// dropped. Equally, backups and restores of imitating collections alone
-    /// will generate warnings (which can be overridden) about missing sharding
-    /// prototype.
-    #[cfg(feature = "enterprise")]
+    /// (The default is ”“): in a cluster, this attribute binds the specifics
+    /// of sharding for the newly created collection to follow that of a
+    /// specified existing collection. The shards of both collections are then
+    /// placed on the same DB-Servers, e.g. to co-locate the edges of a graph
+    /// with their vertices so that traversals stay local. The number of
+    /// shards and the replication factor are taken from the prototype.
+    ///
+    /// Note: Using this parameter has consequences for the prototype
+    /// collection. It can no longer be dropped, before the sharding-imitating
+    /// collections are dropped. Equally, backups and restores of imitating
+    /// collections alone will generate warnings (which can be overridden)
+    /// about missing sharding prototype.
+    #[cfg(any(feature = "cluster", feature = "enterprise"))]
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[builder(default, setter(strip_option))]
+    #[builder(default, setter(into, strip_option))]
     distribute_shards_like: Option<String>,
 
     /// In an Enterprise Edition cluster, this attribute determines an attribute
@@ -234,9 +238,12 @@ pub struct KeyOptions {
     pub allow_user_keys: bool,
 
     /// specifies the type of the key generator. The currently available
-    /// generators are traditional and autoincrement.
+    /// generators are `traditional`, `autoincrement`, `uuid` and `padded`.
+    ///
+    /// `autoincrement` is only supported by single servers and on collections
+    /// with a single shard in a cluster.
     #[serde(skip_serializing_if = "Option::is_none", rename = "type")]
-    #[builder(default, setter(strip_option))]
+    #[builder(default, setter(strip_option, into))]
     pub key_type: Option<String>,
 
     /// increment value for autoincrement key generator. Not used for other key
@@ -290,6 +297,69 @@ impl Default for ChecksumOptions {
     }
 }
 
+/// Options for dropping a collection
+#[derive(Debug, Serialize, Deserialize, PartialEq, TypedBuilder)]
+#[builder(doc)]
+#[serde(rename_all = "camelCase")]
+pub struct DropOptions {
+    /// Whether the collection to drop is a system collection. It must be set
+    /// to drop a system collection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    is_system: Option<bool>,
+}
+
+impl Default for DropOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// What to do when an imported document has the `_key` of an existing one
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OnDuplicate {
+    /// Do not import the document and count it as an error. (default)
+    Error,
+    /// Merge the imported attributes into the existing document.
+    Update,
+    /// Replace the existing document.
+    Replace,
+    /// Do not import the document and count it as ignored.
+    Ignore,
+}
+
+/// Options for bulk imports
+#[derive(Debug, Serialize, Deserialize, PartialEq, TypedBuilder)]
+#[builder(doc)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOptions {
+    /// Wait until the documents have been synced to disk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    wait_for_sync: Option<bool>,
+    /// What to do with documents whose `_key` already exists.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    on_duplicate: Option<OnDuplicate>,
+    /// If set to true, the whole import fails when any document cannot be
+    /// imported, and nothing is imported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    complete: Option<bool>,
+    /// If set to true, the result contains a message for every document that
+    /// could not be imported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    details: Option<bool>,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, TypedBuilder)]
 #[builder(doc)]
 #[serde(rename_all = "camelCase")]
@@ -299,6 +369,26 @@ pub struct PropertiesOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     wait_for_sync: Option<bool>,
+    /// Whether the in-memory hash cache for documents should be enabled.
+    /// Worth it for collections read much more often than written. (rocksdb
+    /// only)
+    #[cfg(feature = "rocksdb")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    cache_enabled: Option<bool>,
+    /// Number of copies of each shard required to be in sync for writes to
+    /// succeed. It can not be larger than the replication factor. (cluster
+    /// only)
+    #[cfg(feature = "cluster")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    write_concern: Option<u16>,
+    /// Attributes computed by the server when documents are written. An
+    /// empty list removes all the computed values. (ArangoDB 3.10+)
+    #[cfg(feature = "arango3_10")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    computed_values: Option<Vec<ComputedValue>>,
     /* TODO need to implement this with feature gate between versions maybe
      *  for ArangoDB 3.7
      * schema: Option<SchemaRules>, */
@@ -309,3 +399,47 @@ impl Default for PropertiesOptions {
         Self::builder().build()
     }
 }
+
+/// Write operations on which a computed value is calculated
+#[cfg(feature = "arango3_10")]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ComputeOn {
+    Insert,
+    Update,
+    Replace,
+}
+
+/// An attribute computed by the server from an AQL expression whenever a
+/// document is written, e.g. a normalized copy of a field to search on.
+/// (ArangoDB 3.10+)
+#[cfg(feature = "arango3_10")]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TypedBuilder)]
+#[builder(doc)]
+#[serde(rename_all = "camelCase")]
+pub struct ComputedValue {
+    /// Name of the computed attribute.
+    #[builder(setter(into))]
+    pub name: String,
+    /// AQL expression computing the value, starting with `RETURN`. The
+    /// document is available as `@doc`, e.g. `RETURN LOWER(@doc.name)`.
+    #[builder(setter(into))]
+    pub expression: String,
+    /// Whether the computed value replaces an attribute with the same name
+    /// given in the document.
+    pub overwrite: bool,
+    /// The operations the value is computed on. (default: all of them)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub compute_on: Option<Vec<ComputeOn>>,
+    /// Whether the attribute is stored when the expression evaluates to
+    /// `null`. (default: true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub keep_null: Option<bool>,
+    /// Whether a warning raised by the expression makes the write operation
+    /// fail. (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub fail_on_warning: Option<bool>,
+}
@@ -1,9 +1,13 @@
 //! Types of response related to collection
+#[cfg(feature = "arango3_10")]
+use crate::collection::options::ComputedValue;
 use crate::collection::{options::KeyOptions, CollectionType};
 use serde::{
     de::{Deserializer, Error as DeError},
     Deserialize,
 };
+#[cfg(feature = "cluster")]
+use serde_json::Value;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -11,13 +15,17 @@ pub struct Info {
     pub count: Option<u32>,
     pub id: String,
     pub name: String,
+    /// Unique identifier of the collection across all databases and servers
     pub globally_unique_id: String,
+    /// Whether the collection is a system collection, e.g. `_users`
     pub is_system: bool,
+    /// Load state of the collection
     pub status: Status,
     #[serde(rename = "type")]
     pub collection_type: CollectionType,
 }
 
+/// Load state of a collection, as reported by the numeric `status` attribute
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum Status {
     NewBorn = 1,
@@ -65,7 +73,10 @@ pub struct Details {
     pub key_options: KeyOptions,
     pub wait_for_sync: bool,
     pub write_concern: u16,
-    #[cfg(rocksdb)]
+    /// Whether the in-memory hash cache for documents is enabled. (rocksdb
+    /// only)
+    #[cfg(feature = "rocksdb")]
+    #[serde(default)]
     pub cache_enabled: bool,
     #[cfg(rocksdb)]
     pub object_id: String,
@@ -77,19 +88,118 @@ pub struct Details {
     pub journal_size: usize,
     #[cfg(mmfiles)]
     pub index_buckets: usize,
+    /// The number of shards of the collection. (cluster only)
+    #[cfg(feature = "cluster")]
+    pub number_of_shards: Option<u32>,
+    /// The attributes used to determine the shard of a document. (cluster
+    /// only)
+    #[cfg(feature = "cluster")]
+    pub shard_keys: Option<Vec<String>>,
+    /// Either the number of copies kept of each shard, or `"satellite"`.
+    /// (cluster only)
+    #[cfg(feature = "cluster")]
+    pub replication_factor: Option<Value>,
+    /// The sharding strategy of the collection. (cluster only)
+    #[cfg(feature = "cluster")]
+    pub sharding_strategy: Option<String>,
+    /// The collection whose sharding is followed by this one. (cluster only)
+    #[cfg(feature = "cluster")]
+    pub distribute_shards_like: Option<String>,
+    /// The attributes computed by the server on writes. (ArangoDB 3.10+)
+    #[cfg(feature = "arango3_10")]
+    #[serde(default)]
+    pub computed_values: Option<Vec<ComputedValue>>,
+    /// Whether the collection is synchronized by revision ranges during
+    /// replication, instead of by documents. (ArangoDB 3.7+)
+    pub sync_by_revision: Option<bool>,
+    /// Whether the revision of a document is used as its internal id.
+    /// (ArangoDB 3.7+)
+    pub uses_revisions_as_document_ids: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ArangoIndex {
+    /// The total number of indexes of the collection
     pub count: Option<u32>,
-    pub size: Option<u32>,
+    /// The total memory or disk size of the indexes, in bytes
+    pub size: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Figures {
     pub indexes: ArangoIndex,
+    /// The size of the documents of the collection, excluding indexes, in
+    /// bytes. (rocksdb only)
+    pub documents_size: Option<u64>,
+    /// Whether the in-memory cache is in use. (rocksdb only)
+    pub cache_in_use: Option<bool>,
+    /// The size of the in-memory cache, in bytes. (rocksdb only)
+    pub cache_size: Option<u64>,
+    /// The memory used by the entries of the in-memory cache, in bytes.
+    /// (rocksdb only)
+    pub cache_usage: Option<u64>,
+    /// Details reported by the storage engine, only returned by
+    /// `Collection::statistics_with_details`. (rocksdb only)
+    #[serde(default)]
+    pub engine: Option<EngineFigures>,
+}
+
+/// Outcome of `Collection::load_indexes`
+///
+/// The cache figures are only read by `Collection::load_indexes_with_figures`,
+/// as a snapshot taken after loading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadIndexes {
+    /// Whether the server accepted to load the indexes.
+    pub result: bool,
+    /// The size of the in-memory cache after loading, in bytes. (rocksdb
+    /// only)
+    pub cache_size: Option<u64>,
+    /// The memory used by the cached index entries after loading, in bytes.
+    /// (rocksdb only)
+    pub cache_usage: Option<u64>,
+}
+
+impl LoadIndexes {
+    /// Whether the memory limit likely stopped the loading before all index
+    /// entries were cached, `None` without cache figures.
+    ///
+    /// This is a heuristic, as the server does not report it: the loading is
+    /// taken as truncated when the cache is at least 90% full.
+    pub fn truncated(&self) -> Option<bool> {
+        match (self.cache_size, self.cache_usage) {
+            (Some(0), _) => Some(false),
+            (Some(size), Some(usage)) => Some(usage.saturating_mul(10) >= size.saturating_mul(9)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineFigures {
+    /// The number of documents as counted by the storage engine.
+    pub documents: Option<u64>,
+    /// The compaction of the collection, if reported by the server.
+    pub compaction_status: Option<CompactionStatus>,
+}
+
+/// Progress of the compaction of a collection
+///
+/// Compaction reclaims the disk space of removed documents, so the disk
+/// usage reported after a bulk removal is only accurate once it is done.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactionStatus {
+    /// Whether the collection is being compacted.
+    #[serde(default)]
+    pub in_progress: bool,
+    /// The number of bytes compacted so far.
+    pub bytes_done: Option<u64>,
+    /// The number of bytes to compact in total.
+    pub bytes_total: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -109,10 +219,8 @@ pub struct Statistics {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Revision {
-    // pub uses_revisions_as_document_ids: Option<bool>,
-    // pub sync_by_revision: bool,
     // pub min_revision: u32,
-    // These 3 properties are for Arangodb 3.7
+    // This property is for Arangodb 3.7
     pub revision: String,
     #[serde(flatten)]
     pub info: Info,
@@ -128,3 +236,46 @@ pub struct Checksum {
     #[serde(flatten)]
     pub info: Info,
 }
+
+/// Result of `Collection::drop_with_options`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DroppedCollection {
+    /// The identifier of the dropped collection.
+    pub id: String,
+    /// The name of the dropped collection.
+    pub name: String,
+    /// Whether it was a system collection.
+    pub was_system: bool,
+}
+
+/// Result of `Collection::checksums_match`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChecksumComparison {
+    /// Whether both checksums are equal.
+    pub matches: bool,
+    /// Revision of the collection the comparison was made from.
+    pub revision: String,
+    /// Revision of the other collection.
+    pub other_revision: String,
+}
+
+/// Result of a bulk import
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+    /// The number of documents imported.
+    pub created: u64,
+    /// The number of documents that were not imported due to an error.
+    pub errors: u64,
+    /// The number of empty documents in the input.
+    pub empty: u64,
+    /// The number of updated or replaced documents, see
+    /// `ImportOptions::on_duplicate`.
+    #[serde(default)]
+    pub updated: u64,
+    /// The number of ignored documents, see `ImportOptions::on_duplicate`.
+    #[serde(default)]
+    pub ignored: u64,
+    /// The reason of every error, when `ImportOptions::details` is set.
+    pub details: Option<Vec<String>>,
+}
@@ -31,11 +31,29 @@ pub(crate) enum Auth<'a> {
 
 impl<'a> Auth<'a> {
     pub fn basic(username: &'a str, password: &'a str) -> Auth<'a> {
-        Auth::Basic(Credential { username, password })
+        Auth::Basic(Credential {
+            username,
+            password,
+            database: None,
+        })
     }
 
     pub fn jwt(username: &'a str, password: &'a str) -> Auth<'a> {
-        Auth::Jwt(Credential { username, password })
+        Auth::Jwt(Credential {
+            username,
+            password,
+            database: None,
+        })
+    }
+
+    /// JWT auth requesting the token from the `database` the user is
+    /// authenticated against, instead of `_system`.
+    pub fn jwt_on(database: &'a str, username: &'a str, password: &'a str) -> Auth<'a> {
+        Auth::Jwt(Credential {
+            username,
+            password,
+            database: Some(database),
+        })
     }
 }
 
@@ -46,4 +64,6 @@ pub(crate) struct Credential<'a> {
     pub username: &'a str,
     /// password
     pub password: &'a str,
+    /// database to authenticate against, `_system` if none
+    pub database: Option<&'a str>,
 }
@@ -0,0 +1,167 @@
+//! Requests with a binary body, e.g. the zip bundle of a Foxx service.
+//!
+//! `ClientExt` only sends `String` bodies, hence the HTTP clients supported
+//! by arangors also implement `BinaryClientExt`. A custom client can
+//! implement it as well to upload binary content through
+//! `Session::request_binary`.
+use http::{Request, Response};
+use maybe_async::maybe_async;
+use uclient::{ClientError, ClientExt};
+
+/// An HTTP client able to send a request with a binary body.
+#[maybe_async]
+pub trait BinaryClientExt: ClientExt {
+    /// Send a request whose body is sent as is.
+    ///
+    /// The default headers of the client are added to the request, unless it
+    /// already sets them, as done by `ClientExt::request`.
+    async fn request_binary(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> Result<Response<String>, ClientError>;
+}
+
+#[cfg(any(
+    feature = "reqwest_async",
+    feature = "reqwest_async_rustls",
+    feature = "reqwest_blocking",
+    feature = "reqwest_blocking_rustls"
+))]
+pub(crate) use self::reqwest_client::{reqwest_parts, send_reqwest, ReqwestParts};
+
+#[cfg(any(
+    feature = "reqwest_async",
+    feature = "reqwest_async_rustls",
+    feature = "reqwest_blocking",
+    feature = "reqwest_blocking_rustls"
+))]
+mod reqwest_client {
+    use std::{any::Any, convert::TryInto};
+
+    use http::{HeaderMap, Request, Response};
+    use maybe_async::maybe_async;
+    #[cfg(feature = "blocking")]
+    use reqwest::blocking::{Client, Request as ReqwestRequest};
+    #[cfg(not(feature = "blocking"))]
+    use reqwest::{Client, Request as ReqwestRequest};
+    use uclient::{reqwest::ReqwestClient, ClientError, ClientExt};
+
+    use super::BinaryClientExt;
+    use crate::{connection::tls::TlsClient, error::TransportError};
+
+    #[maybe_async]
+    impl BinaryClientExt for ReqwestClient {
+        async fn request_binary(
+            &self,
+            request: Request<Vec<u8>>,
+        ) -> Result<Response<String>, ClientError> {
+            // the default headers are only reachable through
+            // `ClientExt::headers`, which takes the client mutably
+            let headers = self.clone().headers().clone();
+            send_reqwest(&self.client, &headers, request)
+                .await
+                .map_err(|e| e.error)
+        }
+    }
+
+    /// Get the `reqwest` client and the default headers of a client, if any.
+    pub(crate) type ReqwestParts<C> = fn(&C) -> Option<(&Client, HeaderMap)>;
+
+    /// The `reqwest` client and the default headers of `client`, when it is
+    /// one of the `reqwest` clients arangors can send the requests of itself.
+    pub(crate) fn reqwest_parts<C: ClientExt + 'static>(
+        client: &C,
+    ) -> Option<(&Client, HeaderMap)> {
+        let client = client as &dyn Any;
+        if let Some(client) = client.downcast_ref::<ReqwestClient>() {
+            // the default headers are only reachable through
+            // `ClientExt::headers`, which takes the client mutably
+            return Some((&client.client, client.clone().headers().clone()));
+        }
+        client
+            .downcast_ref::<TlsClient>()
+            .map(|client| (&client.client, client.headers.clone()))
+    }
+
+    /// Send a request with a `reqwest` client, adding the default `headers`
+    /// the request does not set.
+    ///
+    /// Unlike `ClientExt::request`, the error tells how the request failed,
+    /// as read from the `reqwest` error.
+    #[maybe_async]
+    pub(crate) async fn send_reqwest(
+        client: &Client,
+        headers: &HeaderMap,
+        mut request: Request<Vec<u8>>,
+    ) -> Result<Response<String>, TransportError> {
+        let request_headers = request.headers_mut();
+        for (name, value) in headers.iter() {
+            if !request_headers.contains_key(name) {
+                request_headers.insert(name, value.clone());
+            }
+        }
+        let request: ReqwestRequest = request.try_into()?;
+
+        let resp = client.execute(request).await?;
+
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let version = resp.version();
+        let content = resp.text().await?;
+
+        let mut build = http::Response::builder();
+        for (name, value) in headers.iter() {
+            build = build.header(name, value);
+        }
+        build
+            .status(status)
+            .version(version)
+            .body(content)
+            .map_err(|e| ClientError::HttpClient(format!("{:?}", e)).into())
+    }
+}
+
+#[cfg(feature = "surf_async")]
+#[async_trait::async_trait]
+impl BinaryClientExt for uclient::surf::SurfClient {
+    async fn request_binary(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> Result<Response<String>, ClientError> {
+        use std::str::FromStr;
+
+        use surf::http::{headers::HeaderName, Method, Url};
+
+        let invalid = |e: surf::Error| ClientError::HttpClient(format!("{:?}", e));
+        // the default headers are only reachable through `ClientExt::headers`,
+        // which takes the client mutably
+        let headers = self.clone().headers().clone();
+        let (parts, body) = request.into_parts();
+        let method = Method::from_str(parts.method.as_str()).map_err(invalid)?;
+        let url = Url::parse(&parts.uri.to_string())
+            .map_err(|e| ClientError::HttpClient(format!("{:?}", e)))?;
+
+        let mut req = surf::RequestBuilder::new(method, url);
+        let defaults = headers
+            .iter()
+            .filter(|(name, _)| !parts.headers.contains_key(*name));
+        for (name, value) in defaults.chain(parts.headers.iter()) {
+            let value = value
+                .to_str()
+                .map_err(|e| ClientError::HttpClient(format!("{:?}", e)))?;
+            req = req.header(HeaderName::from_str(name.as_str()).map_err(invalid)?, value);
+        }
+
+        let mut resp = req.body(body).await.map_err(invalid)?;
+        let content = resp.body_string().await.map_err(invalid)?;
+
+        let mut build = http::Response::builder().status(u16::from(resp.status()));
+        for (name, values) in resp.iter() {
+            let values: Vec<&str> = values.iter().map(|v| v.as_str()).collect();
+            build = build.header(name.as_str(), values.join(";"));
+        }
+        build
+            .body(content)
+            .map_err(|e| ClientError::HttpClient(format!("{:?}", e)))
+    }
+}
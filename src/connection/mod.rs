@@ -33,6 +33,15 @@
 //! use arangors::Connection;
 //! let conn = Connection::establish_without_auth("http://localhost:8529").await.unwrap();
 //! ```
+//!
+//! ## Protocol
+//! Requests are sent over HTTP with json bodies. Neither the VelocyStream
+//! (VST) transport nor VelocyPack bodies (`application/x-velocypack`) are
+//! supported: the HTTP clients implement `uclient::ClientExt`, whose requests
+//! and responses carry `String` bodies, which cannot hold binary VelocyPack.
+//! `BinaryClientExt` sends binary request bodies, e.g. Foxx bundles, but the
+//! responses are still read as `String`, hence supporting VelocyPack would
+//! first require binary responses.
 
 use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
@@ -40,25 +49,45 @@ use base64::{engine::general_purpose, Engine as _};
 use http::header::{HeaderMap, AUTHORIZATION, SERVER};
 use log::{debug, trace};
 use maybe_async::maybe_async;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use uclient::ClientExt;
 use url::Url;
 
-use crate::{response::ArangoResult, ClientError};
+use crate::{response::ArangoResult, ArangoError, ClientError};
 
-use super::{database::Database, response::deserialize_response};
+use super::{
+    database::{Database, DefaultOptions},
+    response::deserialize_response,
+};
 
 #[cfg(feature = "cluster")]
-use self::options::{ClusterHealth, CreateDatabase, CreateDatabaseOptions};
+use self::options::{
+    ClusterHealth, CreateDatabase, CreateDatabaseOptions, RebalanceOptions, RebalancePlan,
+    RebalanceRequest, RebalanceStatus, ShardMove, REBALANCE_API_VERSION,
+};
 
 use self::{
     auth::Auth,
+    options::{ConnectionOptions, ProxyOptions},
     role::{Admin, Normal},
 };
 
+pub use self::{binary::BinaryClientExt, proxy::ProxyClientExt, session::Session};
+
 mod auth;
+mod binary;
+pub mod observer;
 pub mod options;
+pub mod proxy;
+mod session;
+#[cfg(any(
+    feature = "reqwest_async",
+    feature = "reqwest_async_rustls",
+    feature = "reqwest_blocking",
+    feature = "reqwest_blocking_rustls"
+))]
+pub mod tls;
 
 pub mod role {
     #[derive(Debug)]
@@ -95,9 +124,10 @@ pub type Connection = GenericConnection<uclient::surf::SurfClient>;
 /// It contains a http client, information about authentication, arangodb url.
 #[derive(Debug, Clone)]
 pub struct GenericConnection<C: ClientExt, S = Normal> {
-    session: Arc<C>,
+    session: Arc<Session<C>>,
     arango_url: Url,
     username: String,
+    default_options: DefaultOptions,
     #[allow(dead_code)]
     state: S,
 }
@@ -110,8 +140,20 @@ impl<S, C: ClientExt> GenericConnection<C, S> {
     /// - Connection failed
     /// - SERVER header in response header is not `ArangoDB` or empty
     #[maybe_async]
-    pub async fn validate_server(arango_url: &str) -> Result<(), ClientError> {
-        let client = C::new(None)?;
+    pub async fn validate_server(arango_url: &str) -> Result<(), ClientError>
+    where
+        C: 'static,
+    {
+        let client = Session::with_options(HeaderMap::new(), Default::default())?;
+        Self::validate_server_with(&client, arango_url).await
+    }
+
+    /// Same as `validate_server`, sending the request with `client`.
+    #[maybe_async]
+    async fn validate_server_with(
+        client: &Session<C>,
+        arango_url: &str,
+    ) -> Result<(), ClientError> {
         let resp = client.get(arango_url.parse().unwrap(), "").await?;
         // have `Server` in header
         match resp.headers().get(SERVER) {
@@ -129,6 +171,37 @@ impl<S, C: ClientExt> GenericConnection<C, S> {
         }
     }
 
+    /// Add a hint to a connection error when the server answers with the
+    /// other scheme, e.g. when an `http://` url is given for a server that
+    /// only accepts TLS.
+    #[maybe_async]
+    async fn with_scheme_hint(
+        client: &Session<C>,
+        arango_url: &Url,
+        err: ClientError,
+    ) -> ClientError {
+        // a TLS handshake with a plain HTTP server fails with a TLS error
+        let message = match &err {
+            ClientError::Connection(message) => message.clone(),
+            ClientError::HttpClient(e) => e.to_string(),
+            _ => return err,
+        };
+        let swapped = match swap_scheme(arango_url) {
+            Some(swapped) => swapped,
+            None => return err,
+        };
+        // the endpoints of the session would send the probe with the scheme
+        // that just failed
+        let client = client.without_endpoints();
+        match Self::validate_server_with(&client, swapped.as_str()).await {
+            Ok(()) => ClientError::Connection(format!(
+                "{} (hint: the server answers on {}, the scheme of the url may be wrong)",
+                message, swapped
+            )),
+            Err(_) => err,
+        }
+    }
+
     /// Get url for remote arangoDB server.
     pub fn url(&self) -> &Url {
         &self.arango_url
@@ -139,21 +212,58 @@ impl<S, C: ClientExt> GenericConnection<C, S> {
     /// Users can use this method to get a authorized session to access
     /// arbitrary path on arangoDB Server.
     ///
+    /// The session wraps the HTTP client `C`, which `Session::client` gives
+    /// access to.
+    ///
     /// TODO This method should only be public in this crate when all features
     ///     are implemented.
-    pub fn session(&self) -> Arc<C> {
+    pub fn session(&self) -> Arc<Session<C>> {
         Arc::clone(&self.session)
     }
 
     /// Get database object with name.
     ///
+    /// Returns `ClientError::InsufficientPermission` when the user is not
+    /// allowed to access the database.
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
     pub async fn db(&self, name: &str) -> Result<Database<C>, ClientError> {
-        let db = Database::new(name, self.url(), self.session());
-        db.info().await?;
-        Ok(db)
+        let db = self.db_unchecked(name);
+        match db.info().await {
+            Ok(_) => Ok(db),
+            Err(ClientError::Arango(e)) if e.code() == 401 || e.code() == 403 => {
+                Err(ClientError::InsufficientPermission {
+                    permission: Permission::NoAccess,
+                    operation: format!("access to database {}: {}", name, e),
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Get database object with name, without checking that it exists.
+    ///
+    /// Unlike `db`, no request is made: a database that does not exist, or
+    /// that the user cannot access, is only reported by the first request
+    /// made with the returned object. Useful to switch between many
+    /// databases known to be valid.
+    pub fn db_unchecked(&self, name: &str) -> Database<C> {
+        Database::new(name, self.url(), self.session())
+            .with_default_options(self.default_options.clone())
+    }
+
+    /// Use `default_options` for the requests made through the databases
+    /// obtained from this connection, see `DefaultOptions`.
+    pub fn with_default_options(mut self, default_options: DefaultOptions) -> Self {
+        self.default_options = default_options;
+        self
+    }
+
+    /// Get the options used when a request does not set them itself.
+    pub fn default_options(&self) -> &DefaultOptions {
+        &self.default_options
     }
 
     /// Get a list of accessible database
@@ -167,7 +277,7 @@ impl<S, C: ClientExt> GenericConnection<C, S> {
     pub async fn accessible_databases(&self) -> Result<HashMap<String, Permission>, ClientError> {
         let url = self
             .arango_url
-            .join(&format!("/_api/user/{}/database", &self.username))
+            .join(&format!("_api/user/{}/database", &self.username))
             .unwrap();
         let resp = self.session.get(url, "").await?;
         let result: ArangoResult<HashMap<String, Permission>> = deserialize_response(resp.body())?;
@@ -190,7 +300,7 @@ impl<S, C: ClientExt> GenericConnection<C, S> {
     /// this function would make a request to arango server.
     #[maybe_async]
     pub async fn server_role(&self) -> Result<String, ClientError> {
-        let url = self.arango_url.join("/_admin/server/role").unwrap();
+        let url = self.arango_url.join("_admin/server/role").unwrap();
         let resp = self.session.get(url, "").await?;
         let result: HashMap<String, Value> = deserialize_response(resp.body())?;
 
@@ -205,12 +315,134 @@ impl<S, C: ClientExt> GenericConnection<C, S> {
     #[maybe_async]
     #[cfg(feature = "cluster")]
     pub async fn cluster_health(&self) -> Result<ClusterHealth, ClientError> {
-        let url = self.arango_url.join("/_admin/cluster/health").unwrap();
+        let url = self.arango_url.join("_admin/cluster/health").unwrap();
         let resp = self.session.get(url, "").await?;
         let result: ClusterHealth = deserialize_response(resp.body())?;
 
         Ok(result)
     }
+
+    /// Returns the imbalance of the cluster and the number of shard moves
+    /// in progress. (ArangoDB 3.10+)
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    #[cfg(feature = "cluster")]
+    pub async fn cluster_rebalance_status(&self) -> Result<RebalanceStatus, ClientError> {
+        let url = self.arango_url.join("_admin/cluster/rebalance").unwrap();
+        let resp = self.session.get(url, "").await?;
+        let result: ArangoResult<RebalanceStatus> = deserialize_response(resp.body())?;
+        Ok(result.unwrap())
+    }
+
+    /// Compute the shard moves balancing the cluster, without executing
+    /// them. (ArangoDB 3.10+)
+    ///
+    /// Review the moves and pass them to `cluster_rebalance_execute`.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    #[cfg(feature = "cluster")]
+    pub async fn cluster_rebalance_plan(
+        &self,
+        options: RebalanceOptions,
+    ) -> Result<RebalancePlan, ClientError> {
+        let url = self.arango_url.join("_admin/cluster/rebalance").unwrap();
+        let body = RebalanceRequest {
+            version: REBALANCE_API_VERSION,
+            body: options,
+        };
+        let resp = self
+            .session
+            .post(url, &serde_json::to_string(&body)?)
+            .await?;
+        let result: ArangoResult<RebalancePlan> = deserialize_response(resp.body())?;
+        Ok(result.unwrap())
+    }
+
+    /// Schedule shard moves, usually computed by `cluster_rebalance_plan`.
+    /// (ArangoDB 3.10+)
+    ///
+    /// The moves are executed in the background, follow their progress with
+    /// `cluster_rebalance_status`.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    #[cfg(feature = "cluster")]
+    pub async fn cluster_rebalance_execute(&self, moves: &[ShardMove]) -> Result<(), ClientError> {
+        let url = self
+            .arango_url
+            .join("_admin/cluster/rebalance/execute")
+            .unwrap();
+        let mut body = HashMap::new();
+        body.insert("moves", moves);
+        let body = RebalanceRequest {
+            version: REBALANCE_API_VERSION,
+            body,
+        };
+        let resp = self
+            .session
+            .post(url, &serde_json::to_string(&body)?)
+            .await?;
+        deserialize_response::<Value>(resp.body())?;
+        Ok(())
+    }
+
+    /// Run JavaScript code on the server and return the value it returns.
+    ///
+    /// The code is the body of a function, hence it must use `return` to
+    /// hand a value back, e.g. `return db._version();`. The returned value
+    /// is serialized as json by the server and deserialized into `T`.
+    ///
+    /// The endpoint `/_admin/execute` is disabled unless the server is
+    /// started with `--javascript.allow-admin-execute true`, a
+    /// `ClientError::Arango` with code 404 is returned otherwise.
+    ///
+    /// # Security
+    /// The code runs with the privileges of the server process: it can read
+    /// and modify any database and access the file system of the server.
+    /// Never pass code built from untrusted input, and only enable the
+    /// endpoint on servers where the users allowed to call it are trusted.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn admin_execute<T>(&self, code: &str) -> Result<T, ClientError>
+    where
+        T: DeserializeOwned,
+    {
+        let url = self
+            .arango_url
+            .join("_admin/execute?returnAsJSON=true")
+            .unwrap();
+        let resp = self.session.post(url, code).await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            // the error may come without a json body, e.g. from a proxy
+            let mut err: ArangoError =
+                serde_json::from_str(resp.body()).unwrap_or_else(|_| ArangoError {
+                    code: status.as_u16(),
+                    error_num: 0,
+                    message: format!("failed to execute code: {}", status),
+                });
+            if err.code == 404 {
+                err.message = format!(
+                    "{} (/_admin/execute requires the server to be started with \
+                     --javascript.allow-admin-execute true)",
+                    err.message
+                );
+            }
+            return Err(err.into());
+        }
+        if resp.body().trim().is_empty() {
+            return Ok(T::deserialize(Value::Null)?);
+        }
+        Ok(serde_json::from_str(resp.body())?)
+    }
 }
 
 impl<C: ClientExt> GenericConnection<C, Normal> {
@@ -228,14 +460,38 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
     async fn establish<T: Into<String>>(
         arango_url: T,
         auth: Auth<'_>,
-    ) -> Result<GenericConnection<C, Normal>, ClientError> {
-        let url_str = arango_url.into();
-        let arango_url = Url::parse(&url_str)
-            .map_err(|_| ClientError::InvalidServer(format!("invalid url: {}", url_str)))?
-            .join("/")
-            .unwrap();
+    ) -> Result<GenericConnection<C, Normal>, ClientError>
+    where
+        C: 'static,
+    {
+        Self::establish_with_client(arango_url, auth, |headers| {
+            Ok(Session::with_options(headers, Default::default())?)
+        })
+        .await
+    }
+
+    /// Same as `establish`, but lets the caller build the http client from
+    /// the authentication headers.
+    ///
+    /// The server is validated, and the jwt token requested, with a client
+    /// built without authentication headers, so that client level settings
+    /// like TLS apply to these requests as well.
+    #[maybe_async]
+    async fn establish_with_client<T, F>(
+        arango_url: T,
+        auth: Auth<'_>,
+        build_client: F,
+    ) -> Result<GenericConnection<C, Normal>, ClientError>
+    where
+        T: Into<String>,
+        F: Fn(HeaderMap) -> Result<Session<C>, ClientError>,
+    {
+        let arango_url = normalize_url(&arango_url.into())?;
+        let anonymous = build_client(HeaderMap::new())?;
 
-        Self::validate_server(&url_str).await?;
+        if let Err(e) = Self::validate_server_with(&anonymous, arango_url.as_str()).await {
+            return Err(Self::with_scheme_hint(&anonymous, &arango_url, e).await);
+        }
 
         let username: String;
         let authorization = match auth {
@@ -249,7 +505,14 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
             Auth::Jwt(cred) => {
                 username = String::from(cred.username);
 
-                let token = Self::jwt_login(&arango_url, cred.username, cred.password).await?;
+                let token = Self::jwt_login(
+                    &anonymous,
+                    &arango_url,
+                    cred.database,
+                    cred.username,
+                    cred.password,
+                )
+                .await?;
                 Some(format!("Bearer {}", token))
             }
             Auth::None => {
@@ -267,7 +530,8 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
         Ok(GenericConnection {
             arango_url,
             username,
-            session: Arc::new(C::new(headers)?),
+            session: Arc::new(build_client(headers)?),
+            default_options: DefaultOptions::default(),
             state: Normal,
         })
     }
@@ -288,7 +552,10 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
     #[maybe_async]
     pub async fn establish_without_auth<T: Into<String>>(
         arango_url: T,
-    ) -> Result<GenericConnection<C, Normal>, ClientError> {
+    ) -> Result<GenericConnection<C, Normal>, ClientError>
+    where
+        C: 'static,
+    {
         trace!("Establish without auth");
         GenericConnection::establish(arango_url.into(), Auth::None).await
     }
@@ -313,7 +580,10 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
         arango_url: &str,
         username: &str,
         password: &str,
-    ) -> Result<GenericConnection<C, Normal>, ClientError> {
+    ) -> Result<GenericConnection<C, Normal>, ClientError>
+    where
+        C: 'static,
+    {
         trace!("Establish with basic auth");
         GenericConnection::establish(arango_url, Auth::basic(username, password)).await
     }
@@ -343,14 +613,55 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
         arango_url: &str,
         username: &str,
         password: &str,
-    ) -> Result<GenericConnection<C, Normal>, ClientError> {
+    ) -> Result<GenericConnection<C, Normal>, ClientError>
+    where
+        C: 'static,
+    {
         trace!("Establish with jwt");
         GenericConnection::establish(arango_url, Auth::jwt(username, password)).await
     }
 
+    /// Establish connection to ArangoDB sever with jwt authentication,
+    /// authenticating against `auth_database` instead of `_system`.
+    ///
+    /// Useful when the users are managed in a database other than
+    /// `_system`, e.g. in multi-tenant deployments. Access to the databases
+    /// is still checked on `db`.
+    ///
+    /// Example:
+    /// ```rust, ignore
+    /// use arangors::Connection;
+    ///
+    /// let conn = Connection::establish_jwt_with_auth_db(
+    ///     "http://localhost:8529",
+    ///     "tenant_db",
+    ///     "username",
+    ///     "password",
+    /// )
+    /// .await
+    /// .unwrap();
+    /// let db = conn.db("tenant_db").await.unwrap();
+    /// ```
+    #[maybe_async]
+    pub async fn establish_jwt_with_auth_db(
+        arango_url: &str,
+        auth_database: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<GenericConnection<C, Normal>, ClientError>
+    where
+        C: 'static,
+    {
+        trace!("Establish with jwt on database {}", auth_database);
+        GenericConnection::establish(arango_url, Auth::jwt_on(auth_database, username, password))
+            .await
+    }
+
     #[maybe_async]
     async fn jwt_login<T: Into<String>>(
+        client: &Session<C>,
         arango_url: &Url,
+        database: Option<&str>,
         username: T,
         password: T,
     ) -> Result<String, ClientError> {
@@ -358,14 +669,24 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
         struct Jwt {
             pub jwt: String,
         }
-        let url = arango_url.join("/_open/auth").unwrap();
+        let mut url = arango_url.clone();
+        {
+            let mut segments = url
+                .path_segments_mut()
+                .map_err(|_| ClientError::InvalidServer(arango_url.to_string()))?;
+            segments.pop_if_empty();
+            if let Some(database) = database {
+                segments.push("_db").push(database);
+            }
+            segments.push("_open").push("auth");
+        }
 
         let mut map = HashMap::new();
         map.insert("username", username.into());
         map.insert("password", password.into());
 
         let jwt: Jwt = deserialize_response(
-            C::new(None)?
+            client
                 .post(url, &serde_json::to_string(&map)?)
                 .await?
                 .body(),
@@ -403,7 +724,7 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
     pub async fn create_database(&self, name: &str) -> Result<Database<C>, ClientError> {
         let mut map = HashMap::new();
         map.insert("name", name);
-        let url = self.arango_url.join("/_api/database").unwrap();
+        let url = self.arango_url.join("_api/database").unwrap();
 
         let resp = self
             .session
@@ -421,7 +742,7 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
         name: &str,
         options: CreateDatabaseOptions,
     ) -> Result<Database<C>, ClientError> {
-        let url = self.arango_url.join("/_api/database").unwrap();
+        let url = self.arango_url.join("_api/database").unwrap();
         let final_options = CreateDatabase::builder()
             .name(name)
             .options(options)
@@ -442,7 +763,7 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
     /// this function would make a request to arango server.
     #[maybe_async]
     pub async fn drop_database(&self, name: &str) -> Result<(), ClientError> {
-        let url_path = format!("/_api/database/{}", name);
+        let url_path = format!("_api/database/{}", name);
         let url = self.arango_url.join(&url_path).unwrap();
 
         let resp = self.session.delete(url, "").await?;
@@ -469,6 +790,256 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
     }
 }
 
+impl<C: ClientExt, S> GenericConnection<C, S> {
+    /// Close the connection once the requests in flight are complete
+    ///
+    /// New requests are refused right away, including those made through the
+    /// `Database` and `Collection` handles obtained from this connection,
+    /// which fail with `ClientError::ConnectionClosed`.
+    ///
+    /// The HTTP client, and its pool of connections, is not released by
+    /// `close`: the handles share it, hence it is only dropped along with the
+    /// last of them, including the `Arc<Session>` returned by `session`.
+    ///
+    /// The async jobs started through the connection, e.g. with
+    /// `Collection::checksum_async`, keep running on the server, see
+    /// `close_and_cancel_jobs`.
+    #[maybe_async]
+    pub async fn close(self) {
+        self.session.close().await
+    }
+
+    /// Same as `close`, then cancel the async jobs started through the
+    /// connection whose result was not fetched, see
+    /// `Session::close_and_cancel_jobs`.
+    #[maybe_async]
+    pub async fn close_and_cancel_jobs(self) -> Result<(), ClientError> {
+        self.session.close_and_cancel_jobs().await
+    }
+}
+
+impl<C: ClientExt + 'static> GenericConnection<C, Normal> {
+    /// Establish connection to ArangoDB sever without Authentication, applying
+    /// `options` to every request.
+    ///
+    /// See `establish_without_auth`.
+    #[maybe_async]
+    pub async fn establish_without_auth_with_options<T: Into<String>>(
+        arango_url: T,
+        options: ConnectionOptions,
+    ) -> Result<Self, ClientError> {
+        trace!("Establish without auth");
+        Self::establish_with_client(arango_url.into(), Auth::None, |headers| {
+            Ok(Session::with_options(headers, options.clone())?)
+        })
+        .await
+    }
+
+    /// Establish connection to ArangoDB sever with basic auth, applying
+    /// `options` to every request.
+    ///
+    /// Example:
+    /// ```rust
+    /// use arangors::{connection::options::ConnectionOptions, Connection};
+    ///
+    /// # #[cfg_attr(any(feature="reqwest_async"), maybe_async::maybe_async, tokio::main)]
+    /// # #[cfg_attr(any(feature="surf_async"), maybe_async::maybe_async, async_std::main)]
+    /// # #[cfg_attr(feature="blocking", maybe_async::must_be_sync)]
+    /// # async fn main() {
+    /// let options = ConnectionOptions::builder().retries(3).build();
+    /// let conn = Connection::establish_basic_auth_with_options(
+    ///     "http://localhost:8529",
+    ///     "username",
+    ///     "password",
+    ///     options,
+    /// )
+    /// .await
+    /// .unwrap();
+    /// # }
+    /// ```
+    #[maybe_async]
+    pub async fn establish_basic_auth_with_options(
+        arango_url: &str,
+        username: &str,
+        password: &str,
+        options: ConnectionOptions,
+    ) -> Result<Self, ClientError> {
+        trace!("Establish with basic auth");
+        Self::establish_with_client(arango_url, Auth::basic(username, password), |headers| {
+            Ok(Session::with_options(headers, options.clone())?)
+        })
+        .await
+    }
+
+    /// Establish connection to ArangoDB sever with jwt authentication,
+    /// applying `options` to every request.
+    ///
+    /// See `establish_jwt`.
+    #[maybe_async]
+    pub async fn establish_jwt_with_options(
+        arango_url: &str,
+        username: &str,
+        password: &str,
+        options: ConnectionOptions,
+    ) -> Result<Self, ClientError> {
+        trace!("Establish with jwt");
+        Self::establish_with_client(arango_url, Auth::jwt(username, password), |headers| {
+            Ok(Session::with_options(headers, options.clone())?)
+        })
+        .await
+    }
+
+    /// Establish connection to a cluster with jwt authentication, failing
+    /// over between several coordinators.
+    ///
+    /// The coordinators are tried in order until one accepts the connection.
+    /// Afterwards, whenever the current coordinator cannot be reached, the
+    /// request is sent to the next one, in a round-robin manner.
+    ///
+    /// Example:
+    /// ```rust, ignore
+    /// use arangors::Connection;
+    ///
+    /// let conn = Connection::establish_jwt_multi(
+    ///     &["http://coordinator1:8529", "http://coordinator2:8529"],
+    ///     "username",
+    ///     "password",
+    /// )
+    /// .await
+    /// .unwrap();
+    /// ```
+    #[maybe_async]
+    pub async fn establish_jwt_multi(
+        arango_urls: &[&str],
+        username: &str,
+        password: &str,
+    ) -> Result<Self, ClientError> {
+        Self::establish_jwt_multi_with_options(arango_urls, username, password, Default::default())
+            .await
+    }
+
+    /// Same as `establish_jwt_multi`, applying `options` to every request.
+    #[maybe_async]
+    pub async fn establish_jwt_multi_with_options(
+        arango_urls: &[&str],
+        username: &str,
+        password: &str,
+        options: ConnectionOptions,
+    ) -> Result<Self, ClientError> {
+        trace!("Establish with jwt on {} endpoints", arango_urls.len());
+        let endpoints = arango_urls
+            .iter()
+            .map(|url| normalize_url(url))
+            .collect::<Result<Vec<_>, _>>()?;
+        let arango_url = *arango_urls
+            .first()
+            .ok_or_else(|| ClientError::InvalidServer("no endpoint given".to_owned()))?;
+
+        // the session fails over to the next coordinator, for the login as
+        // well as for the requests made afterwards
+        Self::establish_with_client(arango_url, Auth::jwt(username, password), |headers| {
+            Ok(Session::with_options(headers, options.clone())?.with_endpoints(&endpoints, 0)?)
+        })
+        .await
+    }
+}
+
+impl<C: ProxyClientExt + 'static> GenericConnection<C, Normal> {
+    /// Establish connection to ArangoDB sever without Authentication, sending
+    /// every request through `proxy`.
+    ///
+    /// See `establish_without_auth` and the `proxy` module.
+    #[maybe_async]
+    pub async fn establish_without_auth_with_proxy<T: Into<String>>(
+        arango_url: T,
+        options: ConnectionOptions,
+        proxy: ProxyOptions,
+    ) -> Result<Self, ClientError> {
+        trace!("Establish without auth through proxy {}", proxy.url());
+        Self::establish_with_proxy(arango_url.into(), Auth::None, options, proxy).await
+    }
+
+    /// Establish connection to ArangoDB sever with basic auth, sending every
+    /// request through `proxy`.
+    ///
+    /// See `establish_basic_auth` and the `proxy` module.
+    #[maybe_async]
+    pub async fn establish_basic_auth_with_proxy(
+        arango_url: &str,
+        username: &str,
+        password: &str,
+        options: ConnectionOptions,
+        proxy: ProxyOptions,
+    ) -> Result<Self, ClientError> {
+        trace!("Establish with basic auth through proxy {}", proxy.url());
+        Self::establish_with_proxy(arango_url, Auth::basic(username, password), options, proxy)
+            .await
+    }
+
+    /// Establish connection to ArangoDB sever with jwt authentication,
+    /// sending every request through `proxy`.
+    ///
+    /// See `establish_jwt` and the `proxy` module.
+    #[maybe_async]
+    pub async fn establish_jwt_with_proxy(
+        arango_url: &str,
+        username: &str,
+        password: &str,
+        options: ConnectionOptions,
+        proxy: ProxyOptions,
+    ) -> Result<Self, ClientError> {
+        trace!("Establish with jwt through proxy {}", proxy.url());
+        Self::establish_with_proxy(arango_url, Auth::jwt(username, password), options, proxy).await
+    }
+
+    #[maybe_async]
+    async fn establish_with_proxy<T: Into<String>>(
+        arango_url: T,
+        auth: Auth<'_>,
+        options: ConnectionOptions,
+        proxy: ProxyOptions,
+    ) -> Result<Self, ClientError> {
+        Self::establish_with_client(arango_url, auth, |headers| {
+            let client = C::with_proxy(headers, &proxy)?;
+            Ok(Session::with_client(client, options.clone())?)
+        })
+        .await
+    }
+}
+
+/// Parse the url of an arango server, making sure its path ends with exactly
+/// one slash so that `http://host:8529` and `http://host:8529/` are handled
+/// the same.
+///
+/// The path is kept, e.g. for a server behind a reverse proxy at
+/// `https://host/arango/`, hence the paths of the api are always joined
+/// relative to the url, e.g. `_api/database` rather than `/_api/database`.
+fn normalize_url(arango_url: &str) -> Result<Url, ClientError> {
+    let mut url = Url::parse(arango_url)
+        .map_err(|_| ClientError::InvalidServer(format!("invalid url: {}", arango_url)))?;
+    if url.cannot_be_a_base() {
+        return Err(ClientError::InvalidServer(format!(
+            "invalid url: {}",
+            arango_url
+        )));
+    }
+    let path = format!("{}/", url.path().trim_end_matches('/'));
+    url.set_path(&path);
+    Ok(url)
+}
+
+/// The url with `https` instead of `http`, or the other way around.
+fn swap_scheme(arango_url: &Url) -> Option<Url> {
+    let scheme = match arango_url.scheme() {
+        "http" => "https",
+        "https" => "http",
+        _ => return None,
+    };
+    let mut swapped = arango_url.clone();
+    swapped.set_scheme(scheme).ok()?;
+    Some(swapped)
+}
+
 impl<C: ClientExt> GenericConnection<C, Admin> {
     pub fn into_normal(self) -> GenericConnection<C, Normal> {
         self.into()
@@ -481,6 +1052,7 @@ impl<C: ClientExt> From<GenericConnection<C, Normal>> for GenericConnection<C, A
             arango_url: conn.arango_url,
             session: conn.session,
             username: conn.username,
+            default_options: conn.default_options,
             state: Admin,
         }
     }
@@ -492,7 +1064,91 @@ impl<C: ClientExt> From<GenericConnection<C, Admin>> for GenericConnection<C, No
             arango_url: conn.arango_url,
             session: conn.session,
             username: conn.username,
+            default_options: conn.default_options,
             state: Normal,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn normalize_trailing_slash() {
+        let without = normalize_url("http://localhost:8529").unwrap();
+        let with = normalize_url("http://localhost:8529/").unwrap();
+        assert_eq!(without, with);
+        assert_eq!(without.as_str(), "http://localhost:8529/");
+        assert_eq!(
+            normalize_url("http://localhost:8529//").unwrap().as_str(),
+            "http://localhost:8529/"
+        );
+        assert_eq!(
+            normalize_url("http://localhost:8529/arango")
+                .unwrap()
+                .as_str(),
+            "http://localhost:8529/arango/"
+        );
+        // the urls of the api are relative, hence keep the path of the server
+        assert_eq!(
+            normalize_url("http://localhost:8529/arango")
+                .unwrap()
+                .join("_db/test_db/")
+                .unwrap()
+                .as_str(),
+            "http://localhost:8529/arango/_db/test_db/"
+        );
+
+        for url in [without, with] {
+            assert_eq!(
+                url.join("_db/test_db/").unwrap().as_str(),
+                "http://localhost:8529/_db/test_db/"
+            );
+        }
+    }
+
+    #[test]
+    fn normalize_ipv6_and_ports() {
+        let url = normalize_url("http://[::1]:8529").unwrap();
+        assert_eq!(url.as_str(), "http://[::1]:8529/");
+        assert_eq!(
+            url.join("_db/test_db/").unwrap().as_str(),
+            "http://[::1]:8529/_db/test_db/"
+        );
+
+        let url = normalize_url("https://[2001:db8::1]:65535/arango").unwrap();
+        assert_eq!(url.port(), Some(65535));
+        assert_eq!(url.as_str(), "https://[2001:db8::1]:65535/arango/");
+
+        // the default port of the scheme is dropped
+        let url = normalize_url("http://[fe80::1]:80").unwrap();
+        assert_eq!(url.as_str(), "http://[fe80::1]/");
+
+        let url = normalize_url("http://localhost:1").unwrap();
+        assert_eq!(swap_scheme(&url).unwrap().as_str(), "https://localhost:1/");
+    }
+
+    #[test]
+    fn swap_url_scheme() {
+        let url = normalize_url("http://localhost:8529").unwrap();
+        let swapped = swap_scheme(&url).unwrap();
+        assert_eq!(swapped.as_str(), "https://localhost:8529/");
+        assert_eq!(swap_scheme(&swapped), Some(url));
+
+        let url = Url::parse("tcp://localhost:8529").unwrap();
+        assert_eq!(swap_scheme(&url), None);
+    }
+
+    #[test]
+    fn normalize_invalid_url() {
+        assert!(matches!(
+            normalize_url("localhost:8529"),
+            Err(ClientError::InvalidServer(_))
+        ));
+        assert!(matches!(
+            normalize_url("not a url"),
+            Err(ClientError::InvalidServer(_))
+        ));
+    }
+}
@@ -0,0 +1,54 @@
+//! Hooks to observe the requests made by a connection, e.g. to record
+//! metrics.
+use std::{fmt::Debug, time::Duration};
+
+use http::{Method, StatusCode, Uri};
+use uclient::ClientError;
+
+/// Observer of the HTTP requests made through a `Session`.
+///
+/// Set it with `ConnectionOptions::builder().observer(..)`. Every attempt is
+/// observed, so a request that is retried calls `on_request` once per
+/// attempt. All hooks do nothing by default.
+///
+/// The hooks are called on the request path, hence they should be cheap,
+/// e.g. incrementing a counter or recording a histogram sample.
+///
+/// # Example
+/// ```rust
+/// use std::{
+///     sync::atomic::{AtomicU64, Ordering},
+///     time::Duration,
+/// };
+///
+/// use arangors::connection::observer::Observer;
+/// use http::{Method, StatusCode, Uri};
+///
+/// #[derive(Debug, Default)]
+/// struct RequestCounter {
+///     requests: AtomicU64,
+/// }
+///
+/// impl Observer for RequestCounter {
+///     fn on_request(&self, _method: &Method, _uri: &Uri) {
+///         self.requests.fetch_add(1, Ordering::Relaxed);
+///     }
+///
+///     fn on_response(&self, status: StatusCode, duration: Duration) {
+///         println!("{} in {:?}", status, duration);
+///     }
+/// }
+/// ```
+pub trait Observer: Debug + Send + Sync {
+    /// Called right before a request is sent.
+    fn on_request(&self, _method: &Method, _uri: &Uri) {}
+
+    /// Called when the server answered a request, whatever the status.
+    ///
+    /// `duration` is the time elapsed since the request was sent.
+    fn on_response(&self, _status: StatusCode, _duration: Duration) {}
+
+    /// Called when a request failed without an answer of the server, e.g.
+    /// because it could not be reached.
+    fn on_error(&self, _error: &ClientError, _duration: Duration) {}
+}
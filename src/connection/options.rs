@@ -1,9 +1,225 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use http::{header::HeaderName, HeaderMap, HeaderValue};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
+use super::observer::Observer;
+
+#[cfg(feature = "cluster")]
+use serde_json::Value;
 #[cfg(feature = "cluster")]
 use std::collections::HashMap;
 
+/// Options applied to every request made through a connection.
+///
+/// The options are implemented on top of `ClientExt`, hence they behave the
+/// same for every HTTP backend (`reqwest`, `surf` or a custom client).
+///
+/// Settings that only the HTTP client itself can honour, e.g. request
+/// timeouts, TLS configuration or response compression, are not part of
+/// these options. Configure them on the client by implementing `ClientExt`,
+/// see the `custom_client` example. TLS settings of the `reqwest` backend
+/// can be set with `tls::TlsOptions`, and a proxy with `ProxyOptions`.
+#[derive(Debug, Clone, TypedBuilder)]
+#[builder(doc)]
+pub struct ConnectionOptions {
+    /// Headers sent with every request, unless the request sets them itself.
+    #[builder(default)]
+    pub(crate) headers: HeaderMap,
+    /// How many times a request is retried when the server cannot be reached
+    /// or does not answer in time, see `ClientError::is_transport_error`.
+    ///
+    /// A request that could not be sent, because no connection could be
+    /// established, is always retried. A request that timed out, or whose
+    /// connection broke while it was in flight, may already have been applied
+    /// by the server, hence it is only retried for `GET`, `HEAD` and `DELETE`:
+    /// a `POST`, `PUT` or `PATCH`, e.g. creating a document or committing a
+    /// transaction, is not sent twice. Requests answered by the server, even
+    /// with an error, are never retried.
+    #[builder(default)]
+    pub(crate) retries: u32,
+    /// Time waited before every retry, including the failover to another
+    /// endpoint. Defaults to zero, i.e. requests are retried immediately.
+    ///
+    /// The wait does not block the async runtime, the blocking clients sleep
+    /// the current thread.
+    #[builder(default)]
+    pub(crate) retry_backoff: Duration,
+    /// How JSON request bodies are sent to the server.
+    #[builder(default)]
+    pub(crate) json_format: JsonFormat,
+    /// Log request and response bodies at trace level.
+    #[builder(default)]
+    pub(crate) log_bodies: bool,
+    /// Value of the `User-Agent` header, defaults to `arangors/{version}`.
+    #[builder(default, setter(strip_option, into))]
+    pub(crate) user_agent: Option<String>,
+    /// Maximum size of a response body in bytes. A bigger response fails with
+    /// `ClientError::ResponseTooLarge` instead of being deserialized.
+    ///
+    /// The body is still received by the HTTP client before the check, so it
+    /// caps the memory used by deserialization, not by the transfer itself.
+    #[builder(default, setter(strip_option))]
+    pub(crate) max_response_size: Option<usize>,
+    /// Hooks called for every request, e.g. to record metrics.
+    #[builder(default, setter(strip_option))]
+    pub(crate) observer: Option<Arc<dyn Observer>>,
+    /// Header computed for every request, e.g. to propagate a trace context.
+    #[builder(default, setter(strip_option))]
+    pub(crate) dynamic_header: Option<DynamicHeader>,
+}
+
+/// A header computed right before every request is sent.
+///
+/// Unlike `ConnectionOptions::headers`, the value can change from one
+/// request to the next, e.g. to propagate the W3C `traceparent` of the
+/// current span so that the server logs can be correlated. The header is
+/// not added when the closure returns `None` or when the request already
+/// sets it, and it takes precedence over the static headers.
+///
+/// The closure is called once per request, retries send the same value.
+///
+/// # Example
+/// ```rust
+/// use arangors::connection::options::{ConnectionOptions, DynamicHeader};
+/// use http::{header::HeaderName, HeaderValue};
+///
+/// fn current_traceparent() -> Option<String> {
+///     None
+/// }
+///
+/// let options = ConnectionOptions::builder()
+///     .dynamic_header(DynamicHeader::new(|| {
+///         let value = HeaderValue::from_str(&current_traceparent()?).ok()?;
+///         Some((HeaderName::from_static("traceparent"), value))
+///     }))
+///     .build();
+/// ```
+#[derive(Clone)]
+pub struct DynamicHeader(Arc<dyn Fn() -> Option<(HeaderName, HeaderValue)> + Send + Sync>);
+
+impl DynamicHeader {
+    pub fn new<F>(header: F) -> Self
+    where
+        F: Fn() -> Option<(HeaderName, HeaderValue)> + Send + Sync + 'static,
+    {
+        DynamicHeader(Arc::new(header))
+    }
+
+    /// Compute the header of the next request.
+    pub fn header(&self) -> Option<(HeaderName, HeaderValue)> {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for DynamicHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DynamicHeader")
+    }
+}
+
+/// HTTP proxy settings, see `GenericConnection::establish_jwt_with_proxy`.
+///
+/// The proxy is used for `http` as well as `https` servers. Without it, the
+/// `reqwest` clients take the proxy from the `HTTP_PROXY` and `HTTPS_PROXY`
+/// environment variables, if set.
+///
+/// ```rust
+/// use arangors::connection::options::ProxyOptions;
+///
+/// let proxy = ProxyOptions::builder()
+///     .url("http://proxy.example.com:3128")
+///     .username("proxy_user")
+///     .password("proxy_password")
+///     .build();
+/// ```
+#[derive(Debug, Clone, TypedBuilder)]
+#[builder(doc)]
+pub struct ProxyOptions {
+    /// Url of the proxy, e.g. `http://proxy.example.com:3128`.
+    #[builder(setter(into))]
+    pub(crate) url: String,
+    /// User to authenticate to the proxy with basic auth.
+    #[builder(default, setter(strip_option, into))]
+    pub(crate) username: Option<String>,
+    /// Password to authenticate to the proxy with, along with `username`.
+    #[builder(default, setter(strip_option, into))]
+    pub(crate) password: Option<String>,
+}
+
+impl ProxyOptions {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+
+    pub fn password(&self) -> Option<&str> {
+        self.password.as_deref()
+    }
+}
+
+/// Default `User-Agent` of arangors.
+pub const DEFAULT_USER_AGENT: &str = concat!("arangors/", env!("CARGO_PKG_VERSION"));
+
+/// Formatting of JSON request bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonFormat {
+    /// Compact JSON, as produced by `serde_json::to_string`.
+    #[default]
+    Compact,
+    /// Pretty printed JSON. Handy when debugging with a proxy or
+    /// `log_bodies`, at the cost of bigger requests.
+    Pretty,
+}
+
+impl ConnectionOptions {
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    pub fn retry_backoff(&self) -> Duration {
+        self.retry_backoff
+    }
+
+    pub fn json_format(&self) -> JsonFormat {
+        self.json_format
+    }
+
+    pub fn log_bodies(&self) -> bool {
+        self.log_bodies
+    }
+
+    pub fn user_agent(&self) -> &str {
+        self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT)
+    }
+
+    pub fn max_response_size(&self) -> Option<usize> {
+        self.max_response_size
+    }
+
+    pub fn observer(&self) -> Option<&Arc<dyn Observer>> {
+        self.observer.as_ref()
+    }
+
+    pub fn dynamic_header(&self) -> Option<&DynamicHeader> {
+        self.dynamic_header.as_ref()
+    }
+}
+
+impl Default for ConnectionOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
 /// Options for create a collection
 #[derive(Serialize, PartialEq, TypedBuilder)]
 #[builder(doc)]
@@ -119,3 +335,107 @@ pub struct ClusterHealth {
 
     pub health: HashMap<String, ServerHealth>,
 }
+
+/// Options to compute a plan of shard moves balancing the cluster
+#[derive(Debug, Serialize, Deserialize, PartialEq, TypedBuilder)]
+#[builder(doc)]
+#[serde(rename_all = "camelCase")]
+#[cfg(feature = "cluster")]
+pub struct RebalanceOptions {
+    /// Maximum number of moves to compute (default: 1000).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    maximum_number_of_moves: Option<u32>,
+    /// Allow to switch the leader of a shard with one of its followers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    leader_changes: Option<bool>,
+    /// Allow to move the leader of a shard to another DB-Server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    move_leaders: Option<bool>,
+    /// Allow to move the followers of a shard to another DB-Server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    move_followers: Option<bool>,
+    /// Leave the shards of system collections in place.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    exclude_system_collections: Option<bool>,
+    /// Weight of the number of shard leaders per DB-Server when computing the
+    /// imbalance.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pi_factor: Option<f64>,
+    /// Databases whose shards are left in place.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    databases_excluded: Option<Vec<String>>,
+}
+
+#[cfg(feature = "cluster")]
+impl Default for RebalanceOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Version of the rebalance API the requests are written for.
+#[cfg(feature = "cluster")]
+pub(crate) const REBALANCE_API_VERSION: u32 = 1;
+
+/// Body of a request to the rebalance API, which must name its version.
+#[derive(Serialize)]
+#[cfg(feature = "cluster")]
+pub(crate) struct RebalanceRequest<T> {
+    pub(crate) version: u32,
+    #[serde(flatten)]
+    pub(crate) body: T,
+}
+
+/// A move of a shard from a DB-Server to another
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg(feature = "cluster")]
+pub struct ShardMove {
+    /// The DB-Server the shard is moved from.
+    pub from: String,
+    /// The DB-Server the shard is moved to.
+    pub to: String,
+    pub shard: String,
+    /// The id of the collection of the shard.
+    pub collection: String,
+    /// Whether the leader of the shard is moved, rather than a follower.
+    pub is_leader: bool,
+    /// All other attributes, sent back as is when executing the move.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Shard moves balancing the cluster, computed by
+/// `cluster_rebalance_plan`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg(feature = "cluster")]
+pub struct RebalancePlan {
+    /// Imbalance of the shard leaders and of the shards before the moves.
+    pub imbalance_before: Value,
+    /// Imbalance expected once the moves are done.
+    pub imbalance_after: Value,
+    pub moves: Vec<ShardMove>,
+}
+
+/// Current imbalance of the cluster and progress of the shard moves
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg(feature = "cluster")]
+pub struct RebalanceStatus {
+    /// Imbalance of the shard leaders per DB-Server.
+    pub leader: Value,
+    /// Imbalance of the shards per DB-Server.
+    pub shards: Value,
+    /// The number of shard moves in progress.
+    pub pending_move_shards: u64,
+    /// The number of shard moves scheduled but not started yet.
+    pub todo_move_shards: u64,
+}
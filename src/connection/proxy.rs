@@ -0,0 +1,132 @@
+//! Connecting through an HTTP proxy.
+//!
+//! Only the HTTP client itself can route requests through a proxy, hence
+//! the clients supported by arangors implement `ProxyClientExt`, which builds
+//! a client sending every request, to `http` as well as `https` servers,
+//! through the proxy of `ProxyOptions`:
+//!
+//! ```rust, ignore
+//! use arangors::{
+//!     connection::options::{ConnectionOptions, ProxyOptions},
+//!     Connection,
+//! };
+//!
+//! let proxy = ProxyOptions::builder()
+//!     .url("http://proxy.example.com:3128")
+//!     .username("proxy_user")
+//!     .password("proxy_password")
+//!     .build();
+//! let conn = Connection::establish_jwt_with_proxy(
+//!     "http://localhost:8529",
+//!     "username",
+//!     "password",
+//!     ConnectionOptions::default(),
+//!     proxy,
+//! )
+//! .await?;
+//! ```
+//!
+//! The `reqwest` clients, `ReqwestClient` and `tls::TlsClient`, support
+//! proxies. The `surf` client does not implement `ProxyClientExt`, so the
+//! proxy constructors of `GenericConnection` are not available with it.
+use http::HeaderMap;
+use uclient::{ClientError, ClientExt};
+
+use super::options::ProxyOptions;
+
+/// An HTTP client that can send its requests through a proxy.
+///
+/// Implemented by the `reqwest` clients. The `surf` client is not supported,
+/// as it offers no way to configure a proxy.
+pub trait ProxyClientExt: ClientExt {
+    /// Build a client with default headers, sending every request through
+    /// `proxy`.
+    fn with_proxy<U: Into<Option<HeaderMap>>>(
+        headers: U,
+        proxy: &ProxyOptions,
+    ) -> Result<Self, ClientError>;
+}
+
+#[cfg(any(
+    feature = "reqwest_async",
+    feature = "reqwest_async_rustls",
+    feature = "reqwest_blocking",
+    feature = "reqwest_blocking_rustls"
+))]
+pub(crate) use self::reqwest_client::to_reqwest_proxy;
+
+#[cfg(any(
+    feature = "reqwest_async",
+    feature = "reqwest_async_rustls",
+    feature = "reqwest_blocking",
+    feature = "reqwest_blocking_rustls"
+))]
+mod reqwest_client {
+    use http::HeaderMap;
+    #[cfg(feature = "blocking")]
+    use reqwest::blocking::Client;
+    #[cfg(not(feature = "blocking"))]
+    use reqwest::Client;
+    use reqwest::Proxy;
+    use uclient::{reqwest::ReqwestClient, ClientError, ClientExt};
+
+    use super::{ProxyClientExt, ProxyOptions};
+
+    impl ProxyClientExt for ReqwestClient {
+        fn with_proxy<U: Into<Option<HeaderMap>>>(
+            headers: U,
+            proxy: &ProxyOptions,
+        ) -> Result<Self, ClientError> {
+            let mut client = ReqwestClient::new(headers)?;
+            client.client = Client::builder()
+                .gzip(true)
+                .proxy(to_reqwest_proxy(proxy)?)
+                .build()
+                .map_err(|e| ClientError::HttpClient(format!("{:?}", e)))?;
+            Ok(client)
+        }
+    }
+
+    /// Build the `reqwest` proxy of `proxy`.
+    pub(crate) fn to_reqwest_proxy(proxy: &ProxyOptions) -> Result<Proxy, ClientError> {
+        let mut reqwest_proxy = Proxy::all(proxy.url.as_str()).map_err(|e| {
+            ClientError::HttpClient(format!("invalid proxy url {}: {}", proxy.url, e))
+        })?;
+        if let Some(username) = &proxy.username {
+            reqwest_proxy =
+                reqwest_proxy.basic_auth(username, proxy.password().unwrap_or_default());
+        }
+        Ok(reqwest_proxy)
+    }
+}
+
+#[cfg(all(
+    test,
+    any(
+        feature = "reqwest_async",
+        feature = "reqwest_async_rustls",
+        feature = "reqwest_blocking",
+        feature = "reqwest_blocking_rustls"
+    )
+))]
+mod test {
+    use uclient::reqwest::ReqwestClient;
+
+    use super::*;
+
+    #[test]
+    fn reqwest_with_proxy() {
+        let proxy = ProxyOptions::builder()
+            .url("http://proxy.example.com:3128")
+            .username("user")
+            .password("secret")
+            .build();
+        assert_eq!(proxy.url(), "http://proxy.example.com:3128");
+        assert_eq!(proxy.username(), Some("user"));
+        assert_eq!(proxy.password(), Some("secret"));
+        assert!(ReqwestClient::with_proxy(HeaderMap::new(), &proxy).is_ok());
+
+        let proxy = ProxyOptions::builder().url("not a url").build();
+        assert!(ReqwestClient::with_proxy(HeaderMap::new(), &proxy).is_err());
+    }
+}
@@ -0,0 +1,1042 @@
+//! HTTP session shared by `Connection`, `Database`, `Collection` and friends.
+//!
+//! `Session` wraps any `ClientExt` implementation and applies the connection
+//! level options in a backend agnostic way, so that `reqwest` and `surf` (or
+//! any custom client) behave the same.
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    task::Waker,
+    time::{Duration, Instant},
+};
+#[cfg(not(feature = "blocking"))]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use http::{
+    header::{HeaderName, USER_AGENT},
+    uri::{Authority, Scheme},
+    HeaderMap, HeaderValue, Method, Request, Response, StatusCode, Uri,
+};
+use log::{trace, warn};
+use maybe_async::maybe_async;
+use uclient::{ClientError, ClientExt};
+use url::Url;
+
+use serde_json::Value;
+
+#[cfg(any(
+    feature = "reqwest_async",
+    feature = "reqwest_async_rustls",
+    feature = "reqwest_blocking",
+    feature = "reqwest_blocking_rustls"
+))]
+use crate::connection::binary::{reqwest_parts, send_reqwest, ReqwestParts};
+#[cfg(not(feature = "blocking"))]
+use crate::replication::Delay;
+use crate::{
+    connection::BinaryClientExt,
+    error::{Failure, TransportError},
+    job::{JobId, ASYNC_HEADER, ASYNC_ID_HEADER},
+    response::deserialize_response,
+    ClientError as ArangoClientError,
+};
+
+use super::options::{ConnectionOptions, JsonFormat};
+
+/// A `ClientExt` wrapper applying `ConnectionOptions` on top of an HTTP
+/// client.
+#[derive(Debug, Clone)]
+pub struct Session<C> {
+    client: C,
+    options: Arc<ConnectionOptions>,
+    endpoints: Arc<Endpoints>,
+    in_flight: Arc<InFlight>,
+    /// Async jobs started through the session whose result was not fetched,
+    /// with their url.
+    jobs: Arc<Mutex<HashMap<JobId, Url>>>,
+    /// The `reqwest` client of `client`, when it is one arangors can send
+    /// the requests of itself.
+    #[cfg(any(
+        feature = "reqwest_async",
+        feature = "reqwest_async_rustls",
+        feature = "reqwest_blocking",
+        feature = "reqwest_blocking_rustls"
+    ))]
+    reqwest_parts: ReqwestParts<C>,
+}
+
+/// Requests in flight on a session and its clones.
+///
+/// Once closed, new requests are refused, so that the number of requests in
+/// flight only decreases until the session is idle.
+#[derive(Debug, Default)]
+struct InFlight {
+    closed: AtomicBool,
+    state: Mutex<InFlightState>,
+    idle: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct InFlightState {
+    count: usize,
+    /// Tasks waiting for the session to be idle.
+    waiters: Vec<Waker>,
+}
+
+impl InFlight {
+    /// Track a request until the returned guard is dropped, unless the
+    /// session is closed.
+    fn start(&self) -> Option<InFlightGuard<'_>> {
+        let mut state = self.state.lock().unwrap();
+        // checked under the lock, so that no request starts once `close` saw
+        // the session idle
+        if self.closed.load(Ordering::SeqCst) {
+            return None;
+        }
+        state.count += 1;
+        Some(InFlightGuard(self))
+    }
+
+    fn finish(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.count -= 1;
+        if state.count == 0 {
+            self.idle.notify_all();
+            for waker in state.waiters.drain(..) {
+                waker.wake();
+            }
+        }
+    }
+
+    fn close(&self) {
+        let _state = self.state.lock().unwrap();
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    #[cfg(feature = "blocking")]
+    fn wait_idle(&self) {
+        let mut state = self.state.lock().unwrap();
+        while state.count > 0 {
+            state = self.idle.wait(state).unwrap();
+        }
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    fn wait_idle(&self) -> WaitIdle<'_> {
+        WaitIdle(self)
+    }
+}
+
+struct InFlightGuard<'a>(&'a InFlight);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.finish();
+    }
+}
+
+/// Future resolving once no request is in flight anymore.
+#[cfg(not(feature = "blocking"))]
+struct WaitIdle<'a>(&'a InFlight);
+
+#[cfg(not(feature = "blocking"))]
+impl Future for WaitIdle<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.0.state.lock().unwrap();
+        if state.count == 0 {
+            Poll::Ready(())
+        } else {
+            // a future polled again must not be registered twice
+            if !state.waiters.iter().any(|w| w.will_wake(cx.waker())) {
+                state.waiters.push(cx.waker().clone());
+            }
+            Poll::Pending
+        }
+    }
+}
+
+/// Server endpoints a session fails over between.
+///
+/// Shared by all clones of a session, so that every `Database` and
+/// `Collection` moves on to the next endpoint once one becomes unreachable.
+#[derive(Debug, Default)]
+struct Endpoints {
+    endpoints: Vec<(Scheme, Authority)>,
+    current: AtomicUsize,
+}
+
+impl Endpoints {
+    fn new(urls: &[Url], current: usize) -> Result<Self, ClientError> {
+        let endpoints = urls
+            .iter()
+            .map(|url| {
+                let host = url.host_str().unwrap_or_default();
+                let authority = match url.port() {
+                    Some(port) => format!("{}:{}", host, port),
+                    None => host.to_owned(),
+                };
+                let invalid = |_| ClientError::HttpClient(format!("invalid endpoint: {}", url));
+                Ok((
+                    url.scheme().parse().map_err(invalid)?,
+                    authority.parse().map_err(invalid)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, ClientError>>()?;
+        Ok(Endpoints {
+            endpoints,
+            current: AtomicUsize::new(current),
+        })
+    }
+
+    /// Number of other endpoints to try when the current one is unreachable.
+    fn failover_attempts(&self) -> u32 {
+        self.endpoints.len().saturating_sub(1) as u32
+    }
+
+    /// Point the request to the current endpoint.
+    fn apply<B>(&self, request: &mut Request<B>) {
+        if self.endpoints.is_empty() {
+            return;
+        }
+        let (scheme, authority) =
+            &self.endpoints[self.current.load(Ordering::Relaxed) % self.endpoints.len()];
+        let mut parts = request.uri().clone().into_parts();
+        parts.scheme = Some(scheme.clone());
+        parts.authority = Some(authority.clone());
+        if let Ok(uri) = Uri::from_parts(parts) {
+            *request.uri_mut() = uri;
+        }
+    }
+
+    fn rotate(&self) {
+        self.current.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl<C: ClientExt + 'static> Session<C> {
+    /// Build a session with default headers and connection options.
+    pub fn with_options(
+        headers: HeaderMap,
+        options: ConnectionOptions,
+    ) -> Result<Self, ClientError> {
+        let mut headers = headers;
+        add_default_headers(&mut headers, &options)?;
+        Ok(Session::from_client(C::new(headers)?, options).sending_reqwest())
+    }
+
+    /// Build a session on top of an already configured HTTP client.
+    ///
+    /// The headers of `options` are added to the default headers of the
+    /// client, unless the client already sets them.
+    pub fn with_client(mut client: C, options: ConnectionOptions) -> Result<Self, ClientError> {
+        add_default_headers(client.headers(), &options)?;
+        Ok(Session::from_client(client, options).sending_reqwest())
+    }
+
+    /// Send the requests itself when the client is one of the `reqwest`
+    /// clients, see `Session::transport`.
+    #[cfg(any(
+        feature = "reqwest_async",
+        feature = "reqwest_async_rustls",
+        feature = "reqwest_blocking",
+        feature = "reqwest_blocking_rustls"
+    ))]
+    fn sending_reqwest(mut self) -> Self {
+        self.reqwest_parts = reqwest_parts::<C>;
+        self
+    }
+
+    #[cfg(not(any(
+        feature = "reqwest_async",
+        feature = "reqwest_async_rustls",
+        feature = "reqwest_blocking",
+        feature = "reqwest_blocking_rustls"
+    )))]
+    fn sending_reqwest(self) -> Self {
+        self
+    }
+}
+
+impl<C: ClientExt> Session<C> {
+    /// Build a session sending every request with `ClientExt::request`.
+    fn from_client(client: C, options: ConnectionOptions) -> Self {
+        Session {
+            client,
+            options: Arc::new(options),
+            endpoints: Default::default(),
+            in_flight: Default::default(),
+            jobs: Default::default(),
+            #[cfg(any(
+                feature = "reqwest_async",
+                feature = "reqwest_async_rustls",
+                feature = "reqwest_blocking",
+                feature = "reqwest_blocking_rustls"
+            ))]
+            reqwest_parts: |_| None,
+        }
+    }
+
+    /// Fail over between `endpoints`, starting with the one at index
+    /// `current`.
+    ///
+    /// Requests are sent to the current endpoint, whatever host their url
+    /// points to. When the current endpoint cannot be reached, the request
+    /// is sent again to the next endpoint, in a round-robin manner.
+    pub fn with_endpoints(
+        mut self,
+        endpoints: &[Url],
+        current: usize,
+    ) -> Result<Self, ClientError> {
+        self.endpoints = Arc::new(Endpoints::new(endpoints, current)?);
+        Ok(self)
+    }
+
+    /// A clone of the session sending requests to the host of their url,
+    /// whatever the endpoints it fails over between.
+    pub(crate) fn without_endpoints(&self) -> Self {
+        Session {
+            endpoints: Default::default(),
+            ..self.clone()
+        }
+    }
+
+    /// Get the underlying HTTP client.
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+
+    /// Get the connection options of this session.
+    pub fn options(&self) -> &ConnectionOptions {
+        &self.options
+    }
+
+    /// Refuse new requests, then wait for the requests in flight to complete.
+    ///
+    /// This applies to all clones of the session. Requests made once the
+    /// session is closed fail with `ClientError::ConnectionClosed`.
+    ///
+    /// The HTTP client is not released by `close`, only once the last clone
+    /// of the session, e.g. the last `Arc<Session>`, is dropped.
+    #[maybe_async]
+    pub async fn close(&self) {
+        self.in_flight.close();
+        self.in_flight.wait_idle().await
+    }
+
+    /// Forget an async job once its result is fetched.
+    pub(crate) fn forget_job(&self, job: &JobId) {
+        self.jobs.lock().unwrap().remove(job);
+    }
+
+    /// Whether the session was closed with `close`.
+    pub fn is_closed(&self) -> bool {
+        self.in_flight.closed.load(Ordering::SeqCst)
+    }
+}
+
+/// Add the headers of `options`, and the user agent, to `headers` unless they
+/// are already set.
+fn add_default_headers(
+    headers: &mut HeaderMap,
+    options: &ConnectionOptions,
+) -> Result<(), ClientError> {
+    for (name, value) in options.headers.iter() {
+        if !headers.contains_key(name) {
+            headers.insert(name, value.clone());
+        }
+    }
+    if !headers.contains_key(USER_AGENT) {
+        let user_agent = HeaderValue::from_str(options.user_agent())
+            .map_err(|e| ClientError::HttpClient(format!("invalid user agent: {}", e)))?;
+        headers.insert(USER_AGENT, user_agent);
+    }
+    Ok(())
+}
+
+/// Add a header computed by `ConnectionOptions::dynamic_header`, unless the
+/// request already sets it.
+fn add_dynamic_header<B>(request: &mut Request<B>, name: HeaderName, value: HeaderValue) {
+    if !request.headers().contains_key(&name) {
+        request.headers_mut().insert(name, value);
+    }
+}
+
+/// The async job started by a request sent with `x-arango-async: store`,
+/// and the url of the job, which lives next to the api of the request, e.g.
+/// `/_db/{database}/_api/job/{id}`.
+fn started_job<B>(request: &Request<B>, response: &Response<String>) -> Option<(JobId, Url)> {
+    if request.headers().get(ASYNC_HEADER)? != "store" {
+        return None;
+    }
+    let id = response.headers().get(ASYNC_ID_HEADER)?.to_str().ok()?;
+    let mut url = Url::parse(&request.uri().to_string()).ok()?;
+    let prefix = url.path().split("/_api/").next()?.to_owned();
+    url.set_path(&format!("{}/_api/job/{}", prefix, id));
+    url.set_query(None);
+    Some((JobId(id.to_owned()), url))
+}
+
+/// Whether a request that failed can be sent again.
+///
+/// A request that could not be sent at all can always be retried. Otherwise
+/// the server may have applied it already, e.g. a `POST` that timed out, so
+/// only the methods that can be repeated safely are retried.
+fn can_retry(method: &Method, failure: Failure) -> bool {
+    match failure {
+        Failure::Connect => true,
+        Failure::Interrupted | Failure::Timeout => {
+            [Method::GET, Method::HEAD, Method::DELETE].contains(method)
+        }
+        Failure::Decode | Failure::Other => false,
+    }
+}
+
+fn clone_request<B: Clone>(request: &Request<B>) -> Request<B> {
+    let mut cloned = Request::new(request.body().clone());
+    *cloned.method_mut() = request.method().clone();
+    *cloned.uri_mut() = request.uri().clone();
+    *cloned.version_mut() = request.version();
+    *cloned.headers_mut() = request.headers().clone();
+    cloned
+}
+
+/// Clients a request with a body of type `B` can be sent with.
+#[maybe_async]
+trait Transport<B> {
+    async fn transport(&self, request: Request<B>) -> Result<Response<String>, TransportError>;
+}
+
+#[maybe_async]
+impl<C: ClientExt> Transport<String> for C {
+    async fn transport(
+        &self,
+        request: Request<String>,
+    ) -> Result<Response<String>, TransportError> {
+        Ok(self.request(request).await?)
+    }
+}
+
+#[maybe_async]
+impl<C: BinaryClientExt> Transport<Vec<u8>> for C {
+    async fn transport(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> Result<Response<String>, TransportError> {
+        Ok(self.request_binary(request).await?)
+    }
+}
+
+/// Wait before retrying a request, without blocking the async runtime.
+#[cfg(not(feature = "blocking"))]
+async fn back_off(wait: Duration) {
+    Delay::new(wait).await
+}
+
+/// Wait before retrying a request.
+#[cfg(feature = "blocking")]
+fn back_off(wait: Duration) {
+    std::thread::sleep(wait)
+}
+
+/// Pretty print a JSON body, leaving anything else untouched.
+fn prettify(body: &mut String) {
+    if body.is_empty() {
+        return;
+    }
+    if let Ok(pretty) = serde_json::from_str::<serde_json::Value>(body)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+    {
+        *body = pretty;
+    }
+}
+
+/// Requests sent through the session.
+///
+/// Unlike the methods of `ClientExt`, which they take precedence over, they
+/// fail with the errors of arangors, e.g. `ClientError::ResponseTooLarge`.
+impl<C: ClientExt> Session<C> {
+    /// Send a request, applying the connection options.
+    #[maybe_async]
+    pub async fn request(
+        &self,
+        mut request: Request<String>,
+    ) -> Result<Response<String>, ArangoClientError> {
+        let _in_flight = self.in_flight.start().ok_or_else(|| {
+            ArangoClientError::ConnectionClosed(format!("{} {}", request.method(), request.uri()))
+        })?;
+        if self.options.json_format == JsonFormat::Pretty {
+            prettify(request.body_mut());
+        }
+        if self.options.log_bodies {
+            trace!(
+                "Request {} {}: {}",
+                request.method(),
+                request.uri(),
+                request.body()
+            );
+        }
+        self.send(request).await
+    }
+
+    /// Send a request whether the session is closed or not.
+    #[maybe_async]
+    async fn send<B>(&self, mut request: Request<B>) -> Result<Response<String>, ArangoClientError>
+    where
+        B: Clone + Send + Sync + Into<Vec<u8>>,
+        C: Transport<B>,
+    {
+        if let Some((name, value)) = self
+            .options
+            .dynamic_header
+            .as_ref()
+            .and_then(|h| h.header())
+        {
+            add_dynamic_header(&mut request, name, value);
+        }
+
+        let max_attempts = self.options.retries + self.endpoints.failover_attempts();
+        let mut attempt = 0;
+        loop {
+            let mut req = clone_request(&request);
+            self.endpoints.apply(&mut req);
+            if let Some(observer) = &self.options.observer {
+                observer.on_request(req.method(), req.uri());
+            }
+            let start = Instant::now();
+            let result = self.transport(req).await;
+            if let Some(observer) = &self.options.observer {
+                match &result {
+                    Ok(response) => observer.on_response(response.status(), start.elapsed()),
+                    Err(e) => observer.on_error(&e.error, start.elapsed()),
+                }
+            }
+            match result {
+                Err(e) if attempt < max_attempts && can_retry(request.method(), e.failure) => {
+                    attempt += 1;
+                    self.endpoints.rotate();
+                    warn!(
+                        "Request to {} failed ({}), retrying ({}/{})",
+                        request.uri(),
+                        e.error,
+                        attempt,
+                        max_attempts
+                    );
+                    if !self.options.retry_backoff.is_zero() {
+                        back_off(self.options.retry_backoff).await;
+                    }
+                }
+                Ok(response) => {
+                    if let Some(limit) = self.options.max_response_size {
+                        let size = response.body().len();
+                        if size > limit {
+                            return Err(ArangoClientError::ResponseTooLarge(format!(
+                                "{} bytes exceed the limit of {} bytes",
+                                size, limit
+                            )));
+                        }
+                    }
+                    if self.options.log_bodies {
+                        trace!(
+                            "Response {} from {}: {}",
+                            response.status(),
+                            request.uri(),
+                            response.body()
+                        );
+                    }
+                    if let Some((job, url)) = started_job(&request, &response) {
+                        self.jobs.lock().unwrap().insert(job, url);
+                    }
+                    return Ok(response);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Send a request once with the HTTP client.
+    ///
+    /// The requests of the `reqwest` clients are sent by arangors itself, so
+    /// that how a request failed is read from the `reqwest` error. The
+    /// failures of the other clients are recovered from their error message,
+    /// see `classify`.
+    #[maybe_async]
+    async fn transport<B>(&self, request: Request<B>) -> Result<Response<String>, TransportError>
+    where
+        B: Send + Sync + Into<Vec<u8>>,
+        C: Transport<B>,
+    {
+        #[cfg(any(
+            feature = "reqwest_async",
+            feature = "reqwest_async_rustls",
+            feature = "reqwest_blocking",
+            feature = "reqwest_blocking_rustls"
+        ))]
+        if let Some((client, headers)) = (self.reqwest_parts)(&self.client) {
+            return send_reqwest(client, &headers, request.map(Into::into)).await;
+        }
+        self.client.transport(request).await
+    }
+
+    /// Same as `close`, then cancel the async jobs started through the session
+    /// whose result was not fetched with `Database::job_result`.
+    ///
+    /// The pending jobs are aborted and the results of the finished ones are
+    /// dropped by the server. Every job is cancelled even if a request fails,
+    /// in which case the first error is returned.
+    #[maybe_async]
+    pub async fn close_and_cancel_jobs(&self) -> Result<(), ArangoClientError> {
+        self.close().await;
+        let jobs: Vec<Url> = self
+            .jobs
+            .lock()
+            .unwrap()
+            .drain()
+            .map(|(_, url)| url)
+            .collect();
+        let mut result = Ok(());
+        for url in jobs {
+            let cancel = Request::put(format!("{}/cancel", url))
+                .body(String::new())
+                .unwrap();
+            let mut outcome = self.send_ignoring_not_found(cancel).await;
+            if outcome.is_ok() {
+                let delete = Request::delete(url.as_str()).body(String::new()).unwrap();
+                outcome = self.send_ignoring_not_found(delete).await;
+            }
+            if result.is_ok() {
+                result = outcome;
+            }
+        }
+        result
+    }
+
+    /// Send a request on a closed session, a job that is not found being done
+    /// already.
+    #[maybe_async]
+    async fn send_ignoring_not_found(
+        &self,
+        request: Request<String>,
+    ) -> Result<(), ArangoClientError> {
+        let resp = self.send(request).await?;
+        if resp.status() != StatusCode::NOT_FOUND {
+            deserialize_response::<Value>(resp.body())?;
+        }
+        Ok(())
+    }
+
+    #[maybe_async]
+    pub async fn get<T>(&self, url: Url, text: T) -> Result<Response<String>, ArangoClientError>
+    where
+        T: Into<String> + Send,
+    {
+        self.request(Request::get(url.as_str()).body(text.into()).unwrap())
+            .await
+    }
+
+    #[maybe_async]
+    pub async fn post<T>(&self, url: Url, text: T) -> Result<Response<String>, ArangoClientError>
+    where
+        T: Into<String> + Send,
+    {
+        self.request(Request::post(url.as_str()).body(text.into()).unwrap())
+            .await
+    }
+
+    #[maybe_async]
+    pub async fn put<T>(&self, url: Url, text: T) -> Result<Response<String>, ArangoClientError>
+    where
+        T: Into<String> + Send,
+    {
+        self.request(Request::put(url.as_str()).body(text.into()).unwrap())
+            .await
+    }
+
+    #[maybe_async]
+    pub async fn patch<T>(&self, url: Url, text: T) -> Result<Response<String>, ArangoClientError>
+    where
+        T: Into<String> + Send,
+    {
+        self.request(Request::patch(url.as_str()).body(text.into()).unwrap())
+            .await
+    }
+
+    #[maybe_async]
+    pub async fn delete<T>(&self, url: Url, text: T) -> Result<Response<String>, ArangoClientError>
+    where
+        T: Into<String> + Send,
+    {
+        self.request(Request::delete(url.as_str()).body(text.into()).unwrap())
+            .await
+    }
+
+    #[maybe_async]
+    pub async fn head<T>(&self, url: Url, text: T) -> Result<Response<String>, ArangoClientError>
+    where
+        T: Into<String> + Send,
+    {
+        self.request(Request::head(url.as_str()).body(text.into()).unwrap())
+            .await
+    }
+}
+
+/// Requests with a binary body, for the clients able to send them.
+impl<C: BinaryClientExt> Session<C> {
+    /// Send a request with a binary body, e.g. the zip bundle of a Foxx
+    /// service, applying the connection options.
+    ///
+    /// Unlike the body of `request`, the body is neither pretty printed nor
+    /// logged.
+    #[maybe_async]
+    pub async fn request_binary(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> Result<Response<String>, ArangoClientError> {
+        let _in_flight = self.in_flight.start().ok_or_else(|| {
+            ArangoClientError::ConnectionClosed(format!("{} {}", request.method(), request.uri()))
+        })?;
+        if self.options.log_bodies {
+            trace!(
+                "Request {} {}: {} bytes",
+                request.method(),
+                request.uri(),
+                request.body().len()
+            );
+        }
+        self.send(request).await
+    }
+}
+
+#[maybe_async]
+impl<C: ClientExt> ClientExt for Session<C> {
+    fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError> {
+        let mut headers = headers.into().unwrap_or_default();
+        let options = ConnectionOptions::default();
+        add_default_headers(&mut headers, &options)?;
+        // `C` is not known to be one of the `reqwest` clients here, hence
+        // every request is sent with `ClientExt::request`
+        Ok(Session::from_client(C::new(headers)?, options))
+    }
+
+    fn headers(&mut self) -> &mut HeaderMap {
+        self.client.headers()
+    }
+
+    async fn request(&self, request: Request<String>) -> Result<Response<String>, ClientError> {
+        Session::request(self, request).await.map_err(|e| match e {
+            ArangoClientError::HttpClient(e) => e,
+            e => ClientError::HttpClient(e.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use http::header::IF_MATCH;
+
+    use super::*;
+
+    /// A client answering every request with a body of the given size.
+    #[derive(Debug, Clone)]
+    struct SizedClient(HeaderMap, usize);
+
+    #[maybe_async]
+    impl ClientExt for SizedClient {
+        fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError> {
+            Ok(SizedClient(headers.into().unwrap_or_default(), 0))
+        }
+
+        fn headers(&mut self) -> &mut HeaderMap {
+            &mut self.0
+        }
+
+        async fn request(
+            &self,
+            _request: Request<String>,
+        ) -> Result<Response<String>, ClientError> {
+            Ok(Response::new("x".repeat(self.1)))
+        }
+    }
+
+    #[maybe_async]
+    impl BinaryClientExt for SizedClient {
+        async fn request_binary(
+            &self,
+            request: Request<Vec<u8>>,
+        ) -> Result<Response<String>, ClientError> {
+            Ok(Response::new(format!("{} bytes", request.body().len())))
+        }
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn send_binary_body() {
+        let session =
+            Session::with_client(SizedClient(HeaderMap::new(), 0), Default::default()).unwrap();
+        let request = Request::put("http://localhost:8529/_api/foxx/service")
+            .body(vec![0x50, 0x4b, 0x03, 0x04])
+            .unwrap();
+        let response = session.request_binary(request).await.unwrap();
+        assert_eq!(response.body(), "4 bytes");
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn response_size_limit() {
+        let url = Url::parse("http://localhost:8529/").unwrap();
+        let options = ConnectionOptions::builder().max_response_size(4).build();
+
+        let session =
+            Session::with_client(SizedClient(HeaderMap::new(), 4), options.clone()).unwrap();
+        let response = session.get(url.clone(), "").await;
+        assert!(response.is_ok());
+
+        let session = Session::with_client(SizedClient(HeaderMap::new(), 5), options).unwrap();
+        match session.get(url, "").await {
+            Err(ArangoClientError::ResponseTooLarge(details)) => {
+                assert_eq!(details, "5 bytes exceed the limit of 4 bytes")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    /// A client failing every request as if the server was down.
+    #[derive(Debug, Clone, Default)]
+    struct DownClient(HeaderMap, Arc<AtomicUsize>);
+
+    #[maybe_async]
+    impl ClientExt for DownClient {
+        fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError> {
+            Ok(DownClient(
+                headers.into().unwrap_or_default(),
+                Default::default(),
+            ))
+        }
+
+        fn headers(&mut self) -> &mut HeaderMap {
+            &mut self.0
+        }
+
+        async fn request(
+            &self,
+            _request: Request<String>,
+        ) -> Result<Response<String>, ClientError> {
+            self.1.fetch_add(1, Ordering::SeqCst);
+            Err(ClientError::HttpClient(
+                "hyper::Error(Connect, ConnectError(\"tcp connect error\"))".to_owned(),
+            ))
+        }
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn back_off_between_retries() {
+        let url = Url::parse("http://localhost:8529/").unwrap();
+        let options = ConnectionOptions::builder()
+            .retries(2)
+            .retry_backoff(Duration::from_millis(30))
+            .build();
+        let client = DownClient::default();
+        let session = Session::with_client(client.clone(), options).unwrap();
+
+        let start = Instant::now();
+        let response = session.get(url, "").await;
+        assert!(matches!(response, Err(ArangoClientError::Connection(_))));
+        assert_eq!(client.1.load(Ordering::SeqCst), 3);
+        assert!(start.elapsed() >= Duration::from_millis(60));
+    }
+
+    #[test]
+    fn fail_over_endpoints() {
+        let urls = vec![
+            Url::parse("http://coordinator1:8529").unwrap(),
+            Url::parse("https://[::1]:8530/").unwrap(),
+        ];
+        let endpoints = Endpoints::new(&urls, 0).unwrap();
+        assert_eq!(endpoints.failover_attempts(), 1);
+
+        let mut request = Request::new(String::new());
+        *request.uri_mut() = "http://localhost:8529/_db/test/_api/version?details=true"
+            .parse()
+            .unwrap();
+        endpoints.apply(&mut request);
+        assert_eq!(
+            request.uri(),
+            "http://coordinator1:8529/_db/test/_api/version?details=true"
+        );
+
+        endpoints.rotate();
+        endpoints.apply(&mut request);
+        assert_eq!(
+            request.uri(),
+            "https://[::1]:8530/_db/test/_api/version?details=true"
+        );
+
+        endpoints.rotate();
+        endpoints.apply(&mut request);
+        assert_eq!(request.uri().host(), Some("coordinator1"));
+
+        // no endpoints means the request is left untouched
+        let endpoints = Endpoints::default();
+        assert_eq!(endpoints.failover_attempts(), 0);
+        endpoints.apply(&mut request);
+        assert_eq!(request.uri().host(), Some("coordinator1"));
+    }
+
+    #[test]
+    fn dynamic_header_does_not_override_request() {
+        let traceparent = HeaderName::from_static("traceparent");
+        let mut request = Request::new(String::new());
+        add_dynamic_header(
+            &mut request,
+            traceparent.clone(),
+            HeaderValue::from_static("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"),
+        );
+        assert_eq!(
+            request.headers()[&traceparent],
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"
+        );
+
+        request
+            .headers_mut()
+            .insert(IF_MATCH, HeaderValue::from_static("\"_rev\""));
+        add_dynamic_header(
+            &mut request,
+            IF_MATCH,
+            HeaderValue::from_static("\"other\""),
+        );
+        assert_eq!(request.headers()[IF_MATCH], "\"_rev\"");
+    }
+
+    #[test]
+    fn retry_only_safe_requests() {
+        for method in [Method::GET, Method::POST, Method::PUT, Method::PATCH] {
+            assert!(can_retry(&method, Failure::Connect));
+            assert!(!can_retry(&method, Failure::Other));
+            assert!(!can_retry(&method, Failure::Decode));
+        }
+        for method in [Method::GET, Method::HEAD, Method::DELETE] {
+            assert!(can_retry(&method, Failure::Timeout));
+            assert!(can_retry(&method, Failure::Interrupted));
+        }
+        for method in [Method::POST, Method::PUT, Method::PATCH] {
+            assert!(!can_retry(&method, Failure::Timeout));
+            assert!(!can_retry(&method, Failure::Interrupted));
+        }
+    }
+
+    #[test]
+    fn track_started_jobs() {
+        let request = Request::get(
+            "http://localhost:8529/arango/_db/test/_api/collection/c/checksum?withData=true",
+        )
+        .header(ASYNC_HEADER, "store")
+        .body(String::new())
+        .unwrap();
+        let response = Response::builder()
+            .status(StatusCode::ACCEPTED)
+            .header(ASYNC_ID_HEADER, "1234")
+            .body(String::new())
+            .unwrap();
+        let (job, url) = started_job(&request, &response).unwrap();
+        assert_eq!(job.as_str(), "1234");
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:8529/arango/_db/test/_api/job/1234"
+        );
+
+        let mut request = request;
+        request.headers_mut().remove(ASYNC_HEADER);
+        assert!(started_job(&request, &response).is_none());
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn closed_session_refuses_requests() {
+        let session =
+            Session::with_client(SizedClient(HeaderMap::new(), 0), Default::default()).unwrap();
+        let url = Url::parse("http://localhost:8529/").unwrap();
+        session.close().await;
+        assert!(session.is_closed());
+        let err = session.get(url, "").await.unwrap_err();
+        assert!(
+            matches!(err, ArangoClientError::ConnectionClosed(_)),
+            "{:?}",
+            err
+        );
+        assert!(!err.is_transport_error());
+    }
+
+    #[test]
+    fn close_refuses_new_requests() {
+        let in_flight = InFlight::default();
+        let guard = in_flight.start().unwrap();
+        assert_eq!(in_flight.state.lock().unwrap().count, 1);
+
+        in_flight.close();
+        assert!(in_flight.start().is_none());
+        // requests started before are still tracked
+        assert_eq!(in_flight.state.lock().unwrap().count, 1);
+        drop(guard);
+        assert_eq!(in_flight.state.lock().unwrap().count, 0);
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    #[test]
+    fn wait_idle_registers_its_waker_once() {
+        let in_flight = InFlight::default();
+        let guard = in_flight.start().unwrap();
+        let waker = futures_util::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut wait = in_flight.wait_idle();
+        for _ in 0..3 {
+            assert!(Pin::new(&mut wait).poll(&mut cx).is_pending());
+        }
+        assert_eq!(in_flight.state.lock().unwrap().waiters.len(), 1);
+
+        drop(guard);
+        assert!(in_flight.state.lock().unwrap().waiters.is_empty());
+        assert!(Pin::new(&mut wait).poll(&mut cx).is_ready());
+    }
+
+    #[test]
+    fn prettify_json_bodies() {
+        let mut body = r#"{"a":[1,2]}"#.to_owned();
+        prettify(&mut body);
+        assert_eq!(body, "{\n  \"a\": [\n    1,\n    2\n  ]\n}");
+
+        let mut body = "not json".to_owned();
+        prettify(&mut body);
+        assert_eq!(body, "not json");
+
+        let mut body = String::new();
+        prettify(&mut body);
+        assert_eq!(body, "");
+    }
+
+    #[cfg(any(
+        feature = "reqwest_async",
+        feature = "reqwest_async_rustls",
+        feature = "reqwest_blocking",
+        feature = "reqwest_blocking_rustls"
+    ))]
+    #[test]
+    fn send_requests_of_reqwest_clients() {
+        let session: Session<uclient::reqwest::ReqwestClient> =
+            Session::with_options(HeaderMap::new(), Default::default()).unwrap();
+        let (_, headers) = (session.reqwest_parts)(&session.client).unwrap();
+        assert!(headers.contains_key(USER_AGENT));
+
+        let session =
+            Session::with_client(SizedClient(HeaderMap::new(), 0), Default::default()).unwrap();
+        assert!((session.reqwest_parts)(&session.client).is_none());
+
+        // unknown to be a `reqwest` client when built as a `ClientExt`
+        let session: Session<uclient::reqwest::ReqwestClient> = ClientExt::new(None).unwrap();
+        assert!((session.reqwest_parts)(&session.client).is_none());
+    }
+}
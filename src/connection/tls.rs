@@ -0,0 +1,238 @@
+//! `reqwest` based HTTP client with custom TLS settings.
+//!
+//! Use it to connect to servers whose certificate is not trusted by the
+//! system, e.g. a self-signed certificate in development or staging. A proxy
+//! can be set along with the TLS settings, see `TlsOptions::proxy`:
+//!
+//! ```rust, ignore
+//! use arangors::connection::{
+//!     options::ConnectionOptions,
+//!     tls::{TlsConnection, TlsOptions},
+//! };
+//!
+//! let tls = TlsOptions::builder()
+//!     .root_certificates(vec![std::fs::read("ca.pem")?])
+//!     .build();
+//! let conn = TlsConnection::establish_jwt_with_tls(
+//!     "https://localhost:8529",
+//!     "username",
+//!     "password",
+//!     ConnectionOptions::default(),
+//!     tls,
+//! )
+//! .await?;
+//! ```
+use http::{HeaderMap, HeaderValue, Request, Response};
+use log::warn;
+use maybe_async::maybe_async;
+#[cfg(feature = "blocking")]
+use reqwest::blocking::Client;
+use reqwest::Certificate;
+#[cfg(not(feature = "blocking"))]
+use reqwest::Client;
+use typed_builder::TypedBuilder;
+use uclient::{ClientError, ClientExt};
+
+use crate::ClientError as ArangoClientError;
+
+use super::{
+    auth::Auth,
+    binary::{send_reqwest, BinaryClientExt},
+    options::{ConnectionOptions, ProxyOptions},
+    proxy::{to_reqwest_proxy, ProxyClientExt},
+    role::Normal,
+    GenericConnection, Session,
+};
+
+/// Connection whose TLS settings can be configured with `TlsOptions`.
+pub type TlsConnection = GenericConnection<TlsClient>;
+
+/// TLS settings of a `TlsClient`.
+#[derive(Debug, Clone, Default, TypedBuilder)]
+#[builder(doc)]
+pub struct TlsOptions {
+    /// PEM encoded root certificates trusted in addition to the ones of the
+    /// system.
+    #[builder(default)]
+    pub(crate) root_certificates: Vec<Vec<u8>>,
+    /// Accept any server certificate, even an invalid or expired one.
+    ///
+    /// # Warning
+    /// This disables the verification of the server identity, which makes
+    /// the connection vulnerable to man-in-the-middle attacks. Only use it
+    /// for testing, prefer `root_certificates` otherwise.
+    #[builder(default)]
+    pub(crate) danger_accept_invalid_certs: bool,
+    /// Proxy every request is sent through, see `ProxyOptions`.
+    ///
+    /// Without custom TLS settings, use
+    /// `GenericConnection::establish_jwt_with_proxy` instead.
+    #[builder(default, setter(strip_option))]
+    pub(crate) proxy: Option<ProxyOptions>,
+}
+
+impl TlsOptions {
+    pub fn root_certificates(&self) -> &[Vec<u8>] {
+        &self.root_certificates
+    }
+
+    pub fn danger_accept_invalid_certs(&self) -> bool {
+        self.danger_accept_invalid_certs
+    }
+
+    pub fn proxy(&self) -> Option<&ProxyOptions> {
+        self.proxy.as_ref()
+    }
+}
+
+/// A `reqwest` client applying `TlsOptions`.
+///
+/// `ClientExt::new` builds a client with the default TLS settings, use
+/// `TlsClient::with_tls` to apply custom ones.
+#[derive(Debug, Clone)]
+pub struct TlsClient {
+    pub(crate) client: Client,
+    pub(crate) headers: HeaderMap,
+}
+
+impl TlsClient {
+    /// Build a client with default headers and TLS settings.
+    pub fn with_tls<U: Into<Option<HeaderMap>>>(
+        headers: U,
+        tls: &TlsOptions,
+    ) -> Result<Self, ClientError> {
+        let mut builder = Client::builder().gzip(true);
+        for pem in tls.root_certificates.iter() {
+            let certificate = Certificate::from_pem(pem)
+                .map_err(|e| ClientError::HttpClient(format!("invalid root certificate: {}", e)))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+        if tls.danger_accept_invalid_certs {
+            warn!("TLS certificate verification is disabled");
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+        if let Some(proxy) = &tls.proxy {
+            builder = builder.proxy(to_reqwest_proxy(proxy)?);
+        }
+        let client = builder
+            .build()
+            .map_err(|e| ClientError::HttpClient(format!("{:?}", e)))?;
+        Ok(TlsClient {
+            client,
+            headers: headers.into().unwrap_or_default(),
+        })
+    }
+}
+
+#[maybe_async]
+impl ClientExt for TlsClient {
+    fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError> {
+        TlsClient::with_tls(headers, &TlsOptions::default())
+    }
+
+    fn headers(&mut self) -> &mut HeaderMap<HeaderValue> {
+        &mut self.headers
+    }
+
+    async fn request(&self, request: Request<String>) -> Result<Response<String>, ClientError> {
+        send_reqwest(&self.client, &self.headers, request.map(String::into_bytes))
+            .await
+            .map_err(|e| e.error)
+    }
+}
+
+impl ProxyClientExt for TlsClient {
+    fn with_proxy<U: Into<Option<HeaderMap>>>(
+        headers: U,
+        proxy: &ProxyOptions,
+    ) -> Result<Self, ClientError> {
+        let tls = TlsOptions::builder().proxy(proxy.clone()).build();
+        TlsClient::with_tls(headers, &tls)
+    }
+}
+
+#[maybe_async]
+impl BinaryClientExt for TlsClient {
+    async fn request_binary(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> Result<Response<String>, ClientError> {
+        send_reqwest(&self.client, &self.headers, request)
+            .await
+            .map_err(|e| e.error)
+    }
+}
+
+impl GenericConnection<TlsClient, Normal> {
+    /// Establish connection to ArangoDB sever without authentication, with
+    /// custom TLS settings.
+    #[maybe_async]
+    pub async fn establish_without_auth_with_tls(
+        arango_url: &str,
+        options: ConnectionOptions,
+        tls: TlsOptions,
+    ) -> Result<Self, ArangoClientError> {
+        Self::establish_with_tls(arango_url, Auth::None, options, tls).await
+    }
+
+    /// Establish connection to ArangoDB sever with basic auth, with custom
+    /// TLS settings.
+    #[maybe_async]
+    pub async fn establish_basic_auth_with_tls(
+        arango_url: &str,
+        username: &str,
+        password: &str,
+        options: ConnectionOptions,
+        tls: TlsOptions,
+    ) -> Result<Self, ArangoClientError> {
+        Self::establish_with_tls(arango_url, Auth::basic(username, password), options, tls).await
+    }
+
+    /// Establish connection to ArangoDB sever with jwt authentication, with
+    /// custom TLS settings.
+    #[maybe_async]
+    pub async fn establish_jwt_with_tls(
+        arango_url: &str,
+        username: &str,
+        password: &str,
+        options: ConnectionOptions,
+        tls: TlsOptions,
+    ) -> Result<Self, ArangoClientError> {
+        Self::establish_with_tls(arango_url, Auth::jwt(username, password), options, tls).await
+    }
+
+    #[maybe_async]
+    async fn establish_with_tls(
+        arango_url: &str,
+        auth: Auth<'_>,
+        options: ConnectionOptions,
+        tls: TlsOptions,
+    ) -> Result<Self, ArangoClientError> {
+        Self::establish_with_client(arango_url, auth, |headers| {
+            let client = TlsClient::with_tls(headers, &tls)?;
+            Ok(Session::with_client(client, options.clone())?)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tls_with_proxy() {
+        let proxy = ProxyOptions::builder()
+            .url("http://proxy.example.com:3128")
+            .build();
+        let tls = TlsOptions::builder().proxy(proxy.clone()).build();
+        assert_eq!(tls.proxy().map(ProxyOptions::url), Some(proxy.url()));
+        assert!(TlsClient::with_tls(HeaderMap::new(), &tls).is_ok());
+        assert!(TlsClient::with_proxy(HeaderMap::new(), &proxy).is_ok());
+
+        let tls = TlsOptions::builder()
+            .proxy(ProxyOptions::builder().url("not a url").build())
+            .build();
+        assert!(TlsClient::with_tls(HeaderMap::new(), &tls).is_err());
+    }
+}
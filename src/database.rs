@@ -4,27 +4,47 @@
 use std::{collections::HashMap, fmt::Debug, sync::Arc};
 use uclient::ClientExt;
 
+#[cfg(not(feature = "blocking"))]
+use futures_util::stream::{self, Stream};
+
+#[cfg(not(feature = "blocking"))]
+use crate::replication::Delay;
+
+use http::{header::CONTENT_TYPE, Method, Request};
 use log::trace;
 use maybe_async::maybe_async;
-use serde::{de::DeserializeOwned, Deserialize};
-use serde_json::value::Value;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, value::Value};
+use typed_builder::TypedBuilder;
 use url::Url;
 
 use crate::{
     analyzer::{AnalyzerDescription, AnalyzerInfo},
-    aql::{AqlQuery, Cursor},
+    aql::{
+        suggest_indexes, AqlQuery, AqlWarning, Cursor, ExplainResult, IndexSuggestion, RunningQuery,
+    },
     collection::{
         options::{CreateOptions, CreateParameters},
         response::{Info, Properties},
         Collection, CollectionType,
     },
-    connection::Version,
+    connection::{BinaryClientExt, Session, Version},
+    foxx::{FoxxService, FoxxSource, InstallOptions, UpgradeOptions},
     graph::{Graph, GraphCollection, GraphResponse, GHARIAL_API_PATH},
     index::{DeleteIndexResponse, Index, IndexCollection, INDEX_API_PATH},
-    response::{deserialize_response, ArangoResult},
+    job::{JobId, ASYNC_ID_HEADER},
+    replication::{
+        Inventory, ReplicationBatch, WalBatch, WalEntry, WalTail, WalTailOptions,
+        CHECK_MORE_HEADER, LAST_INCLUDED_HEADER, LAST_SCANNED_HEADER,
+    },
+    response::{
+        deserialize_array_response, deserialize_lines, deserialize_response,
+        deserialize_response_with_status, ArangoResult,
+    },
+    task::{Task, TaskInfo},
     transaction::{
-        ArangoTransaction, Transaction, TransactionList, TransactionSettings, TransactionState,
-        TRANSACTION_HEADER,
+        ArangoTransaction, Transaction, TransactionCollections, TransactionList,
+        TransactionSettings, TransactionState, TRANSACTION_HEADER,
     },
     user::{
         access_level_enum_to_str, DeleteUserResponse, User, UserAccessLevel,
@@ -41,21 +61,40 @@ use crate::{
 pub struct Database<C: ClientExt> {
     name: String,
     base_url: Url,
-    session: Arc<C>,
+    session: Arc<Session<C>>,
+    default_options: DefaultOptions,
 }
 
 impl<'a, C: ClientExt> Database<C> {
-    pub(crate) fn new<T: Into<String>>(name: T, arango_url: &Url, session: Arc<C>) -> Database<C> {
+    pub(crate) fn new<T: Into<String>>(
+        name: T,
+        arango_url: &Url,
+        session: Arc<Session<C>>,
+    ) -> Database<C> {
         let name = name.into();
-        let path = format!("/_db/{}/", name.as_str());
+        let path = format!("_db/{}/", name.as_str());
         let url = arango_url.join(path.as_str()).unwrap();
         Database {
             name,
             session,
             base_url: url,
+            default_options: DefaultOptions::default(),
         }
     }
 
+    /// Use `default_options` for the requests made through this database
+    /// and the collections obtained from it, replacing the ones inherited
+    /// from the connection.
+    pub fn with_default_options(mut self, default_options: DefaultOptions) -> Self {
+        self.default_options = default_options;
+        self
+    }
+
+    /// Get the options used when a request does not set them itself.
+    pub fn default_options(&self) -> &DefaultOptions {
+        &self.default_options
+    }
+
     /// Retrieve all collections of this database.
     ///
     /// # Note
@@ -86,7 +125,7 @@ impl<'a, C: ClientExt> Database<C> {
         &self.name
     }
 
-    pub fn session(&self) -> Arc<C> {
+    pub fn session(&self) -> Arc<Session<C>> {
         Arc::clone(&self.session)
     }
 
@@ -104,6 +143,20 @@ impl<'a, C: ClientExt> Database<C> {
         Ok(Collection::from_response(self, &resp))
     }
 
+    /// Get collection object with name, or `None` if the collection does not
+    /// exist.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn try_collection(&self, name: &str) -> Result<Option<Collection<C>>, ClientError> {
+        match self.collection(name).await {
+            Ok(collection) => Ok(Some(collection)),
+            Err(e) if e.is_data_source_not_found() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Create a collection via HTTP request with options.
     ///
     /// Return a collection object if success.
@@ -116,6 +169,27 @@ impl<'a, C: ClientExt> Database<C> {
         options: CreateOptions<'f>,
         parameters: CreateParameters,
     ) -> Result<Collection<C>, ClientError> {
+        let (collection, _) = self
+            .create_collection_with_properties(options, parameters)
+            .await?;
+        Ok(collection)
+    }
+
+    /// Create a collection via HTTP request with options.
+    ///
+    /// Return a collection object along with the properties of the created
+    /// collection, including the sharding and replication settings in a
+    /// cluster, so that no additional call to `Collection::properties` is
+    /// needed.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn create_collection_with_properties<'f>(
+        &self,
+        options: CreateOptions<'f>,
+        parameters: CreateParameters,
+    ) -> Result<(Collection<C>, Properties), ClientError> {
         let mut url = self.base_url.join("_api/collection").unwrap();
         let query = serde_qs::to_string(&parameters).unwrap();
         url.set_query(Some(query.as_str()));
@@ -125,7 +199,7 @@ impl<'a, C: ClientExt> Database<C> {
             .post(url, &serde_json::to_string(&options)?)
             .await?;
         let result: Properties = deserialize_response(resp.body())?;
-        self.collection(&result.info.name).await
+        Ok((Collection::from_response(self, &result.info), result))
     }
 
     /// Create a collection via HTTP request.
@@ -143,6 +217,13 @@ impl<'a, C: ClientExt> Database<C> {
         .await
     }
 
+    /// Create an edge collection via HTTP request.
+    ///
+    /// Same as `create_collection`, with the collection type set to
+    /// `CollectionType::Edge`.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
     #[maybe_async]
     pub async fn create_edge_collection(&self, name: &str) -> Result<Collection<C>, ClientError> {
         self.create_collection_with_options(
@@ -157,21 +238,29 @@ impl<'a, C: ClientExt> Database<C> {
 
     /// Drops a collection
     ///
+    /// Return `true` if the collection existed and was dropped, `false` if
+    /// there was no such collection.
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
-    pub async fn drop_collection(&self, name: &str) -> Result<String, ClientError> {
+    pub async fn drop_collection(&self, name: &str) -> Result<bool, ClientError> {
         let url_path = format!("_api/collection/{}", name);
         let url = self.base_url.join(&url_path).unwrap();
 
         #[derive(Debug, Deserialize)]
         struct DropCollectionResponse {
+            #[allow(dead_code)]
             id: String,
         }
 
-        let resp: DropCollectionResponse =
-            deserialize_response(self.session.delete(url, "").await?.body())?;
-        Ok(resp.id)
+        let resp = self.session.delete(url, "").await?;
+        match deserialize_response_with_status::<DropCollectionResponse>(resp.status(), resp.body())
+        {
+            Ok(_) => Ok(true),
+            Err(e) if e.is_data_source_not_found() => Ok(false),
+            Err(e) => Err(e),
+        }
     }
 
     /// Get the version remote arango database server
@@ -210,12 +299,16 @@ impl<'a, C: ClientExt> Database<C> {
     where
         R: DeserializeOwned,
     {
+        aql.check_database(&self.base_url)?;
+        let mut aql = aql;
+        aql.apply_defaults(&self.default_options);
         let url = self.base_url.join("_api/cursor").unwrap();
         let resp = self
             .session
             .post(url, &serde_json::to_string(&aql)?)
             .await?;
         deserialize_response(resp.body())
+            .map_err(|e| query_batch_error(e, aql.query(), 0, aql.fails_on_warning()))
     }
 
     /// Get next batch given the cursor id.
@@ -236,17 +329,27 @@ impl<'a, C: ClientExt> Database<C> {
     }
 
     #[maybe_async]
-    async fn aql_fetch_all<R>(&self, response: Cursor<R>) -> Result<Vec<R>, ClientError>
+    async fn aql_fetch_all<R>(
+        &self,
+        query: &str,
+        fail_on_warning: bool,
+        response: Cursor<R>,
+    ) -> Result<Vec<R>, ClientError>
     where
         R: DeserializeOwned,
     {
         let mut response_cursor = response;
         let mut results: Vec<R> = Vec::new();
+        let mut batch = 0;
         loop {
             results.extend(response_cursor.result.into_iter());
             if response_cursor.more {
                 let id = response_cursor.id.unwrap().clone();
-                response_cursor = self.aql_next_batch(id.as_str()).await?;
+                batch += 1;
+                response_cursor = self
+                    .aql_next_batch(id.as_str())
+                    .await
+                    .map_err(|e| query_batch_error(e, query, batch, fail_on_warning))?;
             } else {
                 break;
             }
@@ -269,14 +372,95 @@ impl<'a, C: ClientExt> Database<C> {
     where
         R: DeserializeOwned,
     {
+        let query = aql.query();
+        let fail_on_warning = aql.fails_on_warning();
         let response = self.aql_query_batch(aql).await?;
         if response.more {
-            self.aql_fetch_all(response).await
+            self.aql_fetch_all(query, fail_on_warning, response).await
         } else {
             Ok(response.result)
         }
     }
 
+    /// Execute an AQL query returning at most one result, e.g. a count or an
+    /// existence check, and return this result, if any.
+    ///
+    /// Only the first batch is fetched, hence a batch size below 2 is raised
+    /// to 2 so that a second result comes with it. It fails with
+    /// `ClientError::TooManyResults` if the query returns more than one
+    /// result, add `LIMIT 1` to the query to only keep the first one instead.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_query_one<R>(&self, aql: AqlQuery<'_>) -> Result<Option<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        let query = aql.query();
+        let mut aql = aql;
+        aql.set_min_batch_size(2);
+        let cursor: Cursor<R> = self.aql_query_batch(aql).await?;
+        // a streaming cursor may report more results after a full batch even
+        // when there are none, so only the results are trusted
+        if cursor.more {
+            if let Some(id) = &cursor.id {
+                // the cursor would expire anyway, so a failure to delete it is
+                // not worth reporting over the result
+                let url = self.base_url.join(&format!("_api/cursor/{}", id)).unwrap();
+                let _ = self.session.delete(url, "").await;
+            }
+        }
+        if cursor.result.len() > 1 {
+            return Err(ClientError::too_many_results(query));
+        }
+        Ok(cursor.result.into_iter().next())
+    }
+
+    /// Execute AQL query fetch all results, along with the warnings raised by
+    /// the query.
+    ///
+    /// Warnings, e.g. a division by zero, do not abort a query unless it is
+    /// run with `fail_on_warning` set in its `AqlOptions`, in which case the
+    /// query fails with `ClientError::QueryWarning` instead. The number of
+    /// warnings returned is limited by `max_warning_count`.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_query_with_warnings<R>(
+        &self,
+        aql: AqlQuery<'_>,
+    ) -> Result<(Vec<R>, Vec<AqlWarning>), ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        let query = aql.query();
+        let fail_on_warning = aql.fails_on_warning();
+        let mut cursor = self.aql_query_batch(aql).await?;
+        let mut results: Vec<R> = Vec::new();
+        let mut warnings = Vec::new();
+        let mut batch = 0;
+        loop {
+            results.extend(cursor.result);
+            // the warnings of a streaming query come with its last batch
+            if let Some(extra) = cursor.extra {
+                warnings.extend(extra.warnings.unwrap_or_default());
+            }
+            match cursor.id {
+                Some(id) if cursor.more => {
+                    batch += 1;
+                    cursor = self
+                        .aql_next_batch(id.as_str())
+                        .await
+                        .map_err(|e| query_batch_error(e, query, batch, fail_on_warning))?;
+                }
+                _ => break,
+            }
+        }
+        Ok((results, warnings))
+    }
+
     /// Similar to `aql_query`, except that this method only accept a string of
     /// AQL query.
     ///
@@ -312,6 +496,309 @@ impl<'a, C: ClientExt> Database<C> {
         self.aql_query(aql).await
     }
 
+    /// Insert `docs` into `collection` with a single AQL query, returning the
+    /// keys of the inserted documents in the same order.
+    ///
+    /// Handy to insert computed documents whose keys are generated by the
+    /// server, e.g. to create edges to them afterwards. The documents are
+    /// passed as a bind variable, so they are not interpolated into the
+    /// query.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_insert_returning_keys<T>(
+        &self,
+        collection: &str,
+        docs: &[T],
+    ) -> Result<Vec<String>, ClientError>
+    where
+        T: Serialize,
+    {
+        let mut bind_vars: HashMap<&str, Value> = HashMap::new();
+        bind_vars.insert("@collection", collection.into());
+        bind_vars.insert("docs", serde_json::to_value(docs)?);
+        self.aql_bind_vars(
+            "FOR doc IN @docs INSERT doc INTO @@collection RETURN NEW._key",
+            bind_vars,
+        )
+        .await
+    }
+
+    /// Execute an AQL query string and return the first batch as raw JSON
+    /// values, along with the count, cursor id and extra information (stats
+    /// and warnings) of the query.
+    ///
+    /// This is meant for schema-less tooling. Remaining batches can be
+    /// fetched with `aql_next_batch` while `more` is true.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_query_raw(&self, query: &str) -> Result<Cursor<Value>, ClientError> {
+        let aql = AqlQuery::builder().query(query).count(true).build();
+        self.aql_query_batch(aql).await
+    }
+
+    /// Explain an AQL query, returning the execution plan chosen by the
+    /// optimizer without executing the query.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn explain_query(&self, aql: AqlQuery<'_>) -> Result<ExplainResult, ClientError> {
+        aql.check_database(&self.base_url)?;
+        let url = self.base_url.join("_api/explain").unwrap();
+        let resp = self
+            .session
+            .post(url, &serde_json::to_string(&aql.explain_request())?)
+            .await?;
+        deserialize_response(resp.body())
+    }
+
+    /// Find collections that are scanned in full and filtered on some
+    /// attributes by an AQL query, and thus might benefit from an index.
+    ///
+    /// This is a **heuristic** based on the execution plan returned by
+    /// `explain_query`. It only detects attributes directly accessed on the
+    /// documents of a full collection scan by a `FILTER`, and cannot tell
+    /// whether an index on them would be selective enough to be picked by the
+    /// optimizer.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn missing_indexes(
+        &self,
+        aql: AqlQuery<'_>,
+    ) -> Result<Vec<IndexSuggestion>, ClientError> {
+        let explain = self.explain_query(aql).await?;
+        Ok(suggest_indexes(&explain))
+    }
+
+    /// List the AQL queries currently running in this database
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn running_queries(&self) -> Result<Vec<RunningQuery>, ClientError> {
+        let url = self.base_url.join("_api/query/current").unwrap();
+        let resp = self.session.get(url, "").await?;
+        deserialize_array_response(resp.body())
+    }
+
+    /// Kill a running AQL query
+    ///
+    /// The query is aborted at its next cancellation point, so it may keep
+    /// running for a short while.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn kill_query(&self, id: &str) -> Result<(), ClientError> {
+        let url = self.base_url.join(&format!("_api/query/{}", id)).unwrap();
+        let resp = self.session.delete(url, "").await?;
+        deserialize_response_with_status::<Value>(resp.status(), resp.body())?;
+        Ok(())
+    }
+
+    /// Kill the running AQL queries whose query string contains `pattern`,
+    /// returning how many were killed
+    ///
+    /// A query that completes before it is killed is not counted.
+    ///
+    /// # Note
+    /// this function would make several requests to arango server.
+    #[maybe_async]
+    pub async fn kill_queries_matching(&self, pattern: &str) -> Result<usize, ClientError> {
+        let mut killed = 0;
+        for query in self.running_queries().await? {
+            if !query.query.contains(pattern) {
+                continue;
+            }
+            match self.kill_query(&query.id).await {
+                Ok(()) => killed += 1,
+                Err(e) if e.is_not_found() => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(killed)
+    }
+
+    /// Stream the operations logged in the write-ahead log after tick `from`.
+    ///
+    /// The log is polled with `tail_wal_batch`, every poll resuming right
+    /// after the last entry of the previous one. Once the server has nothing
+    /// new, polls are spaced out by the poll interval of `options`, which
+    /// doubles while the batches stay empty. The stream only ends once the
+    /// `to` tick of `options` is reached, if set.
+    ///
+    /// A failed poll yields its error and is retried after the poll interval;
+    /// stop consuming the stream to give up.
+    ///
+    /// With the blocking clients, an iterator is returned instead, which
+    /// sleeps between polls.
+    #[cfg(not(feature = "blocking"))]
+    pub fn tail_wal<'s>(
+        &'s self,
+        from: &str,
+        options: WalTailOptions,
+    ) -> impl Stream<Item = Result<WalEntry, ClientError>> + 's {
+        stream::unfold(WalTail::new(from, options), move |mut tail| async move {
+            loop {
+                if let Some(entry) = tail.next_entry() {
+                    return Some((Ok(entry), tail));
+                }
+                if tail.is_done() {
+                    return None;
+                }
+                if let Some(wait) = tail.take_wait() {
+                    Delay::new(wait).await;
+                }
+                let (from, options) = tail.poll_args();
+                match self.tail_wal_batch(from, options).await {
+                    Ok(batch) => tail.advance(batch),
+                    Err(e) => {
+                        tail.fail();
+                        return Some((Err(e), tail));
+                    }
+                }
+            }
+        })
+    }
+
+    /// Iterate over the operations logged in the write-ahead log after tick
+    /// `from`.
+    ///
+    /// The log is polled with `tail_wal_batch`, every poll resuming right
+    /// after the last entry of the previous one. Once the server has nothing
+    /// new, the iterator sleeps for the poll interval of `options`, which
+    /// doubles while the batches stay empty. The iterator only ends once the
+    /// `to` tick of `options` is reached, if set.
+    ///
+    /// A failed poll yields its error and is retried after the poll interval;
+    /// stop iterating to give up.
+    #[cfg(feature = "blocking")]
+    pub fn tail_wal<'s>(
+        &'s self,
+        from: &str,
+        options: WalTailOptions,
+    ) -> impl Iterator<Item = Result<WalEntry, ClientError>> + 's {
+        let mut tail = WalTail::new(from, options);
+        std::iter::from_fn(move || loop {
+            if let Some(entry) = tail.next_entry() {
+                return Some(Ok(entry));
+            }
+            if tail.is_done() {
+                return None;
+            }
+            if let Some(wait) = tail.take_wait() {
+                std::thread::sleep(wait);
+            }
+            let (from, options) = tail.poll_args();
+            match self.tail_wal_batch(from, options) {
+                Ok(batch) => tail.advance(batch),
+                Err(e) => {
+                    tail.fail();
+                    return Some(Err(e));
+                }
+            }
+        })
+    }
+
+    /// Fetch the operations logged in the write-ahead log after tick `from`.
+    ///
+    /// The returned batch contains the ticks needed to resume tailing: pass
+    /// `last_included` as `from` to the next call, and keep polling while
+    /// `check_more` is true. An empty batch means there is currently nothing
+    /// new.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn tail_wal_batch(
+        &self,
+        from: &str,
+        options: WalTailOptions,
+    ) -> Result<WalBatch, ClientError> {
+        let mut url = self.base_url.join("_api/wal/tail").unwrap();
+        let query = serde_qs::to_string(&options).unwrap();
+        url.set_query(Some(query.as_str()));
+        url.query_pairs_mut().append_pair("from", from);
+
+        let resp = self.session.get(url, "").await?;
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_owned())
+        };
+        let last_included = header(LAST_INCLUDED_HEADER).filter(|tick| tick != "0");
+        let last_scanned = header(LAST_SCANNED_HEADER);
+        let check_more = header(CHECK_MORE_HEADER).is_some_and(|v| v == "true");
+
+        Ok(WalBatch {
+            entries: deserialize_lines(resp.body())?,
+            last_included,
+            last_scanned,
+            check_more,
+        })
+    }
+
+    /// Return the collections and views of the database, along with the
+    /// current tick of the server.
+    ///
+    /// System collections are only included if `include_system` is true.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn replication_inventory(
+        &self,
+        include_system: bool,
+    ) -> Result<Inventory, ClientError> {
+        let mut url = self.base_url.join("_api/replication/inventory").unwrap();
+        url.set_query(Some(&format!("includeSystem={}", include_system)));
+
+        let resp = self.session.get(url, "").await?;
+        deserialize_response(resp.body())
+    }
+
+    /// Create a replication batch, which holds a consistent snapshot of the
+    /// database for `ttl` seconds. Its id is required to dump collections on
+    /// the RocksDB storage engine.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn create_replication_batch(
+        &self,
+        ttl: u32,
+    ) -> Result<ReplicationBatch, ClientError> {
+        let url = self.base_url.join("_api/replication/batch").unwrap();
+        let body = serde_json::json!({ "ttl": ttl });
+
+        let resp = self.session.post(url, body.to_string()).await?;
+        deserialize_response(resp.body())
+    }
+
+    /// Delete a replication batch, releasing the snapshot it holds.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn delete_replication_batch(&self, id: &str) -> Result<(), ClientError> {
+        let url = self
+            .base_url
+            .join(&format!("_api/replication/batch/{}", id))
+            .unwrap();
+
+        let resp = self.session.delete(url, "").await?;
+        // responds with 204 and no body on success
+        deserialize_response_with_status::<Option<Value>>(resp.status(), resp.body())?;
+        Ok(())
+    }
+
     /// Create a new index on a collection.
     ///
     /// # Note
@@ -485,6 +972,57 @@ impl<'a, C: ClientExt> Database<C> {
         Ok(result.transactions)
     }
 
+    /// Execute a JavaScript transaction on the server and return the result of
+    /// `action`
+    ///
+    /// `action` is the source of a JavaScript function, which receives
+    /// `params` as its argument. The collections it accesses must be declared
+    /// in `collections`, e.g. with `TransactionCollections::from_collections`.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let count: u64 = database
+    ///     .execute_transaction(
+    ///         TransactionCollections::from_collections(&[], &[&collection]),
+    ///         "function (params) {
+    ///             const db = require('@arangodb').db;
+    ///             db._collection(params.collection).insert({ name: params.name });
+    ///             return db._collection(params.collection).count();
+    ///         }",
+    ///         &json!({ "collection": collection.name(), "name": "alice" }),
+    ///     )
+    ///     .await?;
+    /// ```
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn execute_transaction<T, P>(
+        &self,
+        collections: TransactionCollections,
+        action: &str,
+        params: &P,
+    ) -> Result<T, ClientError>
+    where
+        T: DeserializeOwned,
+        P: Serialize + ?Sized,
+    {
+        let url = self.base_url.join("_api/transaction").unwrap();
+
+        let mut body = json!({
+            "collections": collections,
+            "action": action,
+        });
+        let params = serde_json::to_value(params)?;
+        if !params.is_null() {
+            body["params"] = params;
+        }
+        let resp = self.session.post(url, body.to_string()).await?;
+
+        let result: ArangoResult<T> = deserialize_response(resp.body())?;
+        Ok(result.unwrap())
+    }
+
     /// Begin a server-side transaction, the transaction settings should specify
     /// at least collections to be updated through the write list
     ///
@@ -719,6 +1257,123 @@ impl<'a, C: ClientExt> Database<C> {
         Ok(result)
     }
 
+    /// Register a server side task, see `Task`
+    ///
+    /// The task is registered with its `id` when set, otherwise the server
+    /// generates one. Registering a task with the id of an existing one
+    /// fails.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn create_task(&self, task: Task) -> Result<TaskInfo, ClientError> {
+        let body = serde_json::to_string(&task)?;
+        let resp = match &task.id {
+            Some(id) => {
+                let url = self.base_url.join(&format!("_api/tasks/{}", id)).unwrap();
+                self.session.put(url, body).await?
+            }
+            None => {
+                let url = self.base_url.join("_api/tasks").unwrap();
+                self.session.post(url, body).await?
+            }
+        };
+        deserialize_response(resp.body())
+    }
+
+    /// List the server side tasks
+    ///
+    /// The tasks of all databases are listed, see `TaskInfo::database`.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn list_tasks(&self) -> Result<Vec<TaskInfo>, ClientError> {
+        let url = self.base_url.join("_api/tasks").unwrap();
+        let resp = self.session.get(url, "").await?;
+        deserialize_array_response(resp.body())
+    }
+
+    /// Get a server side task by id
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn task(&self, id: &str) -> Result<TaskInfo, ClientError> {
+        let url = self.base_url.join(&format!("_api/tasks/{}", id)).unwrap();
+        let resp = self.session.get(url, "").await?;
+        deserialize_response(resp.body())
+    }
+
+    /// Unregister a server side task, which stops a periodic task
+    ///
+    /// A run in progress is not interrupted.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn delete_task(&self, id: &str) -> Result<(), ClientError> {
+        let url = self.base_url.join(&format!("_api/tasks/{}", id)).unwrap();
+        let resp = self.session.delete(url, "").await?;
+        deserialize_response_with_status::<Value>(resp.status(), resp.body())?;
+        Ok(())
+    }
+
+    /// Fetch the result of an async job
+    ///
+    /// Returns `None` while the job is pending. Once the job is done, the
+    /// response of the original request is deserialized into `T`, or turned
+    /// into an error if the request failed. The server forgets about the
+    /// result once it has been fetched, hence a second call for the same job
+    /// fails with a not found error.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn job_result<T>(&self, job: &JobId) -> Result<Option<T>, ClientError>
+    where
+        T: DeserializeOwned,
+    {
+        let url = self
+            .base_url
+            .join(&format!("_api/job/{}", job.as_str()))
+            .unwrap();
+        let resp = self.session.put(url, "").await?;
+        // the response of a finished job carries its id, whereas a pending job
+        // is answered with an empty `204 No Content`
+        if resp.headers().get(ASYNC_ID_HEADER).is_none() && resp.status().as_u16() == 204 {
+            return Ok(None);
+        }
+        self.session.forget_job(job);
+        deserialize_response(resp.body()).map(Some)
+    }
+
+    /// List the Foxx services installed in this database, system services
+    /// excluded
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn list_foxx_services(&self) -> Result<Vec<FoxxService>, ClientError> {
+        let url = self.base_url.join("_api/foxx?excludeSystem=true").unwrap();
+        let resp = self.session.get(url, "").await?;
+        deserialize_array_response(resp.body())
+    }
+
+    /// Uninstall the Foxx service mounted at `mount`, running its teardown
+    /// script
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn uninstall_foxx_service(&self, mount: &str) -> Result<(), ClientError> {
+        let mut url = self.base_url.join("_api/foxx/service").unwrap();
+        url.query_pairs_mut().append_pair("mount", mount);
+        let resp = self.session.delete(url, "").await?;
+        deserialize_response_with_status::<Value>(resp.status(), resp.body())?;
+        Ok(())
+    }
+
     /// List available users
     ///
     /// Fetches data about all users. You need the Administrate server access
@@ -918,6 +1573,118 @@ impl<'a, C: ClientExt> Database<C> {
     }
 }
 
+/// Foxx services are uploaded as binary content, e.g. a zip bundle, hence
+/// they are installed with clients able to send it.
+impl<C: BinaryClientExt> Database<C> {
+    /// Install a Foxx service at `mount`, e.g. `/hello`
+    ///
+    /// Installing a service at the mount path of another one fails, see
+    /// `replace_foxx_service`.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn install_foxx_service(
+        &self,
+        mount: &str,
+        source: FoxxSource,
+        options: InstallOptions,
+    ) -> Result<FoxxService, ClientError> {
+        self.send_foxx_service(Method::POST, "_api/foxx", mount, &source, &options)
+            .await
+    }
+
+    /// Upgrade the Foxx service mounted at `mount`, keeping its
+    /// configuration and dependencies
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn upgrade_foxx_service(
+        &self,
+        mount: &str,
+        source: FoxxSource,
+        options: UpgradeOptions,
+    ) -> Result<FoxxService, ClientError> {
+        self.send_foxx_service(Method::PATCH, "_api/foxx/service", mount, &source, &options)
+            .await
+    }
+
+    /// Replace the Foxx service mounted at `mount`, discarding its
+    /// configuration and dependencies
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn replace_foxx_service(
+        &self,
+        mount: &str,
+        source: FoxxSource,
+        options: UpgradeOptions,
+    ) -> Result<FoxxService, ClientError> {
+        self.send_foxx_service(Method::PUT, "_api/foxx/service", mount, &source, &options)
+            .await
+    }
+
+    /// Send the code of a service to `path`, along with its mount path and
+    /// `options` as query parameters.
+    #[maybe_async]
+    async fn send_foxx_service<O>(
+        &self,
+        method: Method,
+        path: &str,
+        mount: &str,
+        source: &FoxxSource,
+        options: &O,
+    ) -> Result<FoxxService, ClientError>
+    where
+        O: Serialize,
+    {
+        let mut url = self.base_url.join(path).unwrap();
+        let query = serde_qs::to_string(options).unwrap();
+        url.set_query(Some(query.as_str()));
+        url.query_pairs_mut().append_pair("mount", mount);
+
+        let req = Request::builder()
+            .method(method)
+            .uri(url.to_string())
+            .header(CONTENT_TYPE, source.content_type())
+            .body(source.body())
+            .unwrap();
+        let resp = self.session.request_binary(req).await?;
+        deserialize_response(resp.body())
+    }
+}
+
+/// Options applied to the requests that do not set them
+///
+/// Set them once, with `GenericConnection::with_default_options` or
+/// `Database::with_default_options`, instead of on every call. An option
+/// given to a call takes precedence over the default, which takes
+/// precedence over the default of the server. Unset defaults change nothing.
+#[derive(Debug, Clone, Default, PartialEq, TypedBuilder)]
+#[builder(doc)]
+pub struct DefaultOptions {
+    /// Wait until documents are synced to disk, for the document writes of
+    /// `Collection`.
+    #[builder(default, setter(strip_option))]
+    pub(crate) wait_for_sync: Option<bool>,
+    /// Maximum number of results of an AQL query transferred in one
+    /// round-trip.
+    #[builder(default, setter(strip_option))]
+    pub(crate) batch_size: Option<u32>,
+}
+
+impl DefaultOptions {
+    pub fn wait_for_sync(&self) -> Option<bool> {
+        self.wait_for_sync
+    }
+
+    pub fn batch_size(&self) -> Option<u32> {
+        self.batch_size
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DatabaseDetails {
@@ -926,3 +1693,20 @@ pub struct DatabaseDetails {
     pub path: String,
     pub is_system: bool,
 }
+
+/// Name the query and batch of an error raised while fetching its results,
+/// and cast the errors raised by a warning when the query is run with
+/// `fail_on_warning`.
+pub(crate) fn query_batch_error(
+    err: ClientError,
+    query: &str,
+    batch: usize,
+    fail_on_warning: bool,
+) -> ClientError {
+    let err = err.in_query_batch(query, batch);
+    if fail_on_warning {
+        err.on_query_warning()
+    } else {
+        err
+    }
+}
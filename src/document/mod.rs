@@ -8,6 +8,8 @@ use serde::{
 };
 use std::ops::Deref;
 
+use crate::response::is_error_stub;
+
 pub mod options;
 pub mod response;
 
@@ -30,10 +32,7 @@ pub struct Document<T> {
     pub document: T,
 }
 
-impl<T> Document<T>
-where
-    T: Serialize + DeserializeOwned,
-{
+impl<T> Document<T> {
     pub fn new(data: T) -> Self {
         Document {
             document: data,
@@ -46,6 +45,31 @@ where
     }
 }
 
+impl<T> Document<T>
+where
+    T: Serialize,
+{
+    /// Serialize the document into a request body for a write, without the
+    /// `_id` and `_rev` system attributes.
+    ///
+    /// A document read from the server carries its `_rev`, and sending it
+    /// back with a replace can fail with a precondition error. `_key` is kept
+    /// only if `keep_key` is true.
+    pub fn to_body(&self, keep_key: bool) -> Result<serde_json::Value, serde_json::Error> {
+        let mut body = serde_json::to_value(&self.document)?;
+        if let Some(obj) = body.as_object_mut() {
+            obj.remove("_id");
+            obj.remove("_rev");
+            if !keep_key {
+                obj.remove("_key");
+            } else if !self.header._key.is_empty() {
+                obj.insert("_key".to_owned(), self.header._key.clone().into());
+            }
+        }
+        Ok(body)
+    }
+}
+
 impl<T> AsRef<T> for Document<T> {
     fn as_ref(&self) -> &T {
         &self.document
@@ -70,10 +94,23 @@ where
     {
         let mut obj = serde_json::Value::deserialize(deserializer)?;
 
+        // bulk operations send an error stub in place of a document they
+        // could not process
+        let is_error_stub = is_error_stub(&obj);
         let json = obj
             .as_object_mut()
             .ok_or_else(|| DeError::custom("should be a json object"))?;
 
+        if is_error_stub {
+            return Err(DeError::custom(format!(
+                "got an error instead of a document: {}({})",
+                json.get("errorMessage")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or_default(),
+                json.get("errorNum").unwrap_or(&serde_json::Value::Null)
+            )));
+        }
+
         let _id = json
             .get("_id")
             .ok_or_else(|| DeError::missing_field("_id"))?;
@@ -93,3 +130,39 @@ where
         Ok(Document { header, document })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    #[test]
+    fn error_stub_is_not_a_document() {
+        let err = serde_json::from_value::<Document<Value>>(json!({
+            "_key": "missing",
+            "error": true,
+            "errorNum": 1202,
+            "errorMessage": "document not found"
+        }))
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "got an error instead of a document: document not found(1202)"
+        );
+    }
+
+    #[test]
+    fn document_with_error_attribute() {
+        let doc = serde_json::from_value::<Document<Value>>(json!({
+            "_id": "jobs/1",
+            "_key": "1",
+            "_rev": "_abc",
+            "error": true,
+            "errorNum": 42
+        }))
+        .unwrap();
+        assert_eq!(doc.header._key, "1");
+        assert_eq!(doc.document["error"], json!(true));
+    }
+}
@@ -3,7 +3,20 @@ use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
 /// Options for document insertion.
-#[derive(Debug, Serialize, Deserialize, PartialEq, TypedBuilder)]
+///
+/// Like every option type, it is built with `InsertOptions::builder()` and
+/// every setter returns the builder, which is turned into the options with
+/// `build()`:
+///
+/// ```rust
+/// use arangors::document::options::InsertOptions;
+///
+/// let options = InsertOptions::builder()
+///     .return_new(true)
+///     .silent(false)
+///     .build();
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TypedBuilder)]
 #[builder(doc)]
 #[serde(rename_all = "camelCase")]
 pub struct InsertOptions {
@@ -34,7 +47,8 @@ pub struct InsertOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     overwrite: Option<bool>,
-    /// TODO add nice formatted documentation from official doc
+    /// What to do when a document with the same _key already exists, see
+    /// `OverwriteMode`. Setting it implies `overwrite`.
     #[cfg(feature = "arango3_7")]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
@@ -61,6 +75,15 @@ pub struct InsertOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     merge_objects: Option<bool>,
+
+    /// Reject documents carrying `_id` or `_rev` instead of letting the
+    /// server silently ignore them, which usually means a document read
+    /// before is inserted again without stripping its system attributes.
+    ///
+    /// This check is done by the client and is not sent to the server.
+    #[serde(skip)]
+    #[builder(default, setter(strip_option))]
+    strict: Option<bool>,
 }
 
 impl Default for InsertOptions {
@@ -69,6 +92,12 @@ impl Default for InsertOptions {
     }
 }
 
+impl InsertOptions {
+    pub(crate) fn is_strict(&self) -> bool {
+        self.strict.unwrap_or(false)
+    }
+}
+
 /// Options for document update,
 #[derive(Debug, Serialize, Deserialize, PartialEq, TypedBuilder)]
 #[builder(doc)]
@@ -124,7 +153,7 @@ impl Default for UpdateOptions {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum OverwriteMode {
     /// If a document with the specified _key value exists already,
@@ -206,23 +235,50 @@ impl Default for ReplaceOptions {
 }
 
 /// Options for document reading.
-#[derive(Debug, Serialize, Deserialize)]
+///
+/// Every option set is sent as a header of the request, so they can be
+/// combined, e.g. a conditional read that is also allowed on a follower.
+#[derive(Debug, Serialize, Deserialize, TypedBuilder)]
+#[builder(doc)]
 #[serde(rename_all = "camelCase")]
-pub enum ReadOptions {
+pub struct ReadOptions {
     /// If the “If-None-Match” header is given, then it must contain exactly one
     /// Etag. The document is returned, if it has a different revision than
     /// the given Etag. Otherwise an HTTP 304 is returned.
-    IfNoneMatch(String),
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(into, strip_option))]
+    if_none_match: Option<String>,
     ///  If the “If-Match” header is given, then it must contain exactly one
     /// Etag. The document is returned, if it has the same revision as the
     /// given Etag. Otherwise a HTTP 412 is returned.
-    IfMatch(String),
-    NoHeader,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(into, strip_option))]
+    if_match: Option<String>,
+    /// If set to true, the document may be read from a follower in a cluster
+    /// (`x-arango-allow-dirty-read` header), which might return an outdated
+    /// revision.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    allow_dirty_read: Option<bool>,
+}
+
+impl ReadOptions {
+    pub fn if_none_match(&self) -> Option<&str> {
+        self.if_none_match.as_deref()
+    }
+
+    pub fn if_match(&self) -> Option<&str> {
+        self.if_match.as_deref()
+    }
+
+    pub fn allow_dirty_read(&self) -> Option<bool> {
+        self.allow_dirty_read
+    }
 }
 
 impl Default for ReadOptions {
     fn default() -> Self {
-        Self::NoHeader
+        Self::builder().build()
     }
 }
 
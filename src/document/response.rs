@@ -2,6 +2,7 @@
 use serde::{de::Error as DeError, Deserialize, Deserializer};
 
 use super::Header;
+use crate::ClientError;
 
 /// Standard Response when having CRUD operation on document
 ///
@@ -50,6 +51,12 @@ impl<T> DocumentResponse<T> {
             None
         }
     }
+    /// Return the document handle `_id`, i.e. `collection/key`
+    ///
+    /// `None` in silent mode, where the server does not send back anything.
+    pub fn id(&self) -> Option<&str> {
+        self.header().map(|header| header._id.as_str())
+    }
     /// Return the old document before changes
     pub fn old_doc(&self) -> Option<&T> {
         if let DocumentResponse::Response { old, .. } = self {
@@ -66,6 +73,28 @@ impl<T> DocumentResponse<T> {
             None
         }
     }
+    /// Take the old document before changes out of the response
+    pub fn into_old(self) -> Option<T> {
+        if let DocumentResponse::Response { old, .. } = self {
+            old
+        } else {
+            None
+        }
+    }
+    /// Take the new document out of the response
+    pub fn into_new(self) -> Option<T> {
+        if let DocumentResponse::Response { new, .. } = self {
+            new
+        } else {
+            None
+        }
+    }
+    /// Take the new document out of the response, failing with
+    /// `ClientError::MissingNewDocument` when the server did not send it
+    /// back, e.g. because `return_new` was not set or in silent mode.
+    pub fn require_new(self) -> Result<T, ClientError> {
+        self.into_new().ok_or(ClientError::MissingNewDocument)
+    }
     /// return the old revision of the document
     pub fn old_rev(&self) -> Option<&String> {
         if let DocumentResponse::Response { _old_rev, .. } = self {
@@ -108,17 +137,27 @@ where
                 _rev: serde_json::from_value(_rev).map_err(DeError::custom)?,
             };
 
+            // `old` is null when an insert with `overwrite` did not replace
+            // anything
             let old = json
                 .remove("old")
+                .filter(|v| !v.is_null())
                 .map(T::deserialize)
                 .transpose()
                 .map_err(DeError::custom)?;
             let new = json
                 .remove("new")
+                .filter(|v| !v.is_null())
                 .map(T::deserialize)
                 .transpose()
                 .map_err(DeError::custom)?;
-            let _old_rev = json.remove("_old_rev").map(|v| v.to_string());
+            let _old_rev = json
+                .remove("_oldRev")
+                .or_else(|| json.remove("_old_rev"))
+                .and_then(|v| match v {
+                    serde_json::Value::String(rev) => Some(rev),
+                    _ => None,
+                });
 
             Ok(DocumentResponse::Response {
                 header,
@@ -129,3 +168,68 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    #[test]
+    fn overwrite_with_return_old() {
+        let response: DocumentResponse<Value> = serde_json::from_value(json!({
+            "_id": "users/1",
+            "_key": "1",
+            "_rev": "_bBBBBBB---",
+            "_oldRev": "_aAAAAAA---",
+            "old": { "_key": "1", "name": "old" }
+        }))
+        .unwrap();
+
+        assert_eq!(response.id(), Some("users/1"));
+        assert_eq!(response.old_doc().unwrap()["name"], "old");
+        assert_eq!(response.old_rev().map(String::as_str), Some("_aAAAAAA---"));
+        assert_eq!(response.new_doc(), None);
+    }
+
+    #[test]
+    fn overwrite_without_existing_document() {
+        let response: DocumentResponse<Value> = serde_json::from_value(json!({
+            "_id": "users/1",
+            "_key": "1",
+            "_rev": "_bBBBBBB---",
+            "old": null
+        }))
+        .unwrap();
+
+        assert_eq!(response.old_doc(), None);
+        assert_eq!(response.old_rev(), None);
+    }
+
+    #[test]
+    fn take_returned_documents() {
+        let response: DocumentResponse<Value> = serde_json::from_value(json!({
+            "_id": "users/1",
+            "_key": "1",
+            "_rev": "_bBBBBBB---",
+            "new": { "_key": "1", "name": "new" }
+        }))
+        .unwrap();
+        assert_eq!(response.require_new().unwrap()["name"], "new");
+
+        let response: DocumentResponse<Value> = serde_json::from_value(json!({
+            "_id": "users/1",
+            "_key": "1",
+            "_rev": "_bBBBBBB---",
+            "old": { "_key": "1", "name": "old" }
+        }))
+        .unwrap();
+        assert_eq!(response.into_old().unwrap()["name"], "old");
+
+        let response: DocumentResponse<Value> = serde_json::from_value(json!({})).unwrap();
+        assert!(matches!(
+            response.require_new(),
+            Err(ClientError::MissingNewDocument)
+        ));
+    }
+}
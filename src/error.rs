@@ -5,6 +5,11 @@ use thiserror::Error;
 
 use crate::connection::Permission;
 
+/// Errors of arangors
+///
+/// The errors of the server, the HTTP client and serde are available as the
+/// `source` of the variants wrapping them, so that the whole chain of causes
+/// can be walked, e.g. by `anyhow`.
 #[derive(Error, Debug)]
 pub enum ClientError {
     #[error("Insufficient permission ({permission:?}) to operate: {operation}")]
@@ -16,10 +21,431 @@ pub enum ClientError {
     InvalidServer(String),
     #[error("Error from server: {0}")]
     Arango(#[from] ArangoError),
+    /// The query used more memory than its `memory_limit` allows (error 32).
+    #[error("Query memory limit exceeded: {0}")]
+    QueryMemoryLimitExceeded(#[source] ArangoError),
+    /// The cursor does not exist (anymore) on the server, usually because it
+    /// was not read within its `ttl` (error 1600). The query must be run
+    /// again.
+    #[error("Cursor expired: {0}")]
+    CursorExpired(#[source] ArangoError),
+    /// A document was rejected by the schema of its collection (error 1620).
+    ///
+    /// `message` is the one configured in the schema rule that failed,
+    /// `collection` the name of the collection, when known, and `source` the
+    /// error of the server.
+    #[error("Schema validation failed: {source}")]
+    SchemaValidation {
+        message: String,
+        collection: Option<String>,
+        #[source]
+        source: ArangoError,
+    },
+    /// A query run with `fail_on_warning` was aborted because it raised a
+    /// warning, e.g. a division by zero.
+    ///
+    /// `code` is the error number of the warning.
+    #[error("Query aborted on warning: {message}({code})")]
+    QueryWarning { code: u16, message: String },
     #[error("Error from serde: {0}")]
     Serde(#[from] serde_json::error::Error),
+    /// The results of an AQL query could not be deserialized.
+    ///
+    /// `batch` is the index of the batch of the cursor, starting at 0, and
+    /// `query` the beginning of the query string.
+    #[error("Failed to deserialize batch {batch} of the results of query `{query}`: {source}")]
+    QueryResultDecode {
+        query: String,
+        batch: usize,
+        #[source]
+        source: serde_json::error::Error,
+    },
+    /// The server could not be reached, or the connection broke while the
+    /// request was in flight.
+    #[error("Failed to connect to server: {0}")]
+    Connection(String),
+    /// The connection was closed with `close`, hence the request was not
+    /// sent. Unlike `Connection`, this is not transient.
+    #[error("Connection closed, refusing {0}")]
+    ConnectionClosed(String),
+    /// The server did not answer in time.
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+    /// The response of the server could not be read, e.g. a body that is not
+    /// valid UTF-8.
+    #[error("Failed to decode response: {0}")]
+    Decode(String),
+    /// A query expected to return at most one result returned more.
+    ///
+    /// `query` is the beginning of the query string.
+    #[error("Query `{query}` returned more than one result")]
+    TooManyResults { query: String },
+    /// A document response misses the new document, usually because
+    /// `return_new` was not set in the options of the request.
+    #[error("Response misses the new document, was `return_new` set?")]
+    MissingNewDocument,
+    /// A document body was rejected by the client before being sent, e.g.
+    /// because it carries `_id` or `_rev` on create with
+    /// `InsertOptions::strict` set.
+    #[error("Invalid document body: {0}")]
+    InvalidDocumentBody(String),
+    /// A query was rejected by the client before being sent, e.g. because a
+    /// collection of another database is bound to it.
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+    /// The response body exceeds `ConnectionOptions::max_response_size`.
+    #[error("Response too large: {0}")]
+    ResponseTooLarge(String),
     #[error("HTTP client error: {0}")]
-    HttpClient(#[from] uclient::ClientError),
+    HttpClient(#[source] uclient::ClientError),
+}
+
+/// Fragments of the debug output of the errors of the HTTP clients telling
+/// that the TLS session could not be established, e.g. because the
+/// certificate of the server is not trusted. Retrying does not help.
+///
+/// These fragments are only the fallback of `classify`, see there.
+const TLS_ERRORS: &[&str] = &[
+    "Ssl(",
+    "InvalidCertificate",
+    "certificate verify failed",
+    "kind: BadServerCertificate",
+    "kind: TlsEngine",
+];
+
+/// Fragments telling that no connection could be established, hence that
+/// nothing was sent to the server.
+const CONNECT_ERRORS: &[&str] = &[
+    "hyper::Error(Connect",
+    "ConnectError(\"tcp connect error\"",
+    "ConnectError(\"dns error\"",
+    "kind: ConnectionRefused",
+    "kind: ConnectionFailed",
+    "kind: NameResolution",
+];
+
+/// Fragments telling that the connection broke while the request was in
+/// flight.
+const INTERRUPTED_ERRORS: &[&str] = &[
+    "kind: ConnectionReset",
+    "kind: ConnectionAborted",
+    "kind: BrokenPipe",
+    "kind: UnexpectedEof",
+    "hyper::Error(IncompleteMessage",
+];
+
+const TIMEOUT_ERRORS: &[&str] = &["source: TimedOut", "kind: TimedOut", "kind: Timeout"];
+
+const DECODE_ERRORS: &[&str] = &["reqwest::Error { kind: Decode"];
+
+/// Maximum number of characters of a query kept in an error.
+const QUERY_CONTEXT_LENGTH: usize = 100;
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_owned(),
+    }
+}
+
+fn contains_any(message: &str, fragments: &[&str]) -> bool {
+    fragments.iter().any(|f| message.contains(f))
+}
+
+/// How a request failed in the HTTP client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Failure {
+    /// No connection could be established, hence nothing was sent.
+    Connect,
+    /// The connection broke while the request was in flight.
+    Interrupted,
+    /// The server did not answer in time.
+    Timeout,
+    /// The response could not be read.
+    Decode,
+    /// Anything else, e.g. a certificate that is not trusted.
+    Other,
+}
+
+/// An error of the HTTP client along with how the request failed.
+#[derive(Debug)]
+pub(crate) struct TransportError {
+    pub(crate) failure: Failure,
+    pub(crate) error: uclient::ClientError,
+}
+
+impl From<uclient::ClientError> for TransportError {
+    fn from(error: uclient::ClientError) -> Self {
+        TransportError {
+            failure: classify(&error),
+            error,
+        }
+    }
+}
+
+/// The `reqwest` clients of arangors send their requests themselves, hence
+/// how a request failed is read from the `reqwest` error before it is turned
+/// into the message of the error of `uclient`.
+#[cfg(any(
+    feature = "reqwest_async",
+    feature = "reqwest_async_rustls",
+    feature = "reqwest_blocking",
+    feature = "reqwest_blocking_rustls"
+))]
+impl From<reqwest::Error> for TransportError {
+    fn from(err: reqwest::Error) -> Self {
+        use std::io::ErrorKind;
+
+        let io_kind = io_error_kind(&err);
+        let failure = if err.is_timeout() {
+            Failure::Timeout
+        } else if err.is_decode() {
+            Failure::Decode
+        } else if err.is_connect() {
+            // TLS errors are reported as connection errors too, and carry no
+            // I/O error but the one of `rustls` about the invalid data it got
+            match io_kind {
+                Some(ErrorKind::InvalidData) | None => Failure::Other,
+                Some(_) => Failure::Connect,
+            }
+        } else if err.is_request() || err.is_body() {
+            Failure::Interrupted
+        } else {
+            Failure::Other
+        };
+        TransportError {
+            failure,
+            error: uclient::ClientError::HttpClient(format!("{:?}", err)),
+        }
+    }
+}
+
+impl From<TransportError> for ClientError {
+    fn from(err: TransportError) -> Self {
+        let message = err.error.to_string();
+        match err.failure {
+            Failure::Connect | Failure::Interrupted => ClientError::Connection(message),
+            Failure::Timeout => ClientError::Timeout(message),
+            Failure::Decode => ClientError::Decode(message),
+            Failure::Other => ClientError::HttpClient(err.error),
+        }
+    }
+}
+
+/// Recover how a request failed from the message of the error of the HTTP
+/// client.
+///
+/// This is the fallback for the clients arangors does not send the requests
+/// of, e.g. `SurfClient` or a custom client, which only report a message:
+/// usually the debug output of their error. The kind of failure is read from
+/// the variants of the `reqwest`, `hyper`, `isahc` and `std::io` errors it
+/// names, hence it may be lost when their debug output changes. The urls the
+/// message contains are left out, so that e.g. a database named `Ssl(` does
+/// not change the outcome.
+pub(crate) fn classify(err: &uclient::ClientError) -> Failure {
+    let uclient::ClientError::HttpClient(message) = err;
+    let details = without_urls(message);
+    if contains_any(&details, TLS_ERRORS) {
+        Failure::Other
+    } else if contains_any(&details, CONNECT_ERRORS) {
+        Failure::Connect
+    } else if contains_any(&details, TIMEOUT_ERRORS) {
+        Failure::Timeout
+    } else if contains_any(&details, INTERRUPTED_ERRORS) {
+        Failure::Interrupted
+    } else if contains_any(&details, DECODE_ERRORS) {
+        Failure::Decode
+    } else {
+        Failure::Other
+    }
+}
+
+/// Kind of the first I/O error in the chain of causes of `err`.
+#[cfg(any(
+    feature = "reqwest_async",
+    feature = "reqwest_async_rustls",
+    feature = "reqwest_blocking",
+    feature = "reqwest_blocking_rustls"
+))]
+fn io_error_kind(err: &(dyn std::error::Error + 'static)) -> Option<std::io::ErrorKind> {
+    let mut cause = Some(err);
+    while let Some(err) = cause {
+        if let Some(io) = err.downcast_ref::<std::io::Error>() {
+            return Some(io.kind());
+        }
+        cause = err.source();
+    }
+    None
+}
+
+/// Remove the urls from an error message, both the plain ones and the
+/// `Url { .. }` structs printed by `reqwest`.
+fn without_urls(message: &str) -> String {
+    let mut stripped = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(start) = rest.find("Url {") {
+        stripped.push_str(&rest[..start]);
+        rest = &rest[start + braced_len(&rest[start..])..];
+    }
+    stripped.push_str(rest);
+    for scheme in ["http://", "https://"] {
+        while let Some(start) = stripped.find(scheme) {
+            let end = stripped[start..]
+                .find(|c: char| c.is_whitespace() || "\"',)".contains(c))
+                .map_or(stripped.len(), |end| start + end);
+            stripped.replace_range(start..end, "");
+        }
+    }
+    stripped
+}
+
+/// Length of `text` up to the end of its first `{ .. }` block, ignoring the
+/// braces within quoted strings.
+fn braced_len(text: &str) -> usize {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' if depth > 1 => depth -= 1,
+            '}' => return i + 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+impl From<uclient::ClientError> for ClientError {
+    fn from(err: uclient::ClientError) -> Self {
+        TransportError::from(err).into()
+    }
+}
+
+impl ClientError {
+    /// Whether the server could not be reached or did not answer in time.
+    ///
+    /// Such errors are usually transient and the request can be retried,
+    /// unlike errors returned by the server itself.
+    pub fn is_transport_error(&self) -> bool {
+        matches!(self, ClientError::Connection(_) | ClientError::Timeout(_))
+    }
+
+    /// Attach the query and the index of the cursor batch to an error raised
+    /// while deserializing the results of an AQL query.
+    pub(crate) fn in_query_batch(self, query: &str, batch: usize) -> Self {
+        match self {
+            ClientError::Serde(source) => ClientError::QueryResultDecode {
+                query: truncate(query, QUERY_CONTEXT_LENGTH),
+                batch,
+                source,
+            },
+            err => err,
+        }
+    }
+
+    /// Cast an error raised by a warning of a query run with
+    /// `fail_on_warning` into `ClientError::QueryWarning`.
+    pub(crate) fn on_query_warning(self) -> Self {
+        match self {
+            ClientError::Arango(e) if AQL_WARNINGS.contains(&e.error_num) => {
+                ClientError::QueryWarning {
+                    code: e.error_num,
+                    message: e.message,
+                }
+            }
+            err => err,
+        }
+    }
+
+    /// Error of a query that returned more than the single result expected.
+    pub(crate) fn too_many_results(query: &str) -> Self {
+        ClientError::TooManyResults {
+            query: truncate(query, QUERY_CONTEXT_LENGTH),
+        }
+    }
+
+    /// Name the collection of a schema validation error.
+    pub(crate) fn in_collection(self, name: &str) -> Self {
+        match self {
+            ClientError::SchemaValidation {
+                message,
+                collection: None,
+                source,
+            } => ClientError::SchemaValidation {
+                message,
+                collection: Some(name.to_owned()),
+                source,
+            },
+            err => err,
+        }
+    }
+
+    /// Whether the server answered that the resource was not found, e.g. a
+    /// document, collection or database (HTTP 404, errors 1202 and 1203).
+    pub fn is_not_found(&self) -> bool {
+        self.server_error().is_some_and(|e| {
+            e.code == 404
+                || e.error_num == ERROR_DOCUMENT_NOT_FOUND
+                || e.error_num == ERROR_DATA_SOURCE_NOT_FOUND
+        })
+    }
+
+    /// Whether the server answered that the request conflicts with the
+    /// current state, e.g. a unique constraint violation (HTTP 409, error
+    /// 1210).
+    pub fn is_conflict(&self) -> bool {
+        self.server_error()
+            .is_some_and(|e| e.code == 409 || e.error_num == ERROR_UNIQUE_CONSTRAINT_VIOLATED)
+    }
+
+    /// Whether the server answered that a precondition failed, e.g. the
+    /// revision given in an `If-Match` header is not the current one (HTTP
+    /// 412).
+    pub fn is_precondition_failed(&self) -> bool {
+        self.server_error().is_some_and(|e| e.code == 412)
+    }
+
+    /// Whether the server answered that the request is not authenticated
+    /// (HTTP 401).
+    ///
+    /// Permissions checked by arangors itself are reported as
+    /// `ClientError::InsufficientPermission` instead.
+    pub fn is_unauthorized(&self) -> bool {
+        self.server_error().is_some_and(|e| e.code == 401)
+    }
+
+    /// The error returned by the server, whichever variant carries it.
+    fn server_error(&self) -> Option<&ArangoError> {
+        match self {
+            ClientError::Arango(e)
+            | ClientError::QueryMemoryLimitExceeded(e)
+            | ClientError::CursorExpired(e)
+            | ClientError::SchemaValidation { source: e, .. } => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Whether the server reported that the document does not exist.
+    pub(crate) fn is_document_not_found(&self) -> bool {
+        matches!(self, ClientError::Arango(e) if e.error_num == ERROR_DOCUMENT_NOT_FOUND)
+    }
+
+    /// Whether the server reported that the collection or view does not
+    /// exist.
+    pub(crate) fn is_data_source_not_found(&self) -> bool {
+        matches!(self, ClientError::Arango(e) if e.error_num == ERROR_DATA_SOURCE_NOT_FOUND)
+    }
 }
 
 #[derive(Deserialize, Debug, Error)]
@@ -33,10 +459,44 @@ pub struct ArangoError {
 
 impl fmt::Display for ArangoError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}({})", self.message, self.error_num)
+        write!(
+            f,
+            "{} (error {}, HTTP {})",
+            self.message, self.error_num, self.code
+        )
     }
 }
 
+/// Error number of ArangoDB when a document is not found.
+pub(crate) const ERROR_DOCUMENT_NOT_FOUND: u16 = 1202;
+
+/// Error number of ArangoDB when a collection or view is not found.
+pub(crate) const ERROR_DATA_SOURCE_NOT_FOUND: u16 = 1203;
+
+/// Error number of ArangoDB when the revision of a document does not match
+/// the expected one.
+pub(crate) const ERROR_CONFLICT: u16 = 1200;
+
+/// Error number of ArangoDB when a unique constraint is violated, e.g. a key
+/// that is already used.
+pub(crate) const ERROR_UNIQUE_CONSTRAINT_VIOLATED: u16 = 1210;
+
+/// Error number of ArangoDB when a resource limit, e.g. the memory limit of a
+/// query, is exceeded.
+pub(crate) const ERROR_RESOURCE_LIMIT: u16 = 32;
+
+/// Error number of ArangoDB when a cursor is not found, e.g. once expired.
+pub(crate) const ERROR_CURSOR_NOT_FOUND: u16 = 1600;
+
+/// Error number of ArangoDB when a document violates the schema of its
+/// collection.
+pub(crate) const ERROR_VALIDATION_FAILED: u16 = 1620;
+
+/// Error numbers that AQL reports as warnings, which abort a query run with
+/// `fail_on_warning`: invalid argument type in a function call, invalid
+/// regular expression, division by zero and array expected.
+const AQL_WARNINGS: &[u16] = &[1542, 1543, 1562, 1563];
+
 impl ArangoError {
     /// Get the HTTP status code of an error response.
     pub fn code(&self) -> u16 {
@@ -51,3 +511,245 @@ impl ArangoError {
         &self.message
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn query_result_decode_context() {
+        let source = serde_json::from_str::<u32>("\"x\"").unwrap_err();
+        let query = format!("FOR d IN c FILTER d.v == '{}' RETURN d", "é".repeat(200));
+        let err = ClientError::Serde(source).in_query_batch(&query, 2);
+        match err {
+            ClientError::QueryResultDecode { query, batch, .. } => {
+                assert_eq!(batch, 2);
+                assert_eq!(query.chars().count(), QUERY_CONTEXT_LENGTH + 3);
+                assert!(query.starts_with("FOR d IN c"));
+                assert!(query.ends_with("..."));
+            }
+            err => panic!("unexpected error: {:?}", err),
+        }
+
+        let err = ClientError::Timeout("timed out".to_owned()).in_query_batch("RETURN 1", 0);
+        assert!(matches!(err, ClientError::Timeout(_)));
+    }
+
+    #[test]
+    fn query_warning() {
+        let error = |error_num| {
+            ClientError::Arango(ArangoError {
+                code: 400,
+                error_num,
+                message: "division by zero".to_owned(),
+            })
+        };
+
+        match error(1562).on_query_warning() {
+            ClientError::QueryWarning { code, message } => {
+                assert_eq!(code, 1562);
+                assert_eq!(message, "division by zero");
+            }
+            err => panic!("unexpected error: {:?}", err),
+        }
+        assert!(matches!(
+            error(1501).on_query_warning(),
+            ClientError::Arango(_)
+        ));
+    }
+
+    #[test]
+    fn error_source_chain() {
+        use std::error::Error;
+
+        let server_error = || ArangoError {
+            code: 404,
+            error_num: ERROR_CURSOR_NOT_FOUND,
+            message: "cursor not found".to_owned(),
+        };
+        let err = ClientError::CursorExpired(server_error());
+        assert_eq!(
+            err.to_string(),
+            "Cursor expired: cursor not found (error 1600, HTTP 404)"
+        );
+        let source = err
+            .source()
+            .and_then(|e| e.downcast_ref::<ArangoError>())
+            .unwrap();
+        assert_eq!(source.error_num(), ERROR_CURSOR_NOT_FOUND);
+
+        let err = ClientError::from(server_error());
+        assert!(err.source().unwrap().is::<ArangoError>());
+
+        let err = ClientError::SchemaValidation {
+            message: "age must be positive".to_owned(),
+            collection: None,
+            source: ArangoError {
+                code: 400,
+                error_num: ERROR_VALIDATION_FAILED,
+                message: "age must be positive".to_owned(),
+            },
+        };
+        assert_eq!(
+            err.to_string(),
+            "Schema validation failed: age must be positive (error 1620, HTTP 400)"
+        );
+        let source = err.source().unwrap().downcast_ref::<ArangoError>().unwrap();
+        assert_eq!(source.error_num(), ERROR_VALIDATION_FAILED);
+        assert_eq!(source.code(), 400);
+
+        let source = serde_json::from_str::<u32>("\"x\"").unwrap_err();
+        let err = ClientError::Serde(source).in_query_batch("RETURN 1", 0);
+        assert!(err.source().unwrap().is::<serde_json::Error>());
+    }
+
+    #[test]
+    fn server_error_predicates() {
+        let error = |code, error_num| {
+            ClientError::Arango(ArangoError {
+                code,
+                error_num,
+                message: String::new(),
+            })
+        };
+
+        assert!(error(404, ERROR_DOCUMENT_NOT_FOUND).is_not_found());
+        assert!(error(404, ERROR_DATA_SOURCE_NOT_FOUND).is_not_found());
+        assert!(ClientError::CursorExpired(ArangoError {
+            code: 404,
+            error_num: ERROR_CURSOR_NOT_FOUND,
+            message: String::new(),
+        })
+        .is_not_found());
+        assert!(error(409, ERROR_UNIQUE_CONSTRAINT_VIOLATED).is_conflict());
+        assert!(error(412, 1200).is_precondition_failed());
+        assert!(error(401, 11).is_unauthorized());
+
+        let err = error(400, 600);
+        assert!(!err.is_not_found());
+        assert!(!err.is_conflict());
+        assert!(!err.is_precondition_failed());
+        assert!(!err.is_unauthorized());
+        assert!(!ClientError::Timeout("timed out".to_owned()).is_not_found());
+    }
+
+    #[test]
+    fn classify_http_client_errors() {
+        let kind = |message: &str| classify(&uclient::ClientError::HttpClient(message.to_owned()));
+        let url = "url: Url { scheme: \"https\", cannot_be_a_base: false, username: \"\", \
+                   password: None, host: Some(Domain(\"tls.example\")), port: Some(8529), path: \
+                   \"/_db/timeout/_api/document/ssl/decode%7B\", query: Some(\"a={\\\"}\"), \
+                   fragment: None }";
+
+        let err: ClientError = uclient::ClientError::HttpClient(format!(
+            "reqwest::Error {{ kind: Request, {}, source: hyper::Error(Connect, \
+             ConnectError(\"tcp connect error\", Os {{ code: 111, kind: ConnectionRefused, \
+             message: \"Connection refused\" }})) }}",
+            url
+        ))
+        .into();
+        assert!(matches!(err, ClientError::Connection(_)));
+        assert!(err.is_transport_error());
+
+        let err: ClientError = uclient::ClientError::HttpClient(format!(
+            "reqwest::Error {{ kind: Request, {}, source: TimedOut }}",
+            url
+        ))
+        .into();
+        assert!(matches!(err, ClientError::Timeout(_)));
+        assert!(err.is_transport_error());
+
+        let err: ClientError = uclient::ClientError::HttpClient(format!(
+            "reqwest::Error {{ kind: Decode, {}, source: \"stream did not contain valid UTF-8\" }}",
+            url
+        ))
+        .into();
+        assert!(matches!(err, ClientError::Decode(_)));
+        assert!(!err.is_transport_error());
+
+        // the url alone does not classify an error
+        let err: ClientError = uclient::ClientError::HttpClient(format!(
+            "reqwest::Error {{ kind: Builder, {}, source: RelativeUrlWithoutBase }}",
+            url
+        ))
+        .into();
+        assert!(matches!(err, ClientError::HttpClient(_)));
+        assert_eq!(
+            kind("error sending request for url (http://timeout.example/_db/tls/): builder"),
+            Failure::Other
+        );
+
+        // an untrusted certificate is not transient
+        let err: ClientError = uclient::ClientError::HttpClient(format!(
+            "reqwest::Error {{ kind: Request, {}, source: hyper::Error(Connect, Ssl(Error {{ code: \
+             ErrorCode(1), cause: Some(Ssl(ErrorStack([Error {{ reason: \"certificate verify \
+             failed\" }}]))) }})) }}",
+            url
+        ))
+        .into();
+        assert!(matches!(err, ClientError::HttpClient(_)));
+        assert!(!err.is_transport_error());
+
+        assert_eq!(
+            kind("hyper::Error(Connect, ConnectError(\"tcp connect error\", TimedOut))"),
+            Failure::Connect
+        );
+        assert_eq!(
+            kind("reqwest::Error { kind: Request, source: hyper::Error(IncompleteMessage) }"),
+            Failure::Interrupted
+        );
+
+        // bare words are not enough to classify an error
+        assert_eq!(kind("ssl handshake timeout while decoding"), Failure::Other);
+
+        let err: ClientError = uclient::ClientError::HttpClient("something else".to_owned()).into();
+        assert!(matches!(err, ClientError::HttpClient(_)));
+    }
+
+    #[cfg(any(feature = "reqwest_async", feature = "reqwest_async_rustls"))]
+    #[tokio::test]
+    async fn classify_reqwest_errors() {
+        let failure = |err: reqwest::Error| TransportError::from(err).failure;
+
+        let err = reqwest::Client::new()
+            .get("http://localhost:8529")
+            .header("x\n", "value")
+            .build()
+            .unwrap_err();
+        assert_eq!(failure(err), Failure::Other);
+
+        // nothing listens on the port of a listener that was dropped
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+        let err = reqwest::get(&url).await.unwrap_err();
+        assert_eq!(failure(err), Failure::Connect);
+
+        // a listener that never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let err = reqwest::Client::new()
+            .get(&url)
+            .timeout(std::time::Duration::from_millis(50))
+            .send()
+            .await
+            .unwrap_err();
+        let err = ClientError::from(TransportError::from(err));
+        assert!(matches!(err, ClientError::Timeout(_)));
+        assert!(err
+            .to_string()
+            .starts_with("Request timed out: HTTP client error: reqwest::Error"));
+    }
+
+    #[test]
+    fn strip_urls() {
+        assert_eq!(
+            without_urls("error for url (https://host:8529/_db/tls/?q=1), retrying"),
+            "error for url (), retrying"
+        );
+        assert_eq!(
+            without_urls("Error { url: Url { path: \"/a}\", query: None }, source: TimedOut }"),
+            "Error { url: , source: TimedOut }"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Types of the Foxx services, JavaScript microservices running inside the
+//! server.
+//!
+//! Services are managed with `Database::install_foxx_service` and friends,
+//! and mounted at a path of the database they are installed in.
+//!
+//! # Note
+//! The code of a service is sent as binary content, hence managing services
+//! requires an HTTP client implementing `BinaryClientExt`, as the clients of
+//! arangors do.
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use typed_builder::TypedBuilder;
+
+/// Where the code of a service is taken from
+#[derive(Debug, Clone, PartialEq)]
+pub enum FoxxSource {
+    /// Url of a zip bundle or a JavaScript file, downloaded by the server,
+    /// e.g. `https://github.com/arangodb-foxx/demo-hello-foxx/archive/master.zip`.
+    Url(String),
+    /// Code of a service made of a single JavaScript file.
+    Script(String),
+    /// Zip bundle of a service, e.g. read from a file with `std::fs::read`.
+    Zip(Vec<u8>),
+}
+
+impl FoxxSource {
+    pub(crate) fn content_type(&self) -> &'static str {
+        match self {
+            FoxxSource::Url(_) => "application/json",
+            FoxxSource::Script(_) => "application/javascript",
+            FoxxSource::Zip(_) => "application/zip",
+        }
+    }
+
+    pub(crate) fn body(&self) -> Vec<u8> {
+        match self {
+            FoxxSource::Url(url) => json!({ "source": url }).to_string().into_bytes(),
+            FoxxSource::Script(script) => script.clone().into_bytes(),
+            FoxxSource::Zip(bundle) => bundle.clone(),
+        }
+    }
+}
+
+/// Options to install a service
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TypedBuilder)]
+#[builder(doc)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallOptions {
+    /// Install the service in development mode, where its code is reloaded
+    /// from the file system on every request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub development: Option<bool>,
+    /// Run the setup script of the service. Defaults to true.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub setup: Option<bool>,
+    /// Install the service in 2.8 legacy compatibility mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub legacy: Option<bool>,
+}
+
+impl Default for InstallOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Options to upgrade or replace a service
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TypedBuilder)]
+#[builder(doc)]
+#[serde(rename_all = "camelCase")]
+pub struct UpgradeOptions {
+    /// Run the teardown script of the old service. Defaults to false for an
+    /// upgrade, true for a replacement.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub teardown: Option<bool>,
+    /// Run the setup script of the new service. Defaults to true.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub setup: Option<bool>,
+    /// Install the new service in 2.8 legacy compatibility mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub legacy: Option<bool>,
+    /// Install the new service even if no service is mounted at the path.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub force: Option<bool>,
+}
+
+impl Default for UpgradeOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// A service installed on the server
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct FoxxService {
+    /// Path the service is mounted at, e.g. `/hello`.
+    pub mount: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    #[serde(default)]
+    pub development: bool,
+    #[serde(default)]
+    pub legacy: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn source_body() {
+        let source = FoxxSource::Url("https://example.com/service.zip".to_owned());
+        assert_eq!(source.content_type(), "application/json");
+        assert_eq!(
+            source.body(),
+            br#"{"source":"https://example.com/service.zip"}"#
+        );
+
+        let source = FoxxSource::Script("module.exports = {};".to_owned());
+        assert_eq!(source.content_type(), "application/javascript");
+        assert_eq!(source.body(), b"module.exports = {};");
+
+        // the local file header signature of a zip archive
+        let source = FoxxSource::Zip(vec![0x50, 0x4b, 0x03, 0x04]);
+        assert_eq!(source.content_type(), "application/zip");
+        assert_eq!(source.body(), [0x50, 0x4b, 0x03, 0x04]);
+    }
+}
@@ -68,6 +68,11 @@ pub(crate) const INDEX_API_PATH: &str = "_api/index";
 #[derive(Debug, Clone, Serialize, Deserialize, Default, TypedBuilder)]
 #[serde(rename_all = "camelCase")]
 pub struct Index {
+    /// Attribute paths covered by the index.
+    ///
+    /// Use the `[*]` expansion to index every element of an array, e.g.
+    /// `tags[*]` or `authors[*].name`. Without it, the array as a whole is
+    /// indexed as a single value.
     #[builder(default)]
     pub fields: Vec<String>,
     #[builder(default, setter(into))]
@@ -94,6 +99,9 @@ pub enum IndexSettings {
         unique: bool,
         sparse: bool,
     },
+    /// `deduplicate` only applies to array indexes (`[*]` fields): when
+    /// set, an array with the same value multiple times is indexed once
+    /// for that value, which matters for `unique` indexes.
     Persistent {
         unique: bool,
         sparse: bool,
@@ -137,6 +145,45 @@ impl Default for IndexSettings {
     }
 }
 
+impl IndexSettings {
+    /// The [`IndexType`] these settings describe.
+    pub fn index_type(&self) -> IndexType {
+        match self {
+            IndexSettings::Primary { .. } => IndexType::Primary,
+            IndexSettings::Persistent { .. } => IndexType::Persistent,
+            IndexSettings::Edge { .. } => IndexType::Edge,
+            IndexSettings::Hash { .. } => IndexType::Hash,
+            IndexSettings::Skiplist { .. } => IndexType::Skiplist,
+            IndexSettings::Ttl { .. } => IndexType::Ttl,
+            IndexSettings::Geo { .. } => IndexType::Geo,
+            IndexSettings::Fulltext { .. } => IndexType::Fulltext,
+        }
+    }
+}
+
+impl Index {
+    /// The type of this index, as carried by its [`settings`].
+    ///
+    /// [`settings`]: enum.IndexSettings.html
+    pub fn index_type(&self) -> IndexType {
+        self.settings.index_type()
+    }
+}
+
+/// The `type` of an index, without any of its type specific settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IndexType {
+    Primary,
+    Persistent,
+    Edge,
+    Hash,
+    Skiplist,
+    Ttl,
+    Geo,
+    Fulltext,
+}
+
 /// Represents a collection of indexes on a collection in ArangoDB.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
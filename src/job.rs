@@ -0,0 +1,32 @@
+//! Types of the async jobs, which let the server run a request in the
+//! background and keep its result until it is fetched.
+//!
+//! A request sent with the `x-arango-async: store` header is answered right
+//! away with `202 Accepted` and the id of the job in the `x-arango-async-id`
+//! header. The result of the job is then fetched with
+//! `Database::job_result`, which yields `None` as long as the job is pending.
+//! Results are only kept by the server until they are fetched once, and are
+//! lost when the server restarts.
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+pub(crate) const ASYNC_HEADER: &str = "x-arango-async";
+pub(crate) const ASYNC_ID_HEADER: &str = "x-arango-async-id";
+
+/// The id of an async job, as returned by the server when the job is accepted
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct JobId(pub(crate) String);
+
+impl JobId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for JobId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
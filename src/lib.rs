@@ -414,6 +414,7 @@ pub use crate::{
     database::Database,
     document::Document,
     error::{ArangoError, ClientError},
+    response::WithHeaders,
 };
 pub use uclient;
 
@@ -424,10 +425,16 @@ pub mod connection;
 pub mod database;
 pub mod document;
 pub mod error;
+pub mod foxx;
 pub mod graph;
 pub mod index;
+pub mod job;
+#[cfg(test)]
+pub(crate) mod mock;
 mod query;
+pub mod replication;
 mod response;
+pub mod task;
 pub mod transaction;
 pub mod user;
 pub mod view;
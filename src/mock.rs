@@ -0,0 +1,23 @@
+//! HTTP clients shared by the unit tests.
+use http::{HeaderMap, Request, Response};
+use maybe_async::maybe_async;
+use uclient::{ClientError, ClientExt};
+
+/// A client that is never asked to send a request.
+#[derive(Debug, Clone)]
+pub(crate) struct NoClient(HeaderMap);
+
+#[maybe_async]
+impl ClientExt for NoClient {
+    fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError> {
+        Ok(NoClient(headers.into().unwrap_or_default()))
+    }
+
+    fn headers(&mut self) -> &mut HeaderMap {
+        &mut self.0
+    }
+
+    async fn request(&self, _request: Request<String>) -> Result<Response<String>, ClientError> {
+        unreachable!("no request is sent")
+    }
+}
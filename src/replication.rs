@@ -0,0 +1,648 @@
+//! Types related to the replication API of ArangoDB.
+//!
+//! The replication API is mostly useful for building change data capture
+//! pipelines or custom replication tools. Operations are conducted on
+//! `Database`.
+//!
+//! For detailed information about the replication API, please check out the
+//! official ArangoDB [documentation](https://www.arangodb.com/docs/stable/http/replications.html).
+#[cfg(not(feature = "blocking"))]
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex, OnceLock, Weak},
+    task::{Context, Poll, Waker},
+    time::Instant,
+};
+use std::{collections::VecDeque, time::Duration};
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use typed_builder::TypedBuilder;
+
+/// Response header holding the tick of the last entry included in a batch.
+pub const LAST_INCLUDED_HEADER: &str = "x-arango-replication-lastincluded";
+/// Response header telling whether there are more entries to fetch.
+pub const CHECK_MORE_HEADER: &str = "x-arango-replication-checkmore";
+/// Response header holding the last tick the server scanned.
+pub const LAST_SCANNED_HEADER: &str = "x-arango-replication-lastscanned";
+
+/// Options for tailing the write-ahead log.
+#[derive(Debug, Clone, Serialize, TypedBuilder)]
+#[builder(doc)]
+#[serde(rename_all = "camelCase")]
+pub struct WalTailOptions {
+    /// Exclusive upper bound tick value for results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    to: Option<String>,
+    /// Last tick value scanned by a previous call, so the server can continue
+    /// scanning where it left off even if no entry was returned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    last_scanned: Option<String>,
+    /// Whether operations of all databases should be included. Only allowed
+    /// on the `_system` database.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    global: Option<bool>,
+    /// Approximate maximum size of the returned result, in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    chunk_size: Option<u64>,
+    /// Id of the client used to tail results. The server uses it to keep the
+    /// WAL around until the client has caught up.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    server_id: Option<String>,
+    /// Id of a replication barrier to protect WAL files from removal.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    barrier_id: Option<String>,
+    /// Time `Database::tail_wal` waits before polling again once the server
+    /// has no more entries. Doubled on every empty batch, up to
+    /// `max_poll_interval`.
+    #[serde(skip)]
+    #[builder(default = Duration::from_millis(500))]
+    poll_interval: Duration,
+    /// Upper bound of the time `Database::tail_wal` waits between two polls.
+    #[serde(skip)]
+    #[builder(default = Duration::from_secs(10))]
+    max_poll_interval: Duration,
+}
+
+impl Default for WalTailOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Kind of operation logged in the write-ahead log.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum WalOperation {
+    CreateDatabase,
+    DropDatabase,
+    CreateCollection,
+    DropCollection,
+    RenameCollection,
+    ChangeCollection,
+    TruncateCollection,
+    CreateIndex,
+    DropIndex,
+    CreateView,
+    DropView,
+    ChangeView,
+    StartTransaction,
+    CommitTransaction,
+    AbortTransaction,
+    /// Insert or replace of a document
+    InsertOrReplaceDocument,
+    RemoveDocument,
+    /// Operation type unknown to this crate
+    Other(u16),
+}
+
+impl From<u16> for WalOperation {
+    fn from(value: u16) -> Self {
+        match value {
+            1100 => WalOperation::CreateDatabase,
+            1101 => WalOperation::DropDatabase,
+            2000 => WalOperation::CreateCollection,
+            2001 => WalOperation::DropCollection,
+            2002 => WalOperation::RenameCollection,
+            2003 => WalOperation::ChangeCollection,
+            2004 => WalOperation::TruncateCollection,
+            2100 => WalOperation::CreateIndex,
+            2101 => WalOperation::DropIndex,
+            2110 => WalOperation::CreateView,
+            2111 => WalOperation::DropView,
+            2112 => WalOperation::ChangeView,
+            2200 => WalOperation::StartTransaction,
+            2201 => WalOperation::CommitTransaction,
+            2202 => WalOperation::AbortTransaction,
+            2300 => WalOperation::InsertOrReplaceDocument,
+            2302 => WalOperation::RemoveDocument,
+            other => WalOperation::Other(other),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WalOperation {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        u16::deserialize(deserializer).map(WalOperation::from)
+    }
+}
+
+/// A single operation logged in the write-ahead log.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalEntry {
+    /// Tick of the operation, used to resume tailing.
+    pub tick: String,
+    #[serde(rename = "type")]
+    pub operation: WalOperation,
+    /// Name of the database the operation belongs to.
+    pub db: Option<String>,
+    /// Globally unique id of the collection the operation belongs to.
+    pub cuid: Option<String>,
+    /// Id of the transaction the operation belongs to.
+    pub tid: Option<String>,
+    /// Payload of the operation, e.g. the document for a insert.
+    pub data: Option<Value>,
+}
+
+/// A batch of write-ahead log entries.
+#[derive(Debug)]
+pub struct WalBatch {
+    pub entries: Vec<WalEntry>,
+    /// Tick of the last entry included in this batch. Pass it as `from` to the
+    /// next call to resume tailing right after this batch.
+    pub last_included: Option<String>,
+    /// Last tick scanned by the server. Pass it as `last_scanned` to the next
+    /// call to avoid scanning the same range again.
+    pub last_scanned: Option<String>,
+    /// Whether the server has more entries available right now.
+    pub check_more: bool,
+}
+
+/// Progress of `Database::tail_wal`, so that every poll resumes where the
+/// previous one stopped.
+pub(crate) struct WalTail {
+    from: String,
+    options: WalTailOptions,
+    entries: VecDeque<WalEntry>,
+    /// Time to wait before the next poll.
+    wait: Option<Duration>,
+    /// Time to wait after the next empty batch.
+    idle: Duration,
+    /// Whether the `to` tick given in the options was reached.
+    done: bool,
+}
+
+impl WalTail {
+    pub(crate) fn new(from: &str, options: WalTailOptions) -> Self {
+        WalTail {
+            from: from.to_owned(),
+            idle: options.poll_interval,
+            options,
+            entries: VecDeque::new(),
+            wait: None,
+            done: false,
+        }
+    }
+
+    /// Next entry of the batches fetched so far.
+    pub(crate) fn next_entry(&mut self) -> Option<WalEntry> {
+        self.entries.pop_front()
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Time to wait before sending the next poll, if any.
+    pub(crate) fn take_wait(&mut self) -> Option<Duration> {
+        self.wait.take()
+    }
+
+    /// Tick and options of the next poll.
+    pub(crate) fn poll_args(&self) -> (&str, WalTailOptions) {
+        (&self.from, self.options.clone())
+    }
+
+    /// Record a batch, resuming the next poll right after it.
+    pub(crate) fn advance(&mut self, batch: WalBatch) {
+        if let Some(tick) = batch.last_included {
+            self.from = tick;
+        }
+        if batch.last_scanned.is_some() {
+            self.options.last_scanned = batch.last_scanned;
+        }
+        let empty = batch.entries.is_empty();
+        self.entries.extend(batch.entries);
+        if batch.check_more {
+            self.idle = self.options.poll_interval;
+        } else if self.options.to.is_some() {
+            self.done = true;
+        } else if empty {
+            self.back_off();
+        } else {
+            self.idle = self.options.poll_interval;
+            self.wait = Some(self.idle);
+        }
+    }
+
+    /// Record a failed poll, so that the next one is delayed.
+    pub(crate) fn fail(&mut self) {
+        self.back_off();
+    }
+
+    fn back_off(&mut self) {
+        self.wait = Some(self.idle);
+        self.idle = (self.idle * 2).min(self.options.max_poll_interval);
+    }
+}
+
+/// Future resolving once a duration elapsed, independent of the async runtime
+/// in use.
+///
+/// All delays are served by a single timer thread, started on first use,
+/// which keeps their deadlines in a heap. Pending delays are only referenced
+/// weakly, so dropping one releases its state right away.
+#[cfg(not(feature = "blocking"))]
+pub(crate) struct Delay {
+    state: Arc<Mutex<DelayState>>,
+}
+
+#[cfg(not(feature = "blocking"))]
+#[derive(Default)]
+struct DelayState {
+    elapsed: bool,
+    waker: Option<Waker>,
+}
+
+#[cfg(not(feature = "blocking"))]
+impl Delay {
+    pub(crate) fn new(duration: Duration) -> Self {
+        let state = Arc::new(Mutex::new(DelayState::default()));
+        if duration.is_zero() {
+            state.lock().unwrap().elapsed = true;
+        } else {
+            Timer::shared().schedule(Instant::now() + duration, Arc::downgrade(&state));
+        }
+        Delay { state }
+    }
+}
+
+#[cfg(not(feature = "blocking"))]
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.elapsed {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// The timer thread shared by all `Delay`s.
+#[cfg(not(feature = "blocking"))]
+struct Timer {
+    deadlines: Mutex<BinaryHeap<Reverse<Deadline>>>,
+    changed: Condvar,
+}
+
+#[cfg(not(feature = "blocking"))]
+struct Deadline {
+    at: Instant,
+    state: Weak<Mutex<DelayState>>,
+}
+
+#[cfg(not(feature = "blocking"))]
+impl PartialEq for Deadline {
+    fn eq(&self, other: &Self) -> bool {
+        self.at == other.at
+    }
+}
+
+#[cfg(not(feature = "blocking"))]
+impl Eq for Deadline {}
+
+#[cfg(not(feature = "blocking"))]
+impl PartialOrd for Deadline {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(not(feature = "blocking"))]
+impl Ord for Deadline {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.at.cmp(&other.at)
+    }
+}
+
+#[cfg(not(feature = "blocking"))]
+impl Timer {
+    fn shared() -> &'static Timer {
+        static TIMER: OnceLock<&'static Timer> = OnceLock::new();
+        TIMER.get_or_init(|| {
+            let timer: &'static Timer = Box::leak(Box::new(Timer {
+                deadlines: Mutex::new(BinaryHeap::new()),
+                changed: Condvar::new(),
+            }));
+            std::thread::Builder::new()
+                .name("arangors-timer".into())
+                .spawn(move || timer.run())
+                .expect("failed to spawn the timer thread");
+            timer
+        })
+    }
+
+    fn schedule(&self, at: Instant, state: Weak<Mutex<DelayState>>) {
+        self.deadlines
+            .lock()
+            .unwrap()
+            .push(Reverse(Deadline { at, state }));
+        self.changed.notify_one();
+    }
+
+    fn run(&self) {
+        let mut deadlines = self.deadlines.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            while let Some(Reverse(next)) = deadlines.peek() {
+                if next.at > now {
+                    break;
+                }
+                let Reverse(next) = deadlines.pop().unwrap();
+                if let Some(state) = next.state.upgrade() {
+                    let mut state = state.lock().unwrap();
+                    state.elapsed = true;
+                    if let Some(waker) = state.waker.take() {
+                        waker.wake();
+                    }
+                }
+            }
+            deadlines = match deadlines.peek() {
+                Some(Reverse(next)) => {
+                    let wait = next.at - now;
+                    self.changed.wait_timeout(deadlines, wait).unwrap().0
+                }
+                None => self.changed.wait(deadlines).unwrap(),
+            };
+        }
+    }
+}
+
+/// Inventory of the collections and views of a database, as seen by the
+/// replication API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Inventory {
+    pub collections: Vec<InventoryCollection>,
+    #[serde(default)]
+    pub views: Vec<Value>,
+    /// Replication state of the server at the time of the inventory.
+    pub state: Value,
+    /// Tick of the server at the time of the inventory. Can be used as `from`
+    /// to tail the write-ahead log afterwards.
+    pub tick: String,
+}
+
+/// A collection listed in an `Inventory`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryCollection {
+    /// Properties of the collection, e.g. `name`, `type` and `keyOptions`.
+    pub parameters: serde_json::Map<String, Value>,
+    /// Indexes of the collection, excluding the primary and edge indexes.
+    pub indexes: Vec<Value>,
+}
+
+/// A replication batch holds a consistent snapshot of the data, which can be
+/// dumped collection by collection.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicationBatch {
+    pub id: String,
+    /// Tick of the server at the time the batch was created.
+    pub last_tick: String,
+}
+
+/// Options for dumping the content of a collection.
+#[derive(Debug, Clone, Serialize, TypedBuilder)]
+#[builder(doc)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpOptions {
+    /// Id of a replication batch. Required by the RocksDB storage engine.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    batch_id: Option<String>,
+    /// Lower bound tick value for results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    from: Option<String>,
+    /// Upper bound tick value for results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    to: Option<String>,
+    /// Approximate maximum size of the returned result, in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    chunk_size: Option<u64>,
+}
+
+impl Default for DumpOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// A single operation of a collection dump.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpEntry {
+    #[serde(rename = "type")]
+    pub operation: WalOperation,
+    /// The document the operation applies to.
+    pub data: Value,
+}
+
+/// A chunk of a collection dump.
+#[derive(Debug)]
+pub struct DumpBatch {
+    pub entries: Vec<DumpEntry>,
+    /// Tick of the last entry included in this chunk. Pass it as `from` to
+    /// fetch the next chunk.
+    pub last_included: Option<String>,
+    /// Whether there are more entries to fetch.
+    pub check_more: bool,
+}
+
+/// Progress of `Collection::replication_dump`, so that every chunk is
+/// fetched right after the previous one.
+pub(crate) struct CollectionDump {
+    options: DumpOptions,
+    entries: VecDeque<DumpEntry>,
+    /// Whether the last chunk was fetched.
+    done: bool,
+}
+
+impl CollectionDump {
+    pub(crate) fn new(options: DumpOptions) -> Self {
+        CollectionDump {
+            options,
+            entries: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Next entry of the chunks fetched so far.
+    pub(crate) fn next_entry(&mut self) -> Option<DumpEntry> {
+        self.entries.pop_front()
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Options of the request of the next chunk.
+    pub(crate) fn options(&self) -> DumpOptions {
+        self.options.clone()
+    }
+
+    /// Record a chunk, fetching the next one right after it.
+    ///
+    /// The dump ends once the server has nothing more, or does not tell
+    /// where to resume from.
+    pub(crate) fn advance(&mut self, batch: DumpBatch) {
+        self.entries.extend(batch.entries);
+        match batch.last_included {
+            Some(tick) if batch.check_more => self.options.from = Some(tick),
+            _ => self.done = true,
+        }
+    }
+
+    /// Record a failed chunk, which ends the dump.
+    pub(crate) fn fail(&mut self) {
+        self.done = true;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn batch(ticks: &[&str], check_more: bool) -> WalBatch {
+        WalBatch {
+            entries: ticks
+                .iter()
+                .map(|tick| WalEntry {
+                    tick: tick.to_string(),
+                    operation: WalOperation::InsertOrReplaceDocument,
+                    db: None,
+                    cuid: None,
+                    tid: None,
+                    data: None,
+                })
+                .collect(),
+            last_included: ticks.last().map(|tick| tick.to_string()),
+            last_scanned: Some("42".to_owned()),
+            check_more,
+        }
+    }
+
+    fn dump_batch(count: usize, last_included: Option<&str>, check_more: bool) -> DumpBatch {
+        DumpBatch {
+            entries: (0..count)
+                .map(|i| DumpEntry {
+                    operation: WalOperation::InsertOrReplaceDocument,
+                    data: serde_json::json!({ "i": i }),
+                })
+                .collect(),
+            last_included: last_included.map(|tick| tick.to_owned()),
+            check_more,
+        }
+    }
+
+    #[test]
+    fn dump_resumes_after_last_included() {
+        let options = DumpOptions::builder().from("1").chunk_size(1024).build();
+        let mut dump = CollectionDump::new(options);
+        dump.advance(dump_batch(2, Some("5"), true));
+        assert_eq!(dump.is_done(), false);
+        let options = dump.options();
+        assert_eq!(options.from.as_deref(), Some("5"));
+        assert_eq!(options.chunk_size, Some(1024));
+        assert_eq!(dump.next_entry().unwrap().data["i"], 0);
+        assert_eq!(dump.next_entry().unwrap().data["i"], 1);
+        assert_eq!(dump.next_entry().is_none(), true);
+
+        dump.advance(dump_batch(1, Some("7"), false));
+        assert_eq!(dump.is_done(), true);
+        assert_eq!(dump.next_entry().is_some(), true);
+
+        // a chunk not telling where to resume from ends the dump
+        let mut dump = CollectionDump::new(DumpOptions::default());
+        dump.advance(dump_batch(0, None, true));
+        assert_eq!(dump.is_done(), true);
+    }
+
+    #[test]
+    fn wal_tail_resumes_after_last_included() {
+        let mut tail = WalTail::new("1", WalTailOptions::default());
+        tail.advance(batch(&["2", "3"], true));
+        assert_eq!(tail.next_entry().unwrap().tick, "2");
+        assert_eq!(tail.next_entry().unwrap().tick, "3");
+        assert_eq!(tail.next_entry().is_none(), true);
+        assert_eq!(tail.take_wait(), None);
+
+        let (from, options) = tail.poll_args();
+        assert_eq!(from, "3");
+        assert_eq!(options.last_scanned.as_deref(), Some("42"));
+
+        // an empty batch keeps the tick to resume from
+        tail.advance(batch(&[], false));
+        assert_eq!(tail.poll_args().0, "3");
+    }
+
+    #[test]
+    fn wal_tail_backs_off_on_empty_batches() {
+        let options = WalTailOptions::builder()
+            .poll_interval(Duration::from_secs(1))
+            .max_poll_interval(Duration::from_secs(3))
+            .build();
+        let mut tail = WalTail::new("1", options);
+
+        tail.advance(batch(&[], false));
+        assert_eq!(tail.take_wait(), Some(Duration::from_secs(1)));
+        tail.advance(batch(&[], false));
+        assert_eq!(tail.take_wait(), Some(Duration::from_secs(2)));
+        tail.advance(batch(&[], false));
+        assert_eq!(tail.take_wait(), Some(Duration::from_secs(3)));
+        tail.fail();
+        assert_eq!(tail.take_wait(), Some(Duration::from_secs(3)));
+
+        // new entries reset the interval, and more entries are fetched at once
+        tail.advance(batch(&["2"], false));
+        assert_eq!(tail.take_wait(), Some(Duration::from_secs(1)));
+        tail.advance(batch(&["3"], true));
+        assert_eq!(tail.take_wait(), None);
+        assert_eq!(tail.is_done(), false);
+    }
+
+    #[test]
+    fn wal_tail_ends_at_to() {
+        let options = WalTailOptions::builder().to("10").build();
+        let mut tail = WalTail::new("1", options);
+        tail.advance(batch(&["2"], true));
+        assert_eq!(tail.is_done(), false);
+        tail.advance(batch(&["3"], false));
+        assert_eq!(tail.is_done(), true);
+        assert_eq!(tail.next_entry().unwrap().tick, "2");
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    #[tokio::test]
+    async fn delays_share_the_timer() {
+        let start = Instant::now();
+        // a dropped delay must not hold up the later ones
+        drop(Delay::new(Duration::from_secs(60)));
+        let long = Delay::new(Duration::from_millis(80));
+        Delay::new(Duration::from_millis(20)).await;
+        assert!(start.elapsed() < Duration::from_millis(80));
+        long.await;
+        assert!(start.elapsed() >= Duration::from_millis(80));
+        Delay::new(Duration::ZERO).await;
+    }
+}
@@ -9,6 +9,7 @@
 //! `ArangoResult`.
 use std::ops::Deref;
 
+use http::{header::ETAG, HeaderMap, StatusCode};
 use log::trace;
 use serde::{
     de::{self, DeserializeOwned, Deserializer},
@@ -16,7 +17,14 @@ use serde::{
 };
 use serde_json::value::Value;
 
-use crate::{ArangoError, ClientError};
+use crate::{
+    error::{
+        ERROR_CONFLICT, ERROR_CURSOR_NOT_FOUND, ERROR_DATA_SOURCE_NOT_FOUND,
+        ERROR_DOCUMENT_NOT_FOUND, ERROR_RESOURCE_LIMIT, ERROR_UNIQUE_CONSTRAINT_VIOLATED,
+        ERROR_VALIDATION_FAILED,
+    },
+    ArangoError, ClientError,
+};
 
 /// Deserialize response from arango server
 ///
@@ -30,7 +38,178 @@ where
     T: DeserializeOwned,
 {
     let response: Response<T> = serde_json::from_str(text)?;
-    Ok(Into::<Result<T, ArangoError>>::into(response)?)
+    Into::<Result<T, ArangoError>>::into(response).map_err(into_client_error)
+}
+
+/// Deserialize response from arango server, accepting an empty body when
+/// the request succeeded.
+///
+/// An empty body with a success `status`, e.g. of a `204 No Content`
+/// response, is deserialized as `null`, so that it is accepted when `T` is
+/// `()` or an `Option`. An empty body with any other status, e.g. sent by a
+/// proxy in front of the server, is an error carrying that status.
+pub(crate) fn deserialize_response_with_status<T>(
+    status: StatusCode,
+    text: &str,
+) -> Result<T, ClientError>
+where
+    T: DeserializeOwned,
+{
+    if !text.trim().is_empty() {
+        return deserialize_response(text);
+    }
+    if status.is_success() {
+        return Ok(T::deserialize(Value::Null)?);
+    }
+    Err(into_client_error(ArangoError {
+        code: status.as_u16(),
+        error_num: 0,
+        message: status.canonical_reason().unwrap_or_default().to_owned(),
+    }))
+}
+
+/// Cast an error of the server into the matching `ClientError` variant.
+fn into_client_error(e: ArangoError) -> ClientError {
+    match e.error_num {
+        ERROR_RESOURCE_LIMIT => ClientError::QueryMemoryLimitExceeded(e),
+        ERROR_CURSOR_NOT_FOUND => ClientError::CursorExpired(e),
+        ERROR_VALIDATION_FAILED => ClientError::SchemaValidation {
+            message: e.message.clone(),
+            collection: None,
+            source: e,
+        },
+        _ => e.into(),
+    }
+}
+
+/// Deserialize a response body made of a bare json array
+///
+/// Some endpoints, e.g. bulk operations or listings, send their results as a
+/// bare array, which has no `error` attribute, and a single error object when
+/// the whole request failed. The array is tried first, anything else goes
+/// through `deserialize_response` so that an error object is cast into an
+/// error.
+pub(crate) fn deserialize_array_response<T>(text: &str) -> Result<Vec<T>, ClientError>
+where
+    T: DeserializeOwned,
+{
+    match serde_json::from_str(text) {
+        Ok(results) => Ok(results),
+        Err(_) => deserialize_response(text),
+    }
+}
+
+/// Tell whether an element returned by a bulk operation is an error stub
+///
+/// The server reports the failure of a single element with an error stub,
+/// e.g. `{"error": true, "errorNum": 1202, "errorMessage": "..."}`, in
+/// place of its result. A document may have an `error` attribute of its own,
+/// but a stub has an error number and no `_id`.
+pub(crate) fn is_error_stub(value: &Value) -> bool {
+    value.get("error").and_then(Value::as_bool) == Some(true)
+        && value.get("errorNum").is_some()
+        && value.get("_id").is_none()
+}
+
+/// Deserialize an element of the array returned by a bulk operation
+///
+/// An error stub, see `is_error_stub`, is cast into an error of this element
+/// only, so that one failure does not fail the whole batch.
+pub(crate) fn deserialize_element<T>(value: Value) -> Result<T, ClientError>
+where
+    T: DeserializeOwned,
+{
+    if !is_error_stub(&value) {
+        return Ok(serde_json::from_value(value)?);
+    }
+    let error_num = value
+        .get("errorNum")
+        .and_then(Value::as_u64)
+        .unwrap_or_default() as u16;
+    let message = value
+        .get("errorMessage")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_owned();
+    // an error stub has no status of its own, use the one the server would
+    // answer for the same error on a single element
+    let code = match error_num {
+        ERROR_DOCUMENT_NOT_FOUND | ERROR_DATA_SOURCE_NOT_FOUND => 404,
+        ERROR_UNIQUE_CONSTRAINT_VIOLATED => 409,
+        ERROR_CONFLICT => 412,
+        _ => 400,
+    };
+    Err(into_client_error(ArangoError {
+        code,
+        error_num,
+        message,
+    }))
+}
+
+/// Deserialize a response body made of one json object per line
+///
+/// Some endpoints, e.g. the replication API, stream newline-delimited json
+/// instead of a single json document. Every line is deserialized with
+/// `deserialize_response` so an error object sent by the server is still
+/// cast into an error. Empty lines, as well as an empty body, are skipped.
+pub(crate) fn deserialize_lines<T>(text: &str) -> Result<Vec<T>, ClientError>
+where
+    T: DeserializeOwned,
+{
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(deserialize_response)
+        .collect()
+}
+
+/// A value returned by the server along with the headers of the response
+///
+/// Returned by the `*_with_headers` variants of some methods, to access
+/// headers like `Etag`, `Location` or the ones of the replication API.
+#[derive(Debug, Clone)]
+pub struct WithHeaders<T> {
+    pub value: T,
+    pub headers: HeaderMap,
+}
+
+impl<T> WithHeaders<T> {
+    pub(crate) fn new(value: T, headers: HeaderMap) -> Self {
+        WithHeaders { value, headers }
+    }
+
+    /// Value of the header `name`, if present and valid UTF-8.
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    /// Value of the `Etag` header, without the surrounding quotes.
+    pub fn etag(&self) -> Option<&str> {
+        self.headers
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(|etag| etag.trim_matches('"'))
+    }
+
+    /// Drop the headers and keep the value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Transform the value, keeping the headers.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> WithHeaders<U> {
+        WithHeaders {
+            value: f(self.value),
+            headers: self.headers,
+        }
+    }
+}
+
+impl<T> Deref for WithHeaders<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
 }
 
 /// An helper enum to divide into successful and failed response
@@ -145,4 +324,145 @@ mod test {
             response
         );
     }
+
+    #[test]
+    fn empty_response() {
+        let result = deserialize_response_with_status::<()>(StatusCode::NO_CONTENT, "");
+        assert_eq!(result.is_ok(), true, "failed: {:?}", result);
+
+        let result =
+            deserialize_response_with_status::<Option<CollectionResponse>>(StatusCode::OK, "");
+        assert_eq!(result.unwrap().is_none(), true);
+
+        let result = deserialize_response_with_status::<CollectionResponse>(StatusCode::OK, "");
+        assert_eq!(result.is_err(), true);
+
+        let result = deserialize_response::<()>("");
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn empty_error_response() {
+        let result = deserialize_response_with_status::<()>(StatusCode::SERVICE_UNAVAILABLE, "");
+        match result {
+            Err(ClientError::Arango(e)) => assert_eq!(e.code(), 503),
+            result => panic!("failed: {:?}", result),
+        }
+
+        let result = deserialize_response_with_status::<Option<Value>>(StatusCode::NOT_FOUND, "");
+        assert_eq!(result.unwrap_err().is_not_found(), true);
+    }
+
+    #[test]
+    fn error_stub_element() {
+        let result = deserialize_element::<CollectionResponse>(serde_json::json!({
+            "error": true,
+            "errorNum": 1210,
+            "errorMessage": "unique constraint violated"
+        }));
+        let err = result.unwrap_err();
+        assert_eq!(err.is_conflict(), true, "failed: {:?}", err);
+
+        let result = deserialize_element::<CollectionResponse>(serde_json::json!({
+            "id": "9947",
+            "name": "relation",
+            "status": 2,
+            "type": 3,
+            "isSystem": false
+        }));
+        assert_eq!(result.unwrap().name, "relation");
+    }
+
+    #[test]
+    fn array_response() {
+        let result = deserialize_array_response::<u32>("[1, 2]");
+        assert_eq!(result.unwrap(), vec![1, 2]);
+
+        let text = "{\"error\":true,\"code\":404,\"errorMessage\":\"error\",\"errorNum\":1203}";
+        let result = deserialize_array_response::<u32>(text);
+        assert_eq!(result.unwrap_err().is_not_found(), true);
+    }
+
+    #[test]
+    fn document_with_error_attribute() {
+        let doc = serde_json::json!({
+            "_id": "users/1",
+            "_key": "1",
+            "_rev": "_c",
+            "error": true,
+            "errorNum": 42
+        });
+        assert_eq!(is_error_stub(&doc), false);
+        let result = deserialize_element::<Value>(doc.clone());
+        assert_eq!(result.unwrap(), doc);
+    }
+
+    #[test]
+    fn memory_limit_exceeded() {
+        let text = "{\"error\":true,\"code\":500,\"errorMessage\":\"query would use more \
+                    memory than allowed (while allocating)\",\"errorNum\":32}";
+        let result = deserialize_response::<Value>(text);
+        assert_eq!(
+            matches!(result, Err(ClientError::QueryMemoryLimitExceeded(_))),
+            true,
+            "failed: {:?}",
+            result
+        );
+
+        let text = "{\"error\":true,\"code\":404,\"errorMessage\":\"error\",\"errorNum\":1203}";
+        let result = deserialize_response::<Value>(text);
+        assert_eq!(matches!(result, Err(ClientError::Arango(_))), true);
+    }
+
+    #[test]
+    fn cursor_expired() {
+        let text = "{\"error\":true,\"code\":404,\"errorMessage\":\"cursor not found\",\
+                    \"errorNum\":1600}";
+        let result = deserialize_response::<Value>(text);
+        assert_eq!(
+            matches!(result, Err(ClientError::CursorExpired(_))),
+            true,
+            "failed: {:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn schema_validation() {
+        let text = "{\"error\":true,\"code\":400,\"errorMessage\":\"name is required\",\
+                    \"errorNum\":1620}";
+        let result = deserialize_response::<Value>(text);
+        match result {
+            Err(ClientError::SchemaValidation {
+                message,
+                collection,
+                source,
+            }) => {
+                assert_eq!(message, "name is required");
+                assert_eq!(collection, None);
+                assert_eq!(source.code(), 400);
+            }
+            result => panic!("failed: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn lines() {
+        let text = "{\"tick\":\"1\"}\n\n{\"tick\":\"2\"}\n";
+        let result = deserialize_lines::<Value>(text);
+        assert_eq!(result.is_ok(), true, "failed: {:?}", result);
+        assert_eq!(result.unwrap().len(), 2);
+
+        let result = deserialize_lines::<Value>("");
+        assert_eq!(result.unwrap().len(), 0);
+
+        let text = "{\"error\":true,\"code\":400,\"errorMessage\":\"error\",\"errorNum\":1409}";
+        let result = deserialize_lines::<Value>(text);
+        assert_eq!(
+            result.is_err(),
+            true,
+            "response should be error: {:?}",
+            result
+        );
+    }
 }
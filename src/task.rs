@@ -0,0 +1,84 @@
+//! Types of the server side tasks, which run JavaScript code once or
+//! periodically.
+//!
+//! Tasks are registered with `Database::create_task` and run in the database
+//! they were created in, with the privileges of the user that created them.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use typed_builder::TypedBuilder;
+
+/// A task to register on the server
+///
+/// `command` is the body of a JavaScript function, which receives `params`
+/// as its only argument. Its return value is discarded, the task has to
+/// store whatever it computes, e.g. in a collection.
+///
+/// Without `period`, the task runs once, `offset` seconds after its
+/// registration. With `period`, it runs every `period` seconds, the first
+/// time after `offset` seconds, until it is deleted. Tasks are not
+/// persisted: they are gone once the server restarts.
+///
+/// # Example
+/// ```rust
+/// use arangors::task::Task;
+/// use serde_json::json;
+///
+/// let task = Task::builder()
+///     .name("cleanup")
+///     .command(
+///         "require('@arangodb').db._query(\
+///          'FOR s IN sessions FILTER s.expires < DATE_NOW() REMOVE s IN sessions')",
+///     )
+///     .period(3600)
+///     .params(json!({}))
+///     .build();
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TypedBuilder)]
+#[builder(doc)]
+pub struct Task {
+    /// The id of the task, generated by the server if not set.
+    #[serde(skip_serializing)]
+    #[builder(default, setter(strip_option, into))]
+    pub id: Option<String>,
+    #[builder(setter(into))]
+    pub name: String,
+    /// JavaScript code to run.
+    #[builder(setter(into))]
+    pub command: String,
+    /// Argument passed to `command`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub params: Option<Value>,
+    /// Number of seconds between two runs, for a periodic task.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub period: Option<u64>,
+    /// Number of seconds to wait before the first run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub offset: Option<f64>,
+}
+
+/// Whether a task runs once or periodically
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskType {
+    Periodic,
+    Timed,
+}
+
+/// A task registered on the server
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct TaskInfo {
+    pub id: String,
+    pub name: String,
+    /// Time of the registration, in seconds since the Unix epoch.
+    pub created: f64,
+    #[serde(rename = "type")]
+    pub task_type: TaskType,
+    pub period: Option<u64>,
+    pub offset: Option<f64>,
+    pub command: String,
+    /// The database the task runs in.
+    pub database: String,
+}
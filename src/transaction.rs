@@ -9,6 +9,8 @@ use url::Url;
 use crate::{
     aql::Cursor,
     collection::response::Info,
+    connection::Session,
+    database::query_batch_error,
     response::{deserialize_response, ArangoResult},
     AqlQuery, ClientError, Collection,
 };
@@ -22,7 +24,49 @@ pub struct TransactionCollections {
     #[builder(default, setter(strip_option))]
     read: Option<Vec<String>>,
 
+    #[builder(default)]
     write: Vec<String>,
+
+    /// Collections locked exclusively: no other transaction can read or write
+    /// them until this one is committed or aborted, which serializes the
+    /// transactions using them. Concurrent transactions wait for the lock up
+    /// to `TransactionSettings::lock_timeout`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    exclusive: Option<Vec<String>>,
+}
+
+impl TransactionCollections {
+    /// Declare the collections of a transaction from their handles.
+    pub fn from_collections<C: ClientExt>(
+        read: &[&Collection<C>],
+        write: &[&Collection<C>],
+    ) -> Self {
+        let names = |collections: &[&Collection<C>]| {
+            collections
+                .iter()
+                .map(|c| c.name().to_owned())
+                .collect::<Vec<_>>()
+        };
+        TransactionCollections {
+            read: if read.is_empty() {
+                None
+            } else {
+                Some(names(read))
+            },
+            write: names(write),
+            exclusive: None,
+        }
+    }
+
+    /// Lock `exclusive` collections exclusively, in addition to the ones
+    /// declared already.
+    pub fn with_exclusive<C: ClientExt>(mut self, exclusive: &[&Collection<C>]) -> Self {
+        self.exclusive
+            .get_or_insert_with(Vec::new)
+            .extend(exclusive.iter().map(|c| c.name().to_owned()));
+        self
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, TypedBuilder)]
@@ -31,17 +75,25 @@ pub struct TransactionCollections {
 pub struct TransactionSettings {
     collections: TransactionCollections,
 
+    /// Whether to force the transaction to write to disk before returning.
     #[builder(default, setter(strip_option))]
     #[serde(skip_serializing_if = "Option::is_none")]
     wait_for_sync: Option<bool>,
 
+    /// Whether reading from collections not declared in `collections` is
+    /// allowed. (default: true)
     #[builder(default = true)]
     allow_implicit: bool,
 
+    /// Timeout in seconds to wait for the collection locks. A value of 0
+    /// disables the timeout.
     #[builder(default, setter(strip_option))]
     #[serde(skip_serializing_if = "Option::is_none")]
     lock_timeout: Option<usize>,
 
+    /// Maximum size of the transaction in bytes. A stream transaction
+    /// exceeding it fails instead of being committed intermediately.
+    /// (rocksdb only)
     #[builder(default, setter(strip_option))]
     #[serde(skip_serializing_if = "Option::is_none")]
     max_transaction_size: Option<usize>,
@@ -123,7 +175,7 @@ pub struct TransactionList {
 pub struct Transaction<C: ClientExt> {
     id: String,
     status: Status,
-    session: Arc<C>,
+    session: Arc<Session<C>>,
     base_url: Url,
 }
 
@@ -131,7 +183,7 @@ impl<C> Transaction<C>
 where
     C: ClientExt,
 {
-    pub(crate) fn new(tx: ArangoTransaction, session: Arc<C>, base_url: Url) -> Self {
+    pub(crate) fn new(tx: ArangoTransaction, session: Arc<Session<C>>, base_url: Url) -> Self {
         Transaction {
             id: tx.id,
             status: tx.status,
@@ -155,7 +207,7 @@ where
     }
 
     /// The transaction session, contains the streaming transaction header value
-    pub fn session(&self) -> Arc<C> {
+    pub fn session(&self) -> Arc<Session<C>> {
         Arc::clone(&self.session)
     }
 
@@ -254,12 +306,14 @@ where
     where
         R: DeserializeOwned,
     {
+        aql.check_database(&self.base_url)?;
         let url = self.base_url.join("_api/cursor").unwrap();
         let resp = self
             .session
             .post(url, &serde_json::to_string(&aql)?)
             .await?;
         deserialize_response(resp.body())
+            .map_err(|e| query_batch_error(e, aql.query(), 0, aql.fails_on_warning()))
     }
 
     #[maybe_async]
@@ -277,17 +331,27 @@ where
     }
 
     #[maybe_async]
-    async fn aql_fetch_all<R>(&self, response: Cursor<R>) -> Result<Vec<R>, ClientError>
+    async fn aql_fetch_all<R>(
+        &self,
+        query: &str,
+        fail_on_warning: bool,
+        response: Cursor<R>,
+    ) -> Result<Vec<R>, ClientError>
     where
         R: DeserializeOwned,
     {
         let mut response_cursor = response;
         let mut results: Vec<R> = Vec::new();
+        let mut batch = 0;
         loop {
+            results.extend(response_cursor.result.into_iter());
             if response_cursor.more {
                 let id = response_cursor.id.unwrap().clone();
-                results.extend(response_cursor.result.into_iter());
-                response_cursor = self.aql_next_batch(id.as_str()).await?;
+                batch += 1;
+                response_cursor = self
+                    .aql_next_batch(id.as_str())
+                    .await
+                    .map_err(|e| query_batch_error(e, query, batch, fail_on_warning))?;
             } else {
                 break;
             }
@@ -310,9 +374,11 @@ where
     where
         R: DeserializeOwned,
     {
+        let query = aql.query();
+        let fail_on_warning = aql.fails_on_warning();
         let response = self.aql_query_batch(aql).await?;
         if response.more {
-            self.aql_fetch_all(response).await
+            self.aql_fetch_all(query, fail_on_warning, response).await
         } else {
             Ok(response.result)
         }
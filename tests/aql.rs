@@ -4,7 +4,7 @@
 use pretty_assertions::assert_eq;
 use serde::{Deserialize, Serialize};
 
-use arangors::{AqlQuery, Connection, Document};
+use arangors::{aql, AqlOptions, AqlQuery, ClientError, Connection, Document};
 use common::{connection, test_setup};
 
 use crate::common::{get_arangodb_host, get_root_password, get_root_user};
@@ -34,6 +34,25 @@ async fn test_aql_str() {
     assert_eq!(result[0].document.password, "test2_pwd");
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_query_raw() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+    let cursor = db
+        .aql_query_raw(r#"FOR i in test_collection FILTER i.username=="test2" return i"#)
+        .await
+        .unwrap();
+    assert_eq!(cursor.count, Some(1));
+    assert_eq!(cursor.more, false);
+    assert_eq!(cursor.result[0]["password"], "test2_pwd");
+    assert_eq!(cursor.extra.is_some(), true);
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -93,3 +112,363 @@ async fn test_aql_try_bind() {
     assert_eq!(result.len(), 1);
     assert_eq!(result[0].document.password, "test2_pwd");
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_bind_collection() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+    let collection = db.collection("test_collection").await.unwrap();
+
+    let aql = AqlQuery::builder()
+        .query(r#"FOR i in @@coll FILTER i.username==@username return i"#)
+        .bind_collection("@@coll", &collection)
+        .unwrap()
+        .bind_var("username", "test2")
+        .build();
+    let result: Vec<Document<User>> = db.aql_query(aql).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].document.password, "test2_pwd");
+
+    // the collection must be bound as a collection parameter
+    let result = AqlQuery::builder()
+        .query(r#"FOR i in @@coll return i"#)
+        .bind_collection("@coll", &collection);
+    assert!(matches!(result, Err(ClientError::InvalidQuery(_))));
+
+    // a query is not run on another database than the one of its collections
+    let system_db = conn.db("_system").await.unwrap();
+    let aql = AqlQuery::builder()
+        .query(r#"FOR i in @@coll return i"#)
+        .bind_collection("@@coll", &collection)
+        .unwrap()
+        .build();
+    let result: Result<Vec<Document<User>>, ClientError> = system_db.aql_query(aql).await;
+    assert!(matches!(result, Err(ClientError::InvalidQuery(_))));
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_bind_collections() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+    let collection = db.collection("test_collection").await.unwrap();
+
+    // several collection bind parameters in one query
+    let aql = AqlQuery::builder()
+        .query(
+            r#"FOR u IN UNION(
+                   (FOR i IN @@first FILTER i.username == @username RETURN i),
+                   (FOR i IN @@second FILTER i.username == @username RETURN i)
+               ) RETURN u"#,
+        )
+        .bind_collection("@@first", &collection)
+        .unwrap()
+        .bind_collection("@@second", &collection)
+        .unwrap()
+        .bind_var("username", "test2")
+        .build();
+    let result: Vec<Document<User>> = db.aql_query(aql).await.unwrap();
+    assert_eq!(result.len(), 2);
+
+    // collection names bound as an array value
+    let aql = AqlQuery::builder()
+        .query("FOR name IN @names RETURN name")
+        .bind_collections("@names", &[&collection, &collection])
+        .unwrap()
+        .build();
+    let result: Vec<String> = db.aql_query(aql).await.unwrap();
+    assert_eq!(result, vec!["test_collection", "test_collection"]);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_macro() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+    let collection = db.collection("test_collection").await.unwrap();
+
+    let aql = aql!(
+        r#"FOR i in @@coll FILTER i.username==@username return i"#,
+        coll = collection.name(),
+        username = "test2",
+    )
+    .unwrap();
+    let result: Vec<Document<User>> = db.aql_query(aql).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].document.password, "test2_pwd");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_memory_limit() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+    let aql = AqlQuery::builder()
+        .query("FOR i IN 1..100000 RETURN CONCAT('value-', i)")
+        .memory_limit(32 * 1024)
+        .build();
+    let result = db.aql_query::<String>(aql).await;
+    assert_eq!(
+        matches!(result, Err(ClientError::QueryMemoryLimitExceeded(_))),
+        true,
+        "unexpected result: {:?}",
+        result.map(|r| r.len())
+    );
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_result_decode_error() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+
+    let result = db.aql_str::<User>(r#"RETURN { "username": "test" }"#).await;
+    match result {
+        Err(ClientError::QueryResultDecode { query, batch, .. }) => {
+            assert_eq!(query, r#"RETURN { "username": "test" }"#);
+            assert_eq!(batch, 0);
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    // only the third element can not be deserialized, one per batch
+    let aql = AqlQuery::builder()
+        .query("FOR i IN 1..3 RETURN i == 3 ? 'three' : i")
+        .batch_size(1)
+        .build();
+    let result = db.aql_query::<u32>(aql).await;
+    match result {
+        Err(ClientError::QueryResultDecode { batch, .. }) => assert_eq!(batch, 2),
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_insert_returning_keys() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+    let collection_name = "test_collection_insert_returning_keys";
+    let _ = db.drop_collection(collection_name).await;
+    let coll = db.create_collection(collection_name).await.unwrap();
+
+    let users = vec![
+        User {
+            username: "a".to_string(),
+            password: "a_pwd".to_string(),
+        },
+        User {
+            username: "b".to_string(),
+            password: "b_pwd".to_string(),
+        },
+    ];
+    let keys = db
+        .aql_insert_returning_keys(collection_name, &users)
+        .await
+        .unwrap();
+    assert_eq!(keys.len(), 2);
+
+    let user: Document<User> = coll.document(&keys[1]).await.unwrap();
+    assert_eq!(user.document.username, "b");
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_cursor_expired() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+
+    // a cursor that never existed is reported like an expired one
+    let id = "1";
+    let result = db.aql_next_batch::<u32>(id).await;
+    assert_eq!(
+        matches!(result, Err(ClientError::CursorExpired(_))),
+        true,
+        "unexpected result: {:?}",
+        result.map(|c| c.result)
+    );
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_fail_on_warning() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+    let query = |fail_on_warning| {
+        AqlQuery::builder()
+            .query("RETURN 1 / @zero")
+            .bind_var("zero", 0)
+            .options(
+                AqlOptions::builder()
+                    .fail_on_warning(fail_on_warning)
+                    .build(),
+            )
+            .build()
+    };
+
+    let (result, warnings) = db
+        .aql_query_with_warnings::<Option<f64>>(query(false))
+        .await
+        .unwrap();
+    assert_eq!(result, vec![None]);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].code, 1562);
+
+    let err = db.aql_query::<Option<f64>>(query(true)).await.unwrap_err();
+    match err {
+        ClientError::QueryWarning { code, .. } => assert_eq!(code, 1562),
+        err => panic!("unexpected error: {:?}", err),
+    }
+
+    // the warning of a streaming query is raised while fetching a later batch
+    let streaming = AqlQuery::builder()
+        .query("FOR i IN 1..10 RETURN i == 10 ? 1 / @zero : i")
+        .bind_var("zero", 0)
+        .batch_size(1)
+        .options(
+            AqlOptions::builder()
+                .stream(true)
+                .fail_on_warning(true)
+                .build(),
+        )
+        .build();
+    let err = db.aql_query::<Option<f64>>(streaming).await.unwrap_err();
+    match err {
+        ClientError::QueryWarning { code, .. } => assert_eq!(code, 1562),
+        err => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_query_one() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+
+    let sum: Option<u32> = db
+        .aql_query_one(AqlQuery::builder().query("RETURN SUM([1, 2, 3])").build())
+        .await
+        .unwrap();
+    assert_eq!(sum, Some(6));
+
+    let none: Option<u32> = db
+        .aql_query_one(AqlQuery::builder().query("FOR i IN [] RETURN i").build())
+        .await
+        .unwrap();
+    assert_eq!(none, None);
+
+    let err = db
+        .aql_query_one::<u32>(AqlQuery::builder().query("FOR i IN 1..3 RETURN i").build())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, ClientError::TooManyResults { .. }),
+        "{:?}",
+        err
+    );
+
+    let streaming = |batch_size| {
+        AqlQuery::builder()
+            .query("FOR i IN 1..1 RETURN i")
+            .batch_size(batch_size)
+            .options(AqlOptions::builder().stream(true).build())
+            .build()
+    };
+    let one: Option<u32> = db.aql_query_one(streaming(1)).await.unwrap();
+    assert_eq!(one, Some(1));
+    let one: Option<u32> = db.aql_query_one(streaming(100)).await.unwrap();
+    assert_eq!(one, Some(1));
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_stream() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+    let aql = AqlQuery::builder()
+        .query("FOR i IN 1..5 RETURN i")
+        .batch_size(2)
+        .options(AqlOptions::builder().stream(true).build())
+        .build();
+    let result: Vec<u32> = db.aql_query(aql).await.unwrap();
+    assert_eq!(result, vec![1, 2, 3, 4, 5]);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_kill_queries_matching() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+    // a streaming cursor keeps its query running until it is exhausted
+    let aql = AqlQuery::builder()
+        .query("/* test_kill_queries_matching */ FOR i IN 1..10 RETURN i")
+        .batch_size(1)
+        .options(AqlOptions::builder().stream(true).build())
+        .build();
+    let cursor = db.aql_query_batch::<u32>(aql).await.unwrap();
+    assert_eq!(cursor.more, true);
+
+    let queries = db.running_queries().await.unwrap();
+    let running = queries
+        .iter()
+        .find(|q| q.query.contains("test_kill_queries_matching"))
+        .expect("The streaming query should be running");
+    assert_eq!(running.stream, true);
+
+    let killed = db
+        .kill_queries_matching("test_kill_queries_matching")
+        .await
+        .unwrap();
+    assert_eq!(killed, 1);
+    let next = db
+        .aql_next_batch::<u32>(cursor.id.as_deref().unwrap())
+        .await;
+    assert_eq!(next.is_err(), true);
+}
@@ -5,13 +5,18 @@ use log::trace;
 use pretty_assertions::assert_eq;
 use serde_json::{json, Value};
 
-use crate::common::{collection, connection};
+use crate::common::{collection, connection, root_connection};
 use arangors::{
     collection::{
-        options::{ChecksumOptions, PropertiesOptions},
+        find::SortOrder,
+        options::{
+            ChecksumOptions, CreateOptions, DropOptions, ImportOptions, KeyOptions,
+            PropertiesOptions,
+        },
         response::Status,
         CollectionType,
     },
+    index::{Index, IndexSettings},
     ClientError, Connection, Document,
 };
 use common::{get_arangodb_host, get_normal_password, get_normal_user, test_setup};
@@ -33,7 +38,29 @@ async fn test_get_collection() {
     let coll = database.collection("test_collection").await;
     assert_eq!(coll.is_err(), false);
     let coll = database.collection("test_collection_non_exists").await;
-    assert_eq!(coll.is_err(), true);
+    assert_eq!(coll.unwrap_err().is_not_found(), true);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_try_collection() {
+    test_setup();
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+    let coll = database.try_collection("test_collection").await.unwrap();
+    assert_eq!(
+        coll.map(|c| c.name().to_string()),
+        Some("test_collection".to_string())
+    );
+    let coll = database
+        .try_collection("test_collection_non_exists")
+        .await
+        .unwrap();
+    assert_eq!(coll.is_none(), true);
 }
 
 #[maybe_async::test(
@@ -54,6 +81,175 @@ async fn test_get_db_from_collection() {
     assert_eq!(db.url(), database.url());
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_create_collection_with_properties() {
+    test_setup();
+    let collection_name = "test_collection_create_with_properties";
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    let _ = database.drop_collection(collection_name).await;
+
+    let (coll, properties) = database
+        .create_collection_with_properties(
+            CreateOptions::builder()
+                .name(collection_name)
+                .wait_for_sync(true)
+                .build(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(coll.name(), collection_name);
+    assert_eq!(properties.info.name, collection_name);
+    assert_eq!(properties.info.id, coll.id());
+    assert_eq!(properties.detail.wait_for_sync, true);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_create_collection_with_key_options() {
+    test_setup();
+    let collection_name = "test_collection_create_with_key_options";
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    let _ = database.drop_collection(collection_name).await;
+
+    let key_options = KeyOptions::builder()
+        .key_type("autoincrement")
+        .allow_user_keys(false)
+        .increment(5)
+        .offset(100)
+        .build();
+    let (coll, properties) = database
+        .create_collection_with_properties(
+            CreateOptions::builder()
+                .name(collection_name)
+                .key_options(key_options)
+                .build(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    let key_options = properties.detail.key_options;
+    assert_eq!(key_options.key_type, Some("autoincrement".to_string()));
+    assert_eq!(key_options.allow_user_keys, false);
+    assert_eq!(key_options.increment, Some(5));
+    assert_eq!(key_options.offset, Some(100));
+
+    let first = coll
+        .create_document(json!({}), Default::default())
+        .await
+        .unwrap();
+    let second = coll
+        .create_document(json!({}), Default::default())
+        .await
+        .unwrap();
+    let first: u64 = first.header().unwrap()._key.parse().unwrap();
+    let second: u64 = second.header().unwrap()._key.parse().unwrap();
+    assert_eq!(second - first, 5);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[cfg(feature = "cluster")]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_create_collection_distributed_like() {
+    test_setup();
+    let vertices_name = "test_collection_distribute_vertices";
+    let edges_name = "test_collection_distribute_edges";
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    let _ = database.drop_collection(edges_name).await;
+    let _ = database.drop_collection(vertices_name).await;
+
+    let (vertices, properties) = database
+        .create_collection_with_properties(
+            CreateOptions::builder()
+                .name(vertices_name)
+                .number_of_shards(3)
+                .sharding_strategy("hash")
+                .build(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(properties.detail.number_of_shards, Some(3));
+    assert_eq!(
+        properties.detail.sharding_strategy,
+        Some("hash".to_string())
+    );
+    assert_eq!(
+        vertices.shard_keys().await.unwrap(),
+        vec!["_key".to_string()]
+    );
+
+    let (edges, properties) = database
+        .create_collection_with_properties(
+            CreateOptions::builder()
+                .name(edges_name)
+                .collection_type(CollectionType::Edge)
+                .distribute_shards_like(vertices_name)
+                .build(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        properties.detail.distribute_shards_like,
+        Some(vertices_name.to_string())
+    );
+    assert_eq!(properties.detail.number_of_shards, Some(3));
+
+    // the prototype can only be dropped once the other collection is gone
+    edges.drop().await.expect("Should drop the collection");
+    vertices.drop().await.expect("Should drop the collection");
+}
+
+#[test]
+fn test_create_options_without_key_options() {
+    let options = CreateOptions::builder().name("test").build();
+    let body = serde_json::to_value(&options).unwrap();
+    assert_eq!(body.get("keyOptions"), None);
+
+    let options = CreateOptions::builder()
+        .name("test")
+        .key_options(KeyOptions::builder().key_type("padded").build())
+        .build();
+    let body = serde_json::to_value(&options).unwrap();
+    assert_eq!(body["keyOptions"], json!({ "type": "padded" }));
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_get_document_url() {
+    test_setup();
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+    let coll = database.collection("test_collection").await.unwrap();
+    assert_eq!(
+        coll.document_url().as_str(),
+        format!("{}_api/document/test_collection/", database.url())
+    );
+    assert_eq!(coll.document_url(), coll.doc_url());
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -65,10 +261,10 @@ async fn test_create_and_drop_collection() {
     let conn = connection().await;
 
     let database = conn.db("test_db").await.unwrap();
-    let coll = database.drop_collection(collection_name).await;
+    let existed = database.drop_collection(collection_name).await;
     assert_eq!(
-        coll.is_err(),
-        true,
+        existed.unwrap(),
+        false,
         "The collection should have been drop previously"
     );
     let coll = database.create_collection(collection_name).await;
@@ -82,7 +278,7 @@ async fn test_create_and_drop_collection() {
     );
 
     let res = database.drop_collection(collection_name).await;
-    assert_eq!(res.is_err(), false, "Fail to drop the collection");
+    assert_eq!(res.unwrap(), true, "Fail to drop the collection");
 
     let coll = database.create_collection(collection_name).await;
     assert_eq!(coll.is_err(), false, "Fail to create the collection");
@@ -102,10 +298,10 @@ async fn test_create_and_drop_edge_collection() {
     let conn = connection().await;
 
     let database = conn.db("test_db").await.unwrap();
-    let coll = database.drop_collection(collection_name).await;
+    let existed = database.drop_collection(collection_name).await;
     assert_eq!(
-        coll.is_err(),
-        true,
+        existed.unwrap(),
+        false,
         "The collection should have been drop previously"
     );
     let coll = database.create_edge_collection(collection_name).await;
@@ -118,7 +314,7 @@ async fn test_create_and_drop_edge_collection() {
     );
 
     let res = database.drop_collection(collection_name).await;
-    assert_eq!(res.is_err(), false, "Fail to drop the collection");
+    assert_eq!(res.unwrap(), true, "Fail to drop the collection");
 
     let coll = database.create_edge_collection(collection_name).await;
     assert_eq!(coll.is_err(), false, "Fail to create the collection");
@@ -148,6 +344,33 @@ async fn test_truncate_collection() {
 
     coll.drop().await.expect("Fail to drop the collection");
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_truncate_and_drop_if_exists() {
+    test_setup();
+    let collection_name = "test_collection_truncate_if_exists";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let truncated = coll.truncate_if_exists().await;
+    assert_eq!(truncated.is_ok(), true);
+
+    let database = conn.db("test_db").await.unwrap();
+    let dropped = database.drop_collection(collection_name).await.unwrap();
+    assert_eq!(dropped, true);
+
+    let truncated = coll.truncate().await;
+    assert_eq!(truncated.is_err(), true);
+    let truncated = coll.truncate_if_exists().await;
+    assert_eq!(truncated.is_ok(), true);
+    let dropped = coll.drop_if_exists().await;
+    assert_eq!(dropped.is_ok(), true);
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -165,10 +388,15 @@ async fn test_get_properties() {
     let result = properties.unwrap();
 
     assert_eq!(result.info.name, collection_name);
-    #[cfg(rocksdb)]
+    #[cfg(feature = "rocksdb")]
     {
         assert_eq!(result.detail.cache_enabled, false);
     }
+    #[cfg(feature = "arango3_7")]
+    {
+        assert_eq!(result.detail.sync_by_revision.is_some(), true);
+        assert_eq!(result.detail.uses_revisions_as_document_ids.is_some(), true);
+    }
     #[cfg(mmfiles)]
     {
         assert_eq!(result.detail.is_volatile, false);
@@ -205,7 +433,7 @@ async fn test_get_document_count() {
     let result = count.unwrap();
     assert_eq!(result.info.count, Some(0));
     assert_eq!(result.info.name, collection_name);
-    #[cfg(rocksdb)]
+    #[cfg(feature = "rocksdb")]
     assert_eq!(result.detail.cache_enabled, false);
     assert_eq!(result.info.is_system, false);
     assert_eq!(result.detail.wait_for_sync, false);
@@ -230,6 +458,90 @@ async fn test_get_document_count() {
     coll.drop().await.expect("Should drop the collection");
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_count_cached() {
+    test_setup();
+    let collection_name = "test_collection_count_cached";
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    let coll = collection(&conn, collection_name).await;
+    let max_age = std::time::Duration::from_secs(60);
+
+    let count = coll.count_cached(max_age).await.unwrap();
+    assert_eq!(count, 0);
+
+    // a write of another client is not seen until the cached count expires
+    database
+        .aql_str::<Value>(r#"INSERT { "name": "test_user" } INTO test_collection_count_cached"#)
+        .await
+        .unwrap();
+    let count = coll.count_cached(max_age).await.unwrap();
+    assert_eq!(count, 0);
+    let count = coll
+        .count_cached(std::time::Duration::from_secs(0))
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+
+    // a write through the handle drops the cached count
+    coll.create_document(json!({ "name": "test_user" }), Default::default())
+        .await
+        .unwrap();
+    let count = coll.count_cached(max_age).await.unwrap();
+    assert_eq!(count, 2);
+
+    coll.truncate().await.unwrap();
+    let count = coll.count_cached(max_age).await.unwrap();
+    assert_eq!(count, 0);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_system_collection_properties() {
+    test_setup();
+    let conn = root_connection().await;
+    let database = conn.db("_system").await.unwrap();
+    let coll = database.collection("_users").await.unwrap();
+
+    let result = coll.properties().await.unwrap();
+    assert_eq!(result.info.name, "_users");
+    assert_eq!(result.info.is_system, true);
+    assert_eq!(result.info.globally_unique_id.is_empty(), false);
+    assert_eq!(result.info.status, Status::Loaded);
+}
+
+#[cfg(not(feature = "cluster"))]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_count_by_shard() {
+    test_setup();
+    let collection_name = "test_collection_count_by_shard";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    coll.create_document(json!({ "a": 1 }), Default::default())
+        .await
+        .unwrap();
+
+    let counts = coll.count_by_shard().await.unwrap();
+    assert_eq!(counts.len(), 1);
+    assert_eq!(counts.get(collection_name), Some(&1));
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -246,7 +558,7 @@ async fn test_get_statistics() {
     let result = statistics.unwrap();
     assert_eq!(result.count, Some(0), "count");
     assert_eq!(result.info.name, collection_name);
-    #[cfg(rocksdb)]
+    #[cfg(feature = "rocksdb")]
     assert_eq!(result.detail.cache_enabled, false);
     assert_eq!(result.info.is_system, false);
     assert_eq!(result.detail.wait_for_sync, false, "wait for sync");
@@ -269,6 +581,60 @@ async fn test_get_statistics() {
     coll.drop().await.expect("Should drop the collection");
 }
 
+#[cfg(not(feature = "mmfiles"))]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_get_statistics_index_and_documents_size() {
+    test_setup();
+    let collection_name = "test_collection_statistics_sizes";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+    let database = conn.db("test_db").await.unwrap();
+
+    database
+        .create_index(
+            collection_name,
+            &Index::builder()
+                .fields(vec!["name".to_string()])
+                .settings(IndexSettings::Persistent {
+                    unique: false,
+                    sparse: false,
+                    deduplicate: false,
+                })
+                .build(),
+        )
+        .await
+        .unwrap();
+    for i in 0..10 {
+        coll.create_document(json!({ "name": format!("name_{}", i) }), Default::default())
+            .await
+            .unwrap();
+    }
+
+    let result = coll.statistics().await.unwrap();
+    assert_eq!(result.figures.indexes.count, Some(2));
+    assert_eq!(result.figures.indexes.size.is_some(), true);
+    assert_eq!(result.figures.documents_size.is_some(), true);
+    assert_eq!(result.figures.engine.is_none(), true);
+
+    let result = coll.statistics_with_details().await.unwrap();
+    let engine = result.figures.engine.expect("engine details");
+    assert_eq!(engine.documents, Some(10));
+    if let Some(compaction) = engine.compaction_status {
+        assert_eq!(
+            compaction.bytes_done <= compaction.bytes_total,
+            true,
+            "{:?}",
+            compaction
+        );
+    }
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -284,7 +650,7 @@ async fn test_get_revision_id() {
 
     let result = revision.unwrap();
     assert_eq!(result.info.name, collection_name);
-    #[cfg(rocksdb)]
+    #[cfg(feature = "rocksdb")]
     assert_eq!(result.detail.cache_enabled, false);
     assert_eq!(result.info.is_system, false);
     assert_eq!(result.detail.wait_for_sync, false);
@@ -368,6 +734,89 @@ async fn test_get_checksum() {
     coll.drop().await.expect("Should drop the collection");
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_import_edges() {
+    test_setup();
+    let edges_name = "test_collection_import_edges";
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    let _ = database.drop_collection(edges_name).await;
+    let edges = database.create_edge_collection(edges_name).await.unwrap();
+
+    let result = edges
+        .import_edges(
+            &[
+                ("vertices/a", "vertices/b", json!({ "weight": 1 })),
+                ("vertices/b", "vertices/c", json!({ "weight": 2 })),
+            ],
+            ImportOptions::builder().details(true).build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.created, 2);
+    assert_eq!(result.errors, 0);
+
+    let from: Vec<String> = database
+        .aql_str(&format!(
+            "FOR e IN {} FILTER e._to == 'vertices/c' RETURN e._from",
+            edges_name
+        ))
+        .await
+        .unwrap();
+    assert_eq!(from, vec!["vertices/b".to_string()]);
+
+    // handles must be prefixed by a collection
+    let result = edges
+        .import_edges(&[("a", "vertices/b", json!({}))], Default::default())
+        .await;
+    assert_eq!(
+        matches!(result, Err(ClientError::InvalidDocumentBody(_))),
+        true
+    );
+
+    edges.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_checksums_match() {
+    test_setup();
+    let conn = connection().await;
+    let coll = collection(&conn, "test_collection_checksums_match").await;
+    let other = collection(&conn, "test_collection_checksums_match_other").await;
+
+    let doc = json!({ "_key": "a", "value": 1 });
+    coll.create_document(doc.clone(), Default::default())
+        .await
+        .unwrap();
+    other
+        .create_document(doc, Default::default())
+        .await
+        .unwrap();
+
+    let result = coll.checksums_match(&other, false, true).await.unwrap();
+    assert_eq!(result.matches, true);
+    assert_eq!(result.revision.is_empty(), false);
+    assert_eq!(result.other_revision.is_empty(), false);
+
+    other
+        .create_document(json!({ "_key": "b" }), Default::default())
+        .await
+        .unwrap();
+    let result = coll.checksums_match(&other, false, false).await.unwrap();
+    assert_eq!(result.matches, false);
+
+    coll.drop().await.expect("Should drop the collection");
+    other.drop().await.expect("Should drop the collection");
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -478,7 +927,17 @@ async fn test_put_load_indexes_into_memory() {
     let load_index = coll.load_indexes().await;
 
     let result = load_index.unwrap();
-    assert_eq!(result, true);
+    assert_eq!(result.result, true);
+    assert_eq!(result.truncated(), None);
+
+    let result = coll.load_indexes_with_figures().await.unwrap();
+    assert_eq!(result.result, true);
+    #[cfg(feature = "rocksdb")]
+    {
+        assert_eq!(result.cache_size.is_some(), true);
+        assert_eq!(result.cache_usage.is_some(), true);
+        assert_eq!(result.truncated().is_some(), true);
+    }
 
     coll.drop().await.expect("Should drop the collection");
 }
@@ -499,7 +958,7 @@ async fn test_put_changes_properties() {
 
     let result = updated_properties.unwrap();
     assert_eq!(result.info.name, collection_name);
-    #[cfg(rocksdb)]
+    #[cfg(feature = "rocksdb")]
     assert_eq!(result.detail.cache_enabled, false);
     assert_eq!(result.info.is_system, false);
     assert_eq!(result.detail.wait_for_sync, true);
@@ -515,6 +974,147 @@ async fn test_put_changes_properties() {
     coll.drop().await.expect("Should drop the collection");
 }
 
+#[cfg(feature = "cluster")]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_put_changes_write_concern() {
+    test_setup();
+    let collection_name = "test_collection_changes_write_concern";
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    let _ = database.drop_collection(collection_name).await;
+    let (coll, properties) = database
+        .create_collection_with_properties(
+            CreateOptions::builder()
+                .name(collection_name)
+                .replication_factor(2)
+                .build(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(properties.detail.write_concern, 1);
+
+    let options = PropertiesOptions::builder().write_concern(2).build();
+    let result = coll.change_properties(options).await.unwrap();
+    assert_eq!(result.detail.write_concern, 2);
+
+    let result = coll.properties().await.unwrap();
+    assert_eq!(result.detail.write_concern, 2);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[cfg(feature = "rocksdb")]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_put_changes_cache_enabled() {
+    test_setup();
+    let collection_name = "test_collection_changes_cache_enabled";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let options = PropertiesOptions::builder().cache_enabled(true).build();
+    let result = coll.change_properties(options).await.unwrap();
+    assert_eq!(result.detail.cache_enabled, true);
+    assert_eq!(result.detail.wait_for_sync, false);
+
+    let result = coll.properties().await.unwrap();
+    assert_eq!(result.detail.cache_enabled, true);
+
+    let options = PropertiesOptions::builder().cache_enabled(false).build();
+    let result = coll.change_properties(options).await.unwrap();
+    assert_eq!(result.detail.cache_enabled, false);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_drop_with_options() {
+    test_setup();
+    let collection_name = "test_collection_drop_with_options";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+    let id = coll.id().to_owned();
+
+    let dropped = coll.drop_with_options(Default::default()).await.unwrap();
+    assert_eq!(dropped.id, id);
+    assert_eq!(dropped.name, collection_name);
+    assert_eq!(dropped.was_system, false);
+
+    let database = conn.db("test_db").await.unwrap();
+    let system_name = "_test_collection_drop_system";
+    let _ = database.drop_collection(system_name).await;
+    let coll = database
+        .create_collection_with_options(
+            CreateOptions::builder()
+                .name(system_name)
+                .is_system(true)
+                .build(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    let dropped = coll
+        .drop_with_options(DropOptions::builder().is_system(true).build())
+        .await
+        .unwrap();
+    assert_eq!(dropped.name, system_name);
+    assert_eq!(dropped.was_system, true);
+}
+
+#[cfg(feature = "arango3_10")]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_put_changes_computed_values() {
+    use arangors::collection::options::{ComputeOn, ComputedValue};
+
+    test_setup();
+    let collection_name = "test_collection_computed_values";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let computed = ComputedValue::builder()
+        .name("search")
+        .expression("RETURN LOWER(@doc.name)")
+        .overwrite(true)
+        .compute_on(vec![ComputeOn::Insert, ComputeOn::Update])
+        .keep_null(true)
+        .fail_on_warning(false)
+        .build();
+    let options = PropertiesOptions::builder()
+        .computed_values(vec![computed.clone()])
+        .build();
+    let result = coll.change_properties(options).await.unwrap();
+    assert_eq!(result.detail.computed_values, Some(vec![computed.clone()]));
+
+    let result = coll.properties().await.unwrap();
+    assert_eq!(result.detail.computed_values, Some(vec![computed]));
+
+    let created = coll
+        .create_document(json!({ "name": "Alice" }), Default::default())
+        .await
+        .unwrap();
+    let key = created.header().unwrap()._key.clone();
+    let doc: Document<Value> = coll.document(&key).await.unwrap();
+    assert_eq!(doc.document["search"], "alice");
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -535,6 +1135,28 @@ async fn test_put_rename() {
     assert_eq!(result.is_system, false);
     assert_eq!(result.status, Status::Loaded);
     assert_eq!(result.collection_type, CollectionType::Document);
+    assert_eq!(
+        coll.url().as_str().ends_with("/test_collection_renamed_2/"),
+        true
+    );
+    assert_eq!(
+        coll.doc_url()
+            .as_str()
+            .ends_with("/test_collection_renamed_2/"),
+        true
+    );
+
+    // documents are reached through the new name
+    let created = coll
+        .create_document(json!({ "_key": "renamed" }), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        created.header().unwrap()._id,
+        "test_collection_renamed_2/renamed"
+    );
+    let doc: Document<Value> = coll.document("renamed").await.unwrap();
+    assert_eq!(doc.header._key, "renamed");
 
     coll.drop().await.expect("Should drop the collection");
 }
@@ -593,3 +1215,310 @@ async fn test_put_rotate_journal() {
 
     coll.drop().await.expect("Should drop the collection");
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_geo_near_and_within() {
+    test_setup();
+    let collection_name = "test_collection_geo";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+    let database = conn.db("test_db").await.unwrap();
+
+    let index = Index::builder()
+        .fields(vec!["location".to_string()])
+        .settings(IndexSettings::Geo { geo_json: true })
+        .build();
+    database
+        .create_index(collection_name, &index)
+        .await
+        .unwrap();
+
+    for (name, lat, lng) in [("a", 0.0, 0.0), ("b", 0.0, 1.0), ("c", 10.0, 10.0)] {
+        coll.create_document(
+            json!({ "name": name, "location": [lng, lat] }),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    }
+
+    let near: Vec<Value> = coll.near("location", 0.0, 0.0, 2, None).await.unwrap();
+    assert_eq!(near.len(), 2);
+    assert_eq!(near[0]["name"], "a");
+    assert_eq!(near[1]["name"], "b");
+
+    let within: Vec<Value> = coll
+        .within("location", 0.0, 0.0, 200_000.0, Some("distance"))
+        .await
+        .unwrap();
+    assert_eq!(within.len(), 2);
+    assert_eq!(within[0]["distance"], 0.0);
+    assert!(within[1]["distance"].as_f64().unwrap() > 0.0);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_fulltext() {
+    test_setup();
+    let collection_name = "test_collection_fulltext";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+    let database = conn.db("test_db").await.unwrap();
+
+    let missing_index = coll.fulltext::<Value>("text", "prefix:arango", None).await;
+    assert_eq!(missing_index.is_err(), true);
+
+    let index = Index::builder()
+        .fields(vec!["text".to_string()])
+        .settings(IndexSettings::Fulltext { min_length: 3 })
+        .build();
+    database
+        .create_index(collection_name, &index)
+        .await
+        .unwrap();
+
+    for text in ["arangodb is a database", "rust driver", "arangors in rust"] {
+        coll.create_document(json!({ "text": text }), Default::default())
+            .await
+            .unwrap();
+    }
+
+    let found: Vec<Value> = coll.fulltext("text", "prefix:arango", None).await.unwrap();
+    assert_eq!(found.len(), 2);
+
+    let found: Vec<Value> = coll.fulltext("text", "rust", Some(1)).await.unwrap();
+    assert_eq!(found.len(), 1);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_find() {
+    test_setup();
+    let collection_name = "test_collection_find";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    for (name, age, city) in [
+        ("alice", 30, "berlin"),
+        ("bob", 25, "paris"),
+        ("carol", 35, "berlin"),
+        ("dave", 40, "rome"),
+    ] {
+        coll.create_document(
+            json!({ "name": name, "age": age, "address": { "city": city } }),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    }
+
+    let found: Vec<Value> = coll
+        .find()
+        .filter_eq("address.city", "berlin")
+        .sort("age", SortOrder::Desc)
+        .run()
+        .await
+        .unwrap();
+    let names: Vec<&str> = found.iter().map(|d| d["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["carol", "alice"]);
+
+    let found: Vec<Value> = coll
+        .find()
+        .filter_in("name", vec!["bob", "dave", "eve"])
+        .sort("name", SortOrder::Asc)
+        .offset(1)
+        .run()
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0]["name"], "dave");
+
+    let found: Vec<Value> = coll
+        .find()
+        .sort("age", SortOrder::Asc)
+        .limit(2)
+        .run()
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0]["name"], "bob");
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_keys_in_range_and_with_prefix() {
+    test_setup();
+    let collection_name = "test_collection_keys_matching";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    for key in ["a1", "a2", "a3", "b1", "b2", "c1"] {
+        coll.create_document(json!({ "_key": key }), Default::default())
+            .await
+            .unwrap();
+    }
+
+    let keys = coll.keys_in_range("a2", "b2", None).await.unwrap();
+    assert_eq!(keys, vec!["a2", "a3", "b1"]);
+
+    let keys = coll.keys_in_range("a", "c", Some(2)).await.unwrap();
+    assert_eq!(keys, vec!["a1", "a2"]);
+
+    let keys = coll.keys_with_prefix("b", None).await.unwrap();
+    assert_eq!(keys, vec!["b1", "b2"]);
+
+    let keys = coll.keys_with_prefix("a", Some(1)).await.unwrap();
+    assert_eq!(keys, vec!["a1"]);
+
+    let keys = coll.keys_with_prefix("z", None).await.unwrap();
+    assert_eq!(keys.is_empty(), true);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_upsert() {
+    test_setup();
+    let conn = connection().await;
+    let coll = collection(&conn, "test_collection_aql_upsert").await;
+
+    let doc: Document<Value> = coll
+        .aql_upsert(
+            json!({ "email": "a@example.com" }),
+            json!({ "email": "a@example.com", "logins": 1 }),
+            json!({ "logins": 2 }),
+            false,
+        )
+        .await
+        .unwrap();
+    assert_eq!(doc.document["logins"], 1);
+    let key = doc.header._key;
+
+    let doc: Document<Value> = coll
+        .aql_upsert(
+            json!({ "email": "a@example.com" }),
+            json!({ "email": "a@example.com", "logins": 1 }),
+            json!({ "logins": 2 }),
+            false,
+        )
+        .await
+        .unwrap();
+    assert_eq!(doc.header._key, key);
+    assert_eq!(doc.document["logins"], 2);
+    assert_eq!(doc.document["email"], "a@example.com");
+
+    // a replace drops the attributes missing from the new document
+    let doc: Document<Value> = coll
+        .aql_upsert(
+            json!({ "email": "a@example.com" }),
+            json!({ "email": "a@example.com", "logins": 1 }),
+            json!({ "logins": 3 }),
+            true,
+        )
+        .await
+        .unwrap();
+    assert_eq!(doc.header._key, key);
+    assert_eq!(doc.document["logins"], 3);
+    assert_eq!(doc.document.get("email"), None);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_group_count() {
+    test_setup();
+    let collection_name = "test_collection_group_count";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    for doc in [
+        json!({ "status": "active" }),
+        json!({ "status": "inactive" }),
+        json!({ "status": "active" }),
+        json!({}),
+    ] {
+        coll.create_document(doc, Default::default()).await.unwrap();
+    }
+
+    let counts = coll.group_count("status").await.unwrap();
+    assert_eq!(
+        counts,
+        vec![
+            (Value::Null, 1),
+            (json!("active"), 2),
+            (json!("inactive"), 1),
+        ]
+    );
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_checksum_async() {
+    test_setup();
+    let conn = connection().await;
+    let coll = collection(&conn, "test_collection_checksum_async").await;
+
+    coll.create_document(json!({ "_key": "a", "value": 1 }), Default::default())
+        .await
+        .unwrap();
+
+    let options = || {
+        ChecksumOptions::builder()
+            .with_revision(true)
+            .with_data(true)
+            .build()
+    };
+    let job = coll.checksum_async(options()).await.unwrap();
+    assert_eq!(job.as_str().is_empty(), false);
+
+    let mut result = None;
+    for _ in 0..100 {
+        result = coll.checksum_result(&job).await.unwrap();
+        if result.is_some() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    let result = result.expect("The checksum job should be done");
+    let expected = coll.checksum_with_options(options()).await.unwrap();
+    assert_eq!(result.checksum, expected.checksum);
+    assert_eq!(result.revision, expected.revision);
+
+    // the result of a job is only kept until it is fetched
+    let err = coll.checksum_result(&job).await.unwrap_err();
+    assert_eq!(err.is_not_found(), true);
+
+    coll.drop().await.expect("Should drop the collection");
+}
@@ -1,12 +1,29 @@
 #![allow(unused_imports)]
 #![allow(unused_parens)]
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use http::{Method, StatusCode, Uri};
 use pretty_assertions::assert_eq;
 use uclient::ClientExt;
 
-use arangors::{connection::Permission, Connection};
+use arangors::{
+    collection::options::ChecksumOptions,
+    connection::{
+        observer::Observer,
+        options::{ConnectionOptions, DynamicHeader, DEFAULT_USER_AGENT},
+        Permission,
+    },
+    ClientError, Connection,
+};
 use common::{
-    connection, get_arangodb_host, get_normal_password, get_normal_user, test_root_and_normal,
-    test_setup,
+    collection, connection, get_arangodb_host, get_normal_password, get_normal_user,
+    test_root_and_normal, test_setup,
 };
 
 pub mod common;
@@ -103,3 +120,355 @@ async fn test_jwt() {
     }
     test_root_and_normal(jwt).await;
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_establish_with_options() {
+    test_setup();
+    let host = get_arangodb_host();
+    let user = get_normal_user();
+    let password = get_normal_password();
+
+    let options = ConnectionOptions::builder()
+        .retries(2)
+        .user_agent("my-app/1.0")
+        .build();
+    let conn = Connection::establish_jwt_with_options(&host, &user, &password, options)
+        .await
+        .unwrap();
+    assert_eq!(conn.session().options().retries(), 2);
+    assert_eq!(conn.session().options().user_agent(), "my-app/1.0");
+    let database = conn.db("test_db").await;
+    assert_eq!(database.is_err(), false);
+}
+
+#[derive(Debug, Default)]
+struct CountingObserver {
+    requests: AtomicU64,
+    responses: AtomicU64,
+}
+
+impl Observer for CountingObserver {
+    fn on_request(&self, _method: &Method, _uri: &Uri) {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn on_response(&self, _status: StatusCode, _duration: Duration) {
+        self.responses.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_observer() {
+    test_setup();
+    let host = get_arangodb_host();
+    let user = get_normal_user();
+    let password = get_normal_password();
+
+    let observer = Arc::new(CountingObserver::default());
+    let options = ConnectionOptions::builder()
+        .observer(observer.clone())
+        .build();
+    let conn = Connection::establish_jwt_with_options(&host, &user, &password, options)
+        .await
+        .unwrap();
+    let before = observer.requests.load(Ordering::SeqCst);
+
+    conn.accessible_databases().await.unwrap();
+    assert_eq!(observer.requests.load(Ordering::SeqCst), before + 1);
+    assert_eq!(
+        observer.responses.load(Ordering::SeqCst),
+        observer.requests.load(Ordering::SeqCst)
+    );
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_dynamic_header() {
+    test_setup();
+    let host = get_arangodb_host();
+    let user = get_normal_user();
+    let password = get_normal_password();
+
+    let calls = Arc::new(AtomicU64::new(0));
+    let counter = calls.clone();
+    let options = ConnectionOptions::builder()
+        .dynamic_header(DynamicHeader::new(move || {
+            let call = counter.fetch_add(1, Ordering::SeqCst);
+            let value = format!("00-{:032x}-{:016x}-01", call + 1, call + 1);
+            Some((
+                http::header::HeaderName::from_static("traceparent"),
+                http::HeaderValue::from_str(&value).unwrap(),
+            ))
+        }))
+        .build();
+    let conn = Connection::establish_jwt_with_options(&host, &user, &password, options)
+        .await
+        .unwrap();
+    let before = calls.load(Ordering::SeqCst);
+
+    conn.accessible_databases().await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), before + 1);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_max_response_size() {
+    test_setup();
+    let host = get_arangodb_host();
+    let user = get_normal_user();
+    let password = get_normal_password();
+
+    let options = ConnectionOptions::builder().max_response_size(4096).build();
+    let conn = Connection::establish_jwt_with_options(&host, &user, &password, options)
+        .await
+        .unwrap();
+    let database = conn.db("test_db").await.unwrap();
+
+    let small: Vec<u32> = database.aql_str("FOR i IN 1..10 RETURN i").await.unwrap();
+    assert_eq!(small.len(), 10);
+
+    let large = database.aql_str::<u32>("FOR i IN 1..2000 RETURN i").await;
+    assert_eq!(matches!(large, Err(ClientError::ResponseTooLarge(_))), true);
+}
+
+#[test]
+fn test_default_user_agent() {
+    let options = ConnectionOptions::default();
+    assert_eq!(options.user_agent(), DEFAULT_USER_AGENT);
+    assert_eq!(DEFAULT_USER_AGENT.starts_with("arangors/"), true);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_establish_jwt_multi() {
+    test_setup();
+    let host = get_arangodb_host();
+    let user = get_normal_user();
+    let password = get_normal_password();
+
+    // nothing listens on port 1, so the second endpoint is used
+    let conn = Connection::establish_jwt_multi(&["http://localhost:1", &host], &user, &password)
+        .await
+        .unwrap();
+    let database = conn.db("test_db").await;
+    assert_eq!(database.is_err(), false);
+
+    let conn = Connection::establish_jwt_multi(&["http://localhost:1"], &user, &password).await;
+    assert_eq!(conn.is_err(), true);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_establish_jwt_with_auth_db() {
+    test_setup();
+    let host = get_arangodb_host();
+    let user = get_normal_user();
+    let password = get_normal_password();
+
+    let conn = Connection::establish_jwt_with_auth_db(&host, "test_db", &user, &password)
+        .await
+        .unwrap();
+    let database = conn.db("test_db").await;
+    assert_eq!(database.is_err(), false);
+
+    // the normal user has no access to the system database
+    let database = conn.db("_system").await;
+    assert_eq!(
+        matches!(database, Err(ClientError::InsufficientPermission { .. })),
+        true
+    );
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_establish_with_wrong_scheme() {
+    test_setup();
+    let host = get_arangodb_host().replacen("http://", "https://", 1);
+    let user = get_normal_user();
+    let password = get_normal_password();
+
+    // the test server does not use TLS
+    let conn = Connection::establish_jwt(&host, &user, &password).await;
+    match conn {
+        Err(ClientError::Connection(message)) => {
+            assert_eq!(
+                message.contains("hint: the server answers on http://"),
+                true
+            )
+        }
+        Err(e) => panic!("unexpected error: {:?}", e),
+        Ok(_) => panic!("should not connect with https"),
+    }
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_db_unchecked() {
+    test_setup();
+    let conn = connection().await;
+
+    let database = conn.db_unchecked("test_db");
+    assert_eq!(database.name(), "test_db");
+    let collections = database.accessible_collections().await;
+    assert_eq!(collections.is_err(), false);
+
+    // errors are reported on first use
+    let database = conn.db_unchecked("test_db_non_exist");
+    let collections = database.accessible_collections().await;
+    assert_eq!(collections.is_err(), true);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_admin_execute() {
+    test_setup();
+    let conn = connection().await;
+
+    // the endpoint is only available when the server allows it
+    match conn.admin_execute::<u32>("return 1 + 1;").await {
+        Ok(result) => assert_eq!(result, 2),
+        Err(ClientError::Arango(err)) => {
+            assert_eq!(err.code(), 404);
+            assert_eq!(
+                err.message().contains("--javascript.allow-admin-execute"),
+                true
+            );
+        }
+        Err(err) => panic!("Unexpected error: {:?}", err),
+    }
+}
+
+#[cfg(any(feature = "reqwest_async", feature = "reqwest_blocking"))]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test)
+)]
+async fn test_establish_jwt_with_tls() {
+    use arangors::connection::tls::{TlsConnection, TlsOptions};
+
+    test_setup();
+    let host = get_arangodb_host();
+    let user = get_normal_user();
+    let password = get_normal_password();
+
+    let tls = TlsOptions::builder()
+        .danger_accept_invalid_certs(true)
+        .build();
+    assert_eq!(tls.danger_accept_invalid_certs(), true);
+    assert_eq!(tls.root_certificates().is_empty(), true);
+
+    let conn = TlsConnection::establish_jwt_with_tls(
+        &host,
+        &user,
+        &password,
+        ConnectionOptions::default(),
+        tls,
+    )
+    .await
+    .unwrap();
+    let database = conn.db("test_db").await;
+    assert_eq!(database.is_err(), false);
+}
+
+#[cfg(all(feature = "cluster", feature = "arango3_10"))]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_cluster_rebalance() {
+    use arangors::connection::options::RebalanceOptions;
+
+    test_setup();
+    let conn = common::root_connection().await;
+
+    let status = conn.cluster_rebalance_status().await.unwrap();
+    assert_eq!(status.leader.is_object(), true);
+
+    let plan = conn
+        .cluster_rebalance_plan(
+            RebalanceOptions::builder()
+                .maximum_number_of_moves(10)
+                .exclude_system_collections(true)
+                .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(plan.moves.len() <= 10, true);
+
+    // executing no moves is accepted and does nothing
+    conn.cluster_rebalance_execute(&[]).await.unwrap();
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_close() {
+    test_setup();
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    let collections = database.accessible_collections().await;
+    assert_eq!(collections.is_ok(), true);
+
+    conn.close().await;
+
+    let err = database.accessible_collections().await.unwrap_err();
+    assert!(matches!(err, ClientError::ConnectionClosed(_)), "{:?}", err);
+    assert_eq!(err.is_transport_error(), false);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_close_and_cancel_jobs() {
+    test_setup();
+    let conn = connection().await;
+    let coll = collection(&conn, "test_collection_close_and_cancel_jobs").await;
+    let job = coll
+        .checksum_async(ChecksumOptions::builder().with_data(true).build())
+        .await
+        .unwrap();
+
+    conn.close_and_cancel_jobs().await.unwrap();
+
+    // the job is gone, whether it was pending or done
+    let conn = connection().await;
+    let coll = collection(&conn, "test_collection_close_and_cancel_jobs").await;
+    let err = coll.checksum_result(&job).await.unwrap_err();
+    assert_eq!(err.is_not_found(), true);
+
+    coll.drop().await.expect("Should drop the collection");
+}
@@ -92,3 +92,148 @@ async fn test_get_version() {
         version.version
     );
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_default_options() {
+    use arangors::{database::DefaultOptions, AqlQuery};
+    use serde_json::Value;
+
+    test_setup();
+    let conn = connection()
+        .await
+        .with_default_options(DefaultOptions::builder().batch_size(1).build());
+    let database = conn.db("test_db").await.unwrap();
+    assert_eq!(database.default_options().batch_size(), Some(1));
+
+    let aql = AqlQuery::builder().query("FOR i IN 1..3 RETURN i").build();
+    let cursor = database.aql_query_batch::<Value>(aql).await.unwrap();
+    assert_eq!(cursor.result.len(), 1);
+    assert_eq!(cursor.more, true);
+
+    // an option given to the query takes precedence
+    let aql = AqlQuery::builder()
+        .query("FOR i IN 1..3 RETURN i")
+        .batch_size(3)
+        .build();
+    let cursor = database.aql_query_batch::<Value>(aql).await.unwrap();
+    assert_eq!(cursor.result.len(), 3);
+
+    // so does a default of the database
+    let database = database.with_default_options(DefaultOptions::default());
+    let result: Vec<Value> = database.aql_str("FOR i IN 1..3 RETURN i").await.unwrap();
+    assert_eq!(result.len(), 3);
+    let cursor = database
+        .aql_query_batch::<Value>(AqlQuery::builder().query("FOR i IN 1..3 RETURN i").build())
+        .await
+        .unwrap();
+    assert_eq!(cursor.more, false);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_tasks() {
+    use arangors::task::{Task, TaskType};
+
+    test_setup();
+    let conn = common::root_connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    let id = "test_task_arangors";
+    let _ = database.delete_task(id).await;
+
+    let task = Task::builder()
+        .id(id)
+        .name("noop")
+        .command("return params.value;")
+        .params(serde_json::json!({ "value": 1 }))
+        .period(3600)
+        .offset(3600.0)
+        .build();
+    let created = database.create_task(task.clone()).await.unwrap();
+    assert_eq!(created.id, id);
+    assert_eq!(created.name, "noop");
+    assert_eq!(created.task_type, TaskType::Periodic);
+    assert_eq!(created.database, "test_db");
+    let duplicate = database.create_task(task).await;
+    assert_eq!(duplicate.is_err(), true);
+
+    let fetched = database.task(id).await.unwrap();
+    assert_eq!(fetched.period, Some(3600));
+    let tasks = database.list_tasks().await.unwrap();
+    assert_eq!(tasks.iter().any(|task| task.id == id), true);
+
+    database.delete_task(id).await.unwrap();
+    let deleted = database.task(id).await.unwrap_err();
+    assert_eq!(deleted.is_not_found(), true);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_foxx_services() {
+    use arangors::foxx::{FoxxSource, UpgradeOptions};
+
+    test_setup();
+    let conn = common::root_connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    let mount = "/test-arangors";
+    let _ = database.uninstall_foxx_service(mount).await;
+
+    let script = |message: &str| {
+        FoxxSource::Script(format!(
+            "const router = require('@arangodb/foxx/router')();\n\
+             router.get('/hello', (req, res) => res.send('{}'));\n\
+             module.context.use(router);",
+            message
+        ))
+    };
+    let service = database
+        .install_foxx_service(mount, script("hello"), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(service.mount, mount);
+    let duplicate = database
+        .install_foxx_service(mount, script("hello"), Default::default())
+        .await;
+    assert_eq!(duplicate.is_err(), true);
+
+    let services = database.list_foxx_services().await.unwrap();
+    assert_eq!(services.iter().any(|s| s.mount == mount), true);
+
+    let service = database
+        .upgrade_foxx_service(mount, script("upgraded"), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(service.mount, mount);
+    let service = database
+        .replace_foxx_service(
+            mount,
+            script("replaced"),
+            UpgradeOptions::builder().teardown(true).build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(service.mount, mount);
+
+    // a bundle made of a manifest and a main file, see tests/fixtures
+    let bundle = include_bytes!("fixtures/foxx_service.zip").to_vec();
+    let service = database
+        .replace_foxx_service(mount, FoxxSource::Zip(bundle), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(service.mount, mount);
+    assert_eq!(service.name.as_deref(), Some("test-arangors"));
+    assert_eq!(service.version.as_deref(), Some("1.0.0"));
+
+    database.uninstall_foxx_service(mount).await.unwrap();
+    let services = database.list_foxx_services().await.unwrap();
+    assert_eq!(services.iter().any(|s| s.mount == mount), false);
+}
@@ -17,7 +17,11 @@ use arangors::{
 use common::{
     collection, connection, get_arangodb_host, get_normal_password, get_normal_user, test_setup,
 };
-use std::{convert::TryInto, ptr::null};
+use std::{
+    convert::TryInto,
+    ptr::null,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 pub mod common;
 
@@ -90,9 +94,9 @@ async fn test_post_create_document() {
     assert_eq!(header._key.is_empty(), false);
 
     let key = &header._key;
-    // Third test is to update a simple document with option return old
-    // Should not return  anything according to doc if overWriteMode is not used for
-    // now TODO update this test with overwriteMode later
+    let rev = header._rev.clone();
+    // Third test is to replace a simple document by inserting it with the
+    // overwrite option, asking for the old document back
     let test_doc: Document<Value> = Document::new(json!({ "no":2 ,
     "_key" : key,
     "testDescription":"Test with old"
@@ -110,6 +114,11 @@ async fn test_post_create_document() {
     let result = update.unwrap();
 
     assert_eq!(result.old_doc().is_some(), true);
+    assert_eq!(
+        result.old_rev(),
+        Some(&rev),
+        "We should get the revision of the replaced document"
+    );
 
     let old_doc = result.old_doc().unwrap();
     assert_eq!(
@@ -140,6 +149,184 @@ async fn test_post_create_document() {
     coll.drop().await.expect("Should drop the collection");
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_post_create_document_ref() {
+    test_setup();
+    let collection_name = "test_collection_create_document_ref";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let test_doc = json!({ "no": 1, "testDescription": "Test with a borrowed document" });
+
+    let result: DocumentResponse<Value> = coll
+        .create_document_ref(&test_doc, InsertOptions::builder().return_new(true).build())
+        .await
+        .unwrap();
+    let header = result.header().unwrap();
+    assert_eq!(header._key.is_empty(), false);
+    assert_eq!(
+        result.id(),
+        Some(format!("{}/{}", collection_name, header._key).as_str())
+    );
+    assert_eq!(
+        result.new_doc().unwrap()["testDescription"],
+        "Test with a borrowed document"
+    );
+    // the input is still usable after the insertion
+    assert_eq!(test_doc["no"], 1);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_create_document_strict() {
+    test_setup();
+    let collection_name = "test_collection_create_document_strict";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let read: DocumentResponse<Value> = coll
+        .create_document(
+            json!({ "no": 1 }),
+            InsertOptions::builder().return_new(true).build(),
+        )
+        .await
+        .unwrap();
+    let mut again = read.new_doc().unwrap().clone();
+    again.as_object_mut().unwrap().remove("_key");
+
+    let strict = InsertOptions::builder().strict(true).build();
+    let result: Result<DocumentResponse<Value>, ClientError> =
+        coll.create_document(again.clone(), strict.clone()).await;
+    match result {
+        Err(ClientError::InvalidDocumentBody(message)) => {
+            assert_eq!(message.contains("_id and _rev"), true)
+        }
+        Err(e) => panic!("expected an invalid document body, got {:?}", e),
+        Ok(_) => panic!("expected an invalid document body"),
+    }
+    let count = coll.document_count().await.unwrap();
+    assert_eq!(count.info.count, Some(1));
+
+    // without strict mode, the system attributes are ignored by the server
+    let result: DocumentResponse<Value> = coll
+        .create_document(again.clone(), InsertOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(result.header().is_some(), true);
+
+    let object = again.as_object_mut().unwrap();
+    object.remove("_id");
+    object.remove("_rev");
+    let result: DocumentResponse<Value> = coll.create_document(again, strict).await.unwrap();
+    assert_eq!(result.header().is_some(), true);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_create_document_with_ttl() {
+    test_setup();
+    let collection_name = "test_collection_create_document_with_ttl";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let result = coll
+        .create_document_with_ttl(
+            json!({ "session": "abc" }),
+            Duration::from_secs(3600),
+            "expiresAt",
+            InsertOptions::builder().return_new(true).build(),
+        )
+        .await
+        .unwrap();
+    let new_doc = result.new_doc().unwrap();
+    assert_eq!(new_doc["session"], "abc");
+    let expires_at = new_doc["expiresAt"].as_u64().unwrap();
+    assert_eq!(expires_at >= now + 3600, true);
+    assert_eq!(expires_at <= now + 3600 + 60, true);
+
+    let not_an_object = coll
+        .create_document_with_ttl(
+            json!(["abc"]),
+            Duration::from_secs(3600),
+            "expiresAt",
+            Default::default(),
+        )
+        .await;
+    assert_eq!(
+        matches!(not_an_object, Err(ClientError::InvalidDocumentBody(_))),
+        true
+    );
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_write_only_document() {
+    test_setup();
+    let collection_name = "test_collection_write_only_document";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    // only implements `Serialize`
+    #[derive(serde::Serialize)]
+    struct WriteOnly<'a> {
+        _key: &'a str,
+        description: &'a str,
+    }
+
+    let result: DocumentResponse<Value> = coll
+        .create_document_ref(
+            &WriteOnly {
+                _key: "write_only",
+                description: "created",
+            },
+            InsertOptions::builder().silent(true).build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.is_silent(), true);
+
+    let result: DocumentResponse<Value> = coll
+        .update_document_ref(
+            "write_only",
+            &WriteOnly {
+                _key: "write_only",
+                description: "updated",
+            },
+            UpdateOptions::builder().silent(true).build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.is_silent(), true);
+
+    let doc: Document<Value> = coll.document("write_only").await.unwrap();
+    assert_eq!(doc.document["description"], "updated");
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
 /// TODO need to use CI to validate this test
 #[cfg(any(feature = "arango3_7"))]
 #[maybe_async::test(
@@ -265,7 +452,8 @@ async fn test_post_create_document_3_7() {
             test_doc,
             InsertOptions::builder()
                 .return_new(true)
-                .overwrite_mode(OverwriteMode::Ignore),
+                .overwrite_mode(OverwriteMode::Ignore)
+                .build(),
         )
         .await;
 
@@ -283,7 +471,10 @@ async fn test_post_create_document_3_7() {
     let update = coll
         .create_document(
             test_doc,
-            InsertOptions::builder().overwrite_mode(OverwriteMode::Replace),
+            InsertOptions::builder()
+                .return_new(true)
+                .overwrite_mode(OverwriteMode::Replace)
+                .build(),
         )
         .await;
 
@@ -307,7 +498,10 @@ async fn test_post_create_document_3_7() {
     let update = coll
         .create_document(
             test_doc,
-            InsertOptions::builder().overwrite_mode(OverwriteMode::Update),
+            InsertOptions::builder()
+                .return_new(true)
+                .overwrite_mode(OverwriteMode::Update)
+                .build(),
         )
         .await;
 
@@ -324,6 +518,58 @@ async fn test_post_create_document_3_7() {
     coll.drop().await.expect("Should drop the collection");
 }
 
+#[cfg(any(feature = "arango3_7"))]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_create_document_overwrite_update_options() {
+    test_setup();
+    let conn = connection().await;
+    let coll = collection(&conn, "test_collection_overwrite_update_options").await;
+
+    coll.create_document(
+        json!({ "_key": "a", "name": "a", "nested": { "x": 1, "y": 2 } }),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+
+    // keepNull=false removes the attributes set to null by the insert
+    let result: DocumentResponse<Value> = coll
+        .create_document(
+            json!({ "_key": "a", "name": null }),
+            InsertOptions::builder()
+                .overwrite_mode(OverwriteMode::Update)
+                .keep_null(false)
+                .return_new(true)
+                .build(),
+        )
+        .await
+        .unwrap();
+    let doc = result.new_doc().unwrap();
+    assert_eq!(doc.get("name").is_none(), true);
+    assert_eq!(doc["nested"], json!({ "x": 1, "y": 2 }));
+
+    // mergeObjects=false replaces nested objects instead of merging them
+    let result: DocumentResponse<Value> = coll
+        .create_document(
+            json!({ "_key": "a", "nested": { "x": 3 } }),
+            InsertOptions::builder()
+                .overwrite_mode(OverwriteMode::Update)
+                .merge_objects(false)
+                .return_new(true)
+                .build(),
+        )
+        .await
+        .unwrap();
+    let doc = result.new_doc().unwrap();
+    assert_eq!(doc["nested"], json!({ "x": 3 }));
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -354,12 +600,64 @@ async fn test_get_read_document() {
     assert_eq!(result.document["testDescription"], "read a document");
     // Test if we get the right doc when it does match
     let read: Result<Document<Value>, ClientError> = coll
-        .document_with_options(_key.as_str(), ReadOptions::IfMatch(_rev.clone()))
+        .document_with_options(
+            _key.as_str(),
+            ReadOptions::builder().if_match(_rev.clone()).build(),
+        )
         .await;
     assert_eq!(read.is_err(), false, "got the right document");
+    // Conditions can be combined with a dirty read
+    let options = ReadOptions::builder()
+        .if_match(_rev.clone())
+        .allow_dirty_read(true)
+        .build();
+    let read: Result<Document<Value>, ClientError> =
+        coll.document_with_options(_key.as_str(), options).await;
+    assert_eq!(read.is_err(), false, "got the right document");
+    // A dirty read falling back to the leader finds the document as well
+    let read: Document<Value> = coll.read_document_consistent(_key.as_str()).await.unwrap();
+    assert_eq!(read.document["no"], 1);
+    let read = coll
+        .read_document_consistent::<Value>("no_such_document")
+        .await;
+    assert_eq!(read.unwrap_err().is_not_found(), true);
+    // The raw body is the document as sent by the server
+    let raw = coll
+        .read_document_raw(_key.as_str(), Default::default())
+        .await
+        .unwrap()
+        .unwrap();
+    let read: Value = serde_json::from_slice(&raw).unwrap();
+    assert_eq!(read["no"], 1);
+    assert_eq!(read["_rev"], _rev.as_str());
+    // An unmodified document has no body
+    let raw = coll
+        .read_document_raw(
+            _key.as_str(),
+            ReadOptions::builder().if_none_match(_rev.clone()).build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(raw, None);
+    let raw = coll
+        .read_document_raw("no_such_document", Default::default())
+        .await;
+    assert_eq!(raw.unwrap_err().is_not_found(), true);
+    let raw = coll
+        .read_document_raw(
+            _key.as_str(),
+            ReadOptions::builder().if_match("_dsdsds_d").build(),
+        )
+        .await;
+    assert_eq!(raw.unwrap_err().is_precondition_failed(), true);
     // Test if we get the 412 code response when there is no match
     let read: Result<Document<Value>, ClientError> = coll
-        .document_with_options(_key.as_str(), ReadOptions::IfMatch("_dsdsds_d".to_string()))
+        .document_with_options(
+            _key.as_str(),
+            ReadOptions::builder()
+                .if_match("_dsdsds_d".to_string())
+                .build(),
+        )
         .await;
     // We should get a 412, for now for some reason the error is parsed as a
     // document todo fix how the reponse/error is built
@@ -367,8 +665,9 @@ async fn test_get_read_document() {
         read.is_err(),
         true,
         "we should get 412, got: {:?}",
-        read.unwrap().document
+        read.as_ref().map(|doc| &doc.document)
     );
+    assert_eq!(read.unwrap_err().is_precondition_failed(), true);
 
     // todo need to test with with IfNoneMatch and 304
 
@@ -417,7 +716,10 @@ async fn test_get_read_document_header() {
     );
 
     let read = coll
-        .document_header_with_options(_key.as_str(), ReadOptions::IfMatch(_rev.clone()))
+        .document_header_with_options(
+            _key.as_str(),
+            ReadOptions::builder().if_match(_rev.clone()).build(),
+        )
         .await;
 
     assert_eq!(read.is_ok(), true, "We should have the right header");
@@ -431,7 +733,12 @@ async fn test_get_read_document_header() {
     );
 
     let read = coll
-        .document_header_with_options(_key.as_str(), ReadOptions::IfMatch("_dsdsds".to_string()))
+        .document_header_with_options(
+            _key.as_str(),
+            ReadOptions::builder()
+                .if_match("_dsdsds".to_string())
+                .build(),
+        )
         .await;
 
     assert_eq!(
@@ -440,7 +747,10 @@ async fn test_get_read_document_header() {
         "We should have an error and the right doc returned"
     );
     let read = coll
-        .document_header_with_options(_key.as_str(), ReadOptions::IfNoneMatch(_rev.clone()))
+        .document_header_with_options(
+            _key.as_str(),
+            ReadOptions::builder().if_none_match(_rev.clone()).build(),
+        )
         .await;
 
     assert_eq!(
@@ -452,6 +762,127 @@ async fn test_get_read_document_header() {
     coll.drop().await.expect("Should drop the collection");
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_document_changed() {
+    test_setup();
+    let collection_name = "test_collection_document_changed";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let create = coll
+        .create_document(json!({ "_key": "doc", "no": 1 }), Default::default())
+        .await
+        .unwrap();
+    let rev = create.header().unwrap()._rev.clone();
+
+    let changed = coll.document_changed("doc", &rev).await.unwrap();
+    assert_eq!(changed, false);
+
+    let update = coll
+        .update_document("doc", json!({ "no": 2 }), Default::default())
+        .await
+        .unwrap();
+    let new_rev = update.header().unwrap()._rev.clone();
+
+    let changed = coll.document_changed("doc", &rev).await.unwrap();
+    assert_eq!(changed, true);
+    let current = coll
+        .document_revision_if_changed("doc", &rev)
+        .await
+        .unwrap();
+    assert_eq!(current, Some(new_rev));
+
+    let missing = coll.document_changed("missing", &rev).await;
+    assert_eq!(missing.unwrap_err().is_not_found(), true);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_document_revision() {
+    test_setup();
+    let collection_name = "test_collection_document_revision";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let create = coll
+        .create_document(json!({ "_key": "doc", "no": 1 }), Default::default())
+        .await
+        .unwrap();
+    let rev = create.header().unwrap()._rev.clone();
+
+    let revision = coll.document_revision("doc").await.unwrap();
+    assert_eq!(revision, rev);
+    let revision = coll.try_document_revision("doc").await.unwrap();
+    assert_eq!(revision, Some(rev));
+
+    let missing = coll.document_revision("missing").await.unwrap_err();
+    assert_eq!(missing.is_not_found(), true);
+    let revision = coll.try_document_revision("missing").await.unwrap();
+    assert_eq!(revision, None);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_create_document_schema_validation() {
+    use arangors::collection::options::CreateOptions;
+
+    test_setup();
+    let collection_name = "test_collection_schema_validation";
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    let _ = database.drop_collection(collection_name).await;
+    let schema = json!({
+        "rule": {
+            "type": "object",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"]
+        },
+        "level": "moderate",
+        "message": "name is required"
+    });
+    let coll = database
+        .create_collection_with_options(
+            CreateOptions::builder()
+                .name(collection_name)
+                .schema(schema)
+                .build(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+    let result = coll
+        .create_document(json!({ "no": 1 }), Default::default())
+        .await;
+    match result {
+        Err(ClientError::SchemaValidation {
+            message,
+            collection,
+            ..
+        }) => {
+            assert_eq!(message.contains("name is required"), true, "{}", message);
+            assert_eq!(collection, Some(collection_name.to_string()));
+        }
+        result => panic!("unexpected result: {:?}", result.map(|r| r.is_silent())),
+    }
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -759,6 +1190,76 @@ async fn test_delete_remove_document() {
     // todo do more test
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_document_with_special_key() {
+    test_setup();
+    let collection_name = "test_collection_document_special_key";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let key = "my-key:with.special@chars(1)";
+    coll.create_document(json!({ "_key": key, "no": 1 }), Default::default())
+        .await
+        .unwrap();
+
+    let doc: Document<Value> = coll.document(key).await.unwrap();
+    assert_eq!(doc.header._key, key);
+
+    let header = coll.document_header(key).await.unwrap();
+    assert_eq!(header._key, key);
+
+    coll.update_document(key, json!({ "no": 2 }), Default::default())
+        .await
+        .unwrap();
+    coll.replace_document(key, json!({ "no": 3 }), Default::default(), None)
+        .await
+        .unwrap();
+    let doc: Document<Value> = coll.document(key).await.unwrap();
+    assert_eq!(doc.document["no"], 3);
+
+    let removed: DocumentResponse<Value> = coll
+        .remove_document(key, Default::default(), None)
+        .await
+        .unwrap();
+    assert_eq!(removed.header().unwrap()._key, key);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_document_to_body() {
+    test_setup();
+    let collection_name = "test_collection_document_to_body";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let header = coll
+        .create_document(json!({ "no": 1 }), Default::default())
+        .await
+        .unwrap()
+        .header()
+        .unwrap()
+        ._key
+        .clone();
+    let doc: Document<Value> = coll.document(&header).await.unwrap();
+    assert_eq!(doc.document.get("_rev").is_some(), true);
+
+    let body = doc.to_body(true).unwrap();
+    assert_eq!(body, json!({ "_key": header, "no": 1 }));
+    let body = doc.to_body(false).unwrap();
+    assert_eq!(body, json!({ "no": 1 }));
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -816,3 +1317,249 @@ async fn test_document_deserialization() {
     assert_eq!(result.rev, header._rev);
     assert_eq!(result.id, header._id);
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_read_documents() {
+    test_setup();
+    let collection_name = "test_collection_read_documents";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    for (key, no) in [("a", 1), ("b", 2), ("c", 3)] {
+        coll.create_document(json!({ "_key": key, "no": no }), Default::default())
+            .await
+            .unwrap();
+    }
+
+    let keys = ["c", "missing", "a", "b", "a"];
+    let docs: Vec<Option<Document<Value>>> = coll.read_documents(&keys).await.unwrap();
+    assert_eq!(docs.len(), keys.len());
+    for (key, doc) in keys.iter().zip(docs.iter()) {
+        match doc {
+            Some(doc) => assert_eq!(doc.header._key, *key),
+            None => assert_eq!(*key, "missing"),
+        }
+    }
+    assert_eq!(docs[0].as_ref().unwrap().document["no"], 3);
+    assert_eq!(docs[1].is_none(), true);
+    assert_eq!(docs[4].as_ref().unwrap().document["no"], 1);
+
+    let docs: Vec<Option<Document<Value>>> = coll.read_documents(&[]).await.unwrap();
+    assert_eq!(docs.is_empty(), true);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_document_with_headers() {
+    test_setup();
+    let collection_name = "test_collection_document_with_headers";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let created = coll
+        .create_document_with_headers::<_, Value>(
+            &json!({ "_key": "with_headers", "no": 1 }),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    let rev = created.header().unwrap()._rev.clone();
+    assert_eq!(created.etag(), Some(rev.as_str()));
+    assert_eq!(
+        created
+            .get_header("location")
+            .unwrap()
+            .ends_with("/with_headers"),
+        true
+    );
+
+    let doc = coll
+        .document_with_headers::<Value>("with_headers", Default::default())
+        .await
+        .unwrap();
+    assert_eq!(doc.etag(), Some(rev.as_str()));
+    assert_eq!(doc.value.document["no"], 1);
+    assert_eq!(doc.into_inner().header._rev, rev);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_insert() {
+    test_setup();
+    let collection_name = "test_collection_insert";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let header = coll
+        .insert(&json!({ "_key": "inserted", "no": 1 }), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(header._key, "inserted");
+    assert_eq!(header._id, format!("{}/inserted", collection_name));
+    assert_eq!(header._rev.is_empty(), false);
+
+    let header = coll
+        .insert(
+            &json!({ "_key": "silent:key@1", "no": 2 }),
+            InsertOptions::builder().silent(true).build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(header._key, "silent:key@1");
+    assert_eq!(header._id, format!("{}/silent:key@1", collection_name));
+
+    let doc: Document<Value> = coll.document("silent:key@1").await.unwrap();
+    assert_eq!(doc.header._rev, header._rev);
+
+    let key = coll
+        .insert_get_key(
+            &json!({ "no": 3 }),
+            InsertOptions::builder().silent(true).build(),
+        )
+        .await
+        .unwrap();
+    let doc: Document<Value> = coll.document(&key).await.unwrap();
+    assert_eq!(doc.document["no"], 3);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_create_documents() {
+    test_setup();
+    let collection_name = "test_collection_create_documents";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let docs = vec![
+        json!({ "_key": "a", "no": 1 }),
+        json!({ "_key": "b", "no": 2 }),
+        json!({ "_key": "a", "no": 3 }),
+    ];
+    let results = coll
+        .create_documents::<_, Value>(&docs, InsertOptions::builder().return_new(true).build())
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap().new_doc().unwrap()["no"], 1);
+    assert_eq!(
+        results[1].as_ref().unwrap().id(),
+        Some("test_collection_create_documents/b")
+    );
+    assert_eq!(results[2].as_ref().err().unwrap().is_conflict(), true);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_replace_documents() {
+    test_setup();
+    let collection_name = "test_collection_replace_documents";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let docs = vec![
+        json!({ "_key": "a", "no": 1 }),
+        json!({ "_key": "b", "no": 2 }),
+    ];
+    let created = coll
+        .create_documents::<_, Value>(&docs, Default::default())
+        .await
+        .unwrap();
+    let rev_b = created[1].as_ref().unwrap().header().unwrap()._rev.clone();
+
+    let docs = vec![
+        json!({ "_key": "a", "no": 10 }),
+        json!({ "_key": "b", "_rev": rev_b, "no": 20 }),
+        json!({ "_key": "missing", "no": 30 }),
+    ];
+    let results = coll
+        .replace_documents::<_, Value>(
+            &docs,
+            ReplaceOptions::builder()
+                .ignore_revs(false)
+                .return_old(true)
+                .return_new(true)
+                .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 3);
+    let replaced = results[0].as_ref().unwrap();
+    assert_eq!(replaced.old_doc().unwrap()["no"], 1);
+    assert_eq!(replaced.new_doc().unwrap()["no"], 10);
+    assert_eq!(results[1].as_ref().unwrap().new_doc().unwrap()["no"], 20);
+    assert_eq!(results[2].as_ref().err().unwrap().is_not_found(), true);
+
+    // a stale revision is rejected
+    let results = coll
+        .replace_documents::<_, Value>(
+            &[json!({ "_key": "b", "_rev": rev_b, "no": 200 })],
+            ReplaceOptions::builder().ignore_revs(false).build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        results[0].as_ref().err().unwrap().is_precondition_failed(),
+        true
+    );
+
+    let missing_key = coll
+        .replace_documents::<_, Value>(&[json!({ "no": 1 })], Default::default())
+        .await;
+    assert_eq!(
+        matches!(missing_key, Err(ClientError::InvalidDocumentBody(_))),
+        true
+    );
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[cfg(not(feature = "blocking"))]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_insert_stream() {
+    use futures_util::stream::{self, StreamExt};
+
+    test_setup();
+    let collection_name = "test_collection_insert_stream";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let docs = stream::iter((0..25).map(|no| json!({ "no": no })));
+    let results: Vec<_> = coll
+        .insert_stream::<_, _, Value>(docs, 10, Default::default())
+        .collect()
+        .await;
+    assert_eq!(results.len(), 25);
+    assert_eq!(results.iter().all(|result| result.is_ok()), true);
+    let count = coll.document_count().await.unwrap();
+    assert_eq!(count.info.count, Some(25));
+
+    coll.drop().await.expect("Should drop the collection");
+}
@@ -12,7 +12,7 @@ use arangors::{
         response::Status,
         CollectionType,
     },
-    index::{Index, IndexSettings},
+    index::{Index, IndexSettings, IndexType},
     ClientError, Connection, Document,
 };
 use common::{get_arangodb_host, get_normal_password, get_normal_user, test_setup};
@@ -65,6 +65,59 @@ async fn test_persistent_index() {
     }
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_array_index() {
+    test_setup();
+    let collection_name = "test_collection";
+    let index_name = "idx_array_test";
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+
+    let index = Index::builder()
+        .name(index_name)
+        .fields(vec!["tags[*]".to_string()])
+        .settings(IndexSettings::Persistent {
+            unique: false,
+            sparse: true,
+            deduplicate: true,
+        })
+        .build();
+
+    let index = database
+        .create_index(collection_name, &index)
+        .await
+        .unwrap();
+
+    let list = database.indexes(collection_name).await.unwrap();
+    let listed = list
+        .indexes
+        .iter()
+        .find(|i| i.id == index.id)
+        .expect("the array index should be listed");
+
+    assert_eq!(listed.fields, vec!["tags[*]".to_string()]);
+    if let IndexSettings::Persistent {
+        unique,
+        sparse,
+        deduplicate,
+    } = listed.settings
+    {
+        assert_eq!(unique, false);
+        assert_eq!(sparse, true);
+        assert_eq!(deduplicate, true);
+    } else {
+        panic!("unexpected index settings: {:?}", listed.settings);
+    }
+
+    let delete_result = database.delete_index(&index.id).await.unwrap();
+    assert_eq!(delete_result.id, index.id);
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -277,3 +330,50 @@ async fn test_list_indexes() {
 
     assert!(list.indexes.len() > 0);
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_indexes_of_type() {
+    test_setup();
+    let collection_name = "test_collection";
+    let index_name = "idx_of_type_test";
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+    let coll = database.collection(collection_name).await.unwrap();
+
+    let index = Index::builder()
+        .name(index_name)
+        .fields(vec!["username".to_string()])
+        .settings(IndexSettings::Persistent {
+            unique: false,
+            sparse: false,
+            deduplicate: false,
+        })
+        .build();
+    let index = database
+        .create_index(collection_name, &index)
+        .await
+        .unwrap();
+    assert_eq!(index.index_type(), IndexType::Persistent);
+
+    let persistent = coll.indexes_of_type(IndexType::Persistent).await.unwrap();
+    assert_eq!(persistent.iter().any(|i| i.id == index.id), true);
+    assert_eq!(
+        persistent
+            .iter()
+            .all(|i| i.index_type() == IndexType::Persistent),
+        true
+    );
+
+    let primary = coll.indexes_of_type(IndexType::Primary).await.unwrap();
+    assert_eq!(primary.len(), 1);
+
+    let fulltext = coll.indexes_of_type(IndexType::Fulltext).await.unwrap();
+    assert_eq!(fulltext.iter().any(|i| i.id == index.id), false);
+
+    database.delete_index(&index.id).await.unwrap();
+}
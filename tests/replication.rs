@@ -0,0 +1,142 @@
+#![allow(unused_imports)]
+#![allow(unused_parens)]
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+use crate::common::{collection, root_connection};
+use arangors::replication::{DumpEntry, DumpOptions, WalEntry, WalOperation, WalTailOptions};
+use common::test_setup;
+
+pub mod common;
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_inventory_and_tail_wal() {
+    test_setup();
+    let collection_name = "test_collection_replication_tail";
+    let conn = root_connection().await;
+    let coll = collection(&conn, collection_name).await;
+    let database = conn.db("test_db").await.unwrap();
+
+    let inventory = database.replication_inventory(false).await.unwrap();
+    assert!(inventory
+        .collections
+        .iter()
+        .any(|c| c.parameters.get("name") == Some(&json!(collection_name))));
+
+    coll.create_document(json!({ "a": 1 }), Default::default())
+        .await
+        .unwrap();
+
+    let batch = database
+        .tail_wal_batch(&inventory.tick, WalTailOptions::default())
+        .await
+        .unwrap();
+    assert!(batch
+        .entries
+        .iter()
+        .any(|e| e.operation == WalOperation::InsertOrReplaceDocument));
+    assert!(batch.last_included.is_some());
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_tail_wal_resumes() {
+    test_setup();
+    let collection_name = "test_collection_replication_tail_resume";
+    let conn = root_connection().await;
+    let coll = collection(&conn, collection_name).await;
+    let database = conn.db("test_db").await.unwrap();
+
+    let inventory = database.replication_inventory(false).await.unwrap();
+    for i in 0..3 {
+        coll.create_document(json!({ "i": i }), Default::default())
+            .await
+            .unwrap();
+    }
+
+    let options = WalTailOptions::builder()
+        .chunk_size(1)
+        .poll_interval(std::time::Duration::from_millis(10))
+        .build();
+    let is_insert = |entry: &WalEntry| entry.operation == WalOperation::InsertOrReplaceDocument;
+    #[cfg(not(feature = "blocking"))]
+    let inserts: Vec<WalEntry> = {
+        use futures_util::{StreamExt, TryStreamExt};
+        database
+            .tail_wal(&inventory.tick, options)
+            .try_filter(|entry| futures_util::future::ready(is_insert(entry)))
+            .take(3)
+            .try_collect()
+            .await
+            .unwrap()
+    };
+    #[cfg(feature = "blocking")]
+    let inserts: Vec<WalEntry> = database
+        .tail_wal(&inventory.tick, options)
+        .filter(|entry| entry.as_ref().map_or(true, is_insert))
+        .take(3)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(inserts.len(), 3);
+    let mut ticks: Vec<u64> = inserts.iter().map(|e| e.tick.parse().unwrap()).collect();
+    ticks.dedup();
+    assert_eq!(ticks.len(), 3);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_replication_dump() {
+    test_setup();
+    let collection_name = "test_collection_replication_dump";
+    let conn = root_connection().await;
+    let coll = collection(&conn, collection_name).await;
+    let database = conn.db("test_db").await.unwrap();
+
+    for i in 0..3 {
+        coll.create_document(json!({ "i": i }), Default::default())
+            .await
+            .unwrap();
+    }
+
+    let batch = database.create_replication_batch(60).await.unwrap();
+    let dump = coll
+        .replication_dump_batch(DumpOptions::builder().batch_id(batch.id.as_str()).build())
+        .await
+        .unwrap();
+    assert_eq!(dump.entries.len(), 3);
+
+    // tiny chunks, so that the dump is fetched in several requests
+    let options = DumpOptions::builder()
+        .batch_id(batch.id.as_str())
+        .chunk_size(1)
+        .build();
+    #[cfg(not(feature = "blocking"))]
+    let entries: Vec<DumpEntry> = {
+        use futures_util::TryStreamExt;
+        coll.replication_dump(options).try_collect().await.unwrap()
+    };
+    #[cfg(feature = "blocking")]
+    let entries: Vec<DumpEntry> = coll
+        .replication_dump(options)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(entries.len(), 3);
+    database.delete_replication_batch(&batch.id).await.unwrap();
+
+    coll.drop().await.expect("Should drop the collection");
+}
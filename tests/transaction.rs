@@ -17,7 +17,7 @@ use arangors::{
     transaction::{
         Status as TransactionStatus, Transaction, TransactionCollections, TransactionSettings,
     },
-    ClientError, Connection, Database, Document,
+    AqlOptions, AqlQuery, ClientError, Connection, Database, Document,
 };
 use common::{get_arangodb_host, get_normal_password, get_normal_user, test_setup};
 
@@ -80,6 +80,36 @@ async fn test_start_transaction() {
     assert_eq!(status, TransactionStatus::Aborted);
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_transaction_size_and_lock_settings() {
+    test_setup();
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+
+    let settings = TransactionSettings::builder()
+        .collections(
+            TransactionCollections::builder()
+                .write(vec!["test_collection".to_string()])
+                .build(),
+        )
+        .allow_implicit(false)
+        .lock_timeout(30)
+        .max_transaction_size(128 * 1024 * 1024)
+        .build();
+    let body = serde_json::to_value(&settings).unwrap();
+    assert_eq!(body["allowImplicit"], false);
+    assert_eq!(body["lockTimeout"], 30);
+    assert_eq!(body["maxTransactionSize"], 128 * 1024 * 1024);
+
+    let tx = database.begin_transaction(settings).await.unwrap();
+    let status = tx.abort().await.unwrap();
+    assert_eq!(status, TransactionStatus::Aborted);
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -110,7 +140,7 @@ async fn test_abort_transaction() {
     let collection = database.collection("test_collection").await.unwrap();
     let doc = collection.document::<Value>(&key).await;
 
-    assert_eq!(doc.is_err(), true);
+    assert_eq!(doc.unwrap_err().is_not_found(), true);
 }
 
 #[maybe_async::test(
@@ -155,3 +185,125 @@ async fn test_commit_transaction() {
 
     assert_eq!(old_doc.is_ok(), true);
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_execute_transaction() {
+    test_setup();
+    let collection_name = "test_collection_js_transaction";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+    let database = conn.db("test_db").await.unwrap();
+
+    let action = r#"function (params) {
+        const db = require('@arangodb').db;
+        const collection = db._collection(params.collection);
+        params.names.forEach(name => collection.insert({ name }));
+        return collection.count();
+    }"#;
+    let count: u64 = database
+        .execute_transaction(
+            TransactionCollections::from_collections(&[], &[&coll]),
+            action,
+            &json!({ "collection": coll.name(), "names": ["alice", "bob"] }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+
+    let count: u64 = database
+        .execute_transaction(
+            TransactionCollections::from_collections(&[&coll], &[]),
+            "function () { return require('@arangodb').db._collection('test_collection_js_transaction').count(); }",
+            &(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_exclusive_transaction() {
+    test_setup();
+    let collection_name = "test_collection_exclusive_transaction";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+    let database = conn.db("test_db").await.unwrap();
+    let settings = |lock_timeout| {
+        TransactionSettings::builder()
+            .lock_timeout(lock_timeout)
+            .collections(
+                TransactionCollections::builder()
+                    .build()
+                    .with_exclusive(&[&coll]),
+            )
+            .build()
+    };
+
+    let first = database.begin_transaction(settings(60)).await.unwrap();
+    first
+        .collection(collection_name)
+        .await
+        .unwrap()
+        .create_document(json!({ "_key": "first" }), Default::default())
+        .await
+        .unwrap();
+
+    // the exclusive lock is held until the first transaction ends
+    let second = database.begin_transaction(settings(1)).await;
+    assert_eq!(second.is_err(), true);
+
+    let status = first.commit().await.unwrap();
+    assert_eq!(status, TransactionStatus::Committed);
+
+    let second = database.begin_transaction(settings(60)).await.unwrap();
+    let doc: Document<Value> = second
+        .collection(collection_name)
+        .await
+        .unwrap()
+        .document("first")
+        .await
+        .unwrap();
+    assert_eq!(doc.header._key, "first");
+    let status = second.abort().await.unwrap();
+    assert_eq!(status, TransactionStatus::Aborted);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_transaction_aql_fail_on_warning() {
+    test_setup();
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    let tx = create_transaction(&database, "test_collection".to_string())
+        .await
+        .unwrap();
+
+    let query = AqlQuery::builder()
+        .query("RETURN 1 / @zero")
+        .bind_var("zero", 0)
+        .options(AqlOptions::builder().fail_on_warning(true).build())
+        .build();
+    let err = tx.aql_query::<Option<f64>>(query).await.unwrap_err();
+    match err {
+        ClientError::QueryWarning { code, .. } => assert_eq!(code, 1562),
+        err => panic!("unexpected error: {:?}", err),
+    }
+
+    let status = tx.abort().await.unwrap();
+    assert_eq!(status, TransactionStatus::Aborted);
+}